@@ -0,0 +1,373 @@
+use cosmwasm_std::{
+    entry_point, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, WasmMsg,
+};
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
+use terraswap::asset::{Asset, AssetInfo};
+
+use white_whale::flashloan_router::msg::*;
+use white_whale::memory::LIST_SIZE_LIMIT;
+use white_whale::ust_vault::msg::{ExecuteMsg as VaultMsg, FlashLoanPayload, VaultQueryMsg};
+use white_whale::vault::fees::{EffectiveFeeResponse, LoanFee};
+
+use crate::error::FlashLoanRouterError;
+use crate::state::{OngoingLoan, Vault, ADMIN, LOAN, VAULTS, WHITELIST};
+
+type RouterResult = Result<Response, FlashLoanRouterError>;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:flashloan-router";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> RouterResult {
+    // Use CW2 to set the contract version, this is needed for migrations
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Setup the admin as the creator of the contract
+    ADMIN.set(deps.branch(), Some(info.sender))?;
+    WHITELIST.save(deps.storage, &vec![])?;
+    add_vaults(deps, msg.vaults)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> RouterResult {
+    match msg {
+        ExecuteMsg::FlashLoan { payload } => handle_flashloan(deps, env, info, payload),
+        ExecuteMsg::VaultCallback {} => handle_vault_callback(deps, env, info),
+        ExecuteMsg::UpdateVaults { to_add, to_remove } => {
+            update_vaults(deps, info, to_add, to_remove)
+        }
+        ExecuteMsg::AddToWhitelist { contract_addr } => add_to_whitelist(deps, info, contract_addr),
+        ExecuteMsg::RemoveFromWhitelist { contract_addr } => {
+            remove_from_whitelist(deps, info, contract_addr)
+        }
+        ExecuteMsg::SetAdmin { admin } => {
+            let admin_addr = deps.api.addr_validate(&admin)?;
+            let previous_admin = ADMIN.get(deps.as_ref())?.unwrap();
+            ADMIN.execute_update_admin(deps, info, Some(admin_addr))?;
+            Ok(Response::default()
+                .add_attribute("previous admin", previous_admin)
+                .add_attribute("admin", admin))
+        }
+        ExecuteMsg::Callback(msg) => _handle_callback(deps, env, info, msg),
+    }
+}
+
+//----------------------------------------------------------------------------------------
+//  CONTRACT UPGRADEABILITY
+//----------------------------------------------------------------------------------------
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> RouterResult {
+    let version: Version = CONTRACT_VERSION.parse()?;
+    let storage_version: Version = get_contract_version(deps.storage)?.version.parse()?;
+
+    if storage_version < version {
+        set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        // If state structure changed in any contract version in the way migration is needed, it
+        // should occur here
+    }
+    Ok(Response::default())
+}
+
+//----------------------------------------------------------------------------------------
+//  PRIVATE FUNCTIONS
+//----------------------------------------------------------------------------------------
+
+fn _handle_callback(deps: DepsMut, env: Env, info: MessageInfo, msg: CallbackMsg) -> RouterResult {
+    // Callback functions can only be called this contract itself
+    if info.sender != env.contract.address {
+        return Err(FlashLoanRouterError::NotCallback {});
+    }
+    match msg {
+        CallbackMsg::RepayLoans {} => repay_loans(deps, env),
+    }
+}
+
+//----------------------------------------------------------------------------------------
+//  EXECUTE FUNCTION HANDLERS
+//----------------------------------------------------------------------------------------
+
+/// Takes a flashloan from the vault of each requested asset, one after the other. Each vault
+/// calls the router back once it lent its asset, see [`handle_vault_callback`].
+pub fn handle_flashloan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payload: MultiFlashLoanPayload,
+) -> RouterResult {
+    // The loans of one flashloan are taken in nested calls, no other flashloan can start meanwhile
+    if LOAN.may_load(deps.storage)?.is_some() {
+        return Err(FlashLoanRouterError::LoanOngoing {});
+    }
+    // The vaults only see the router, so it enforces its own whitelist of borrowers
+    if !WHITELIST.load(deps.storage)?.contains(&info.sender) {
+        return Err(FlashLoanRouterError::NotWhitelisted {});
+    }
+    if payload.requested_assets.is_empty() {
+        return Err(FlashLoanRouterError::NoAssetsRequested {});
+    }
+
+    let mut vaults = vec![];
+    for (i, asset) in payload.requested_assets.iter().enumerate() {
+        // A vault can't lend twice in the same flashloan
+        if payload.requested_assets[..i]
+            .iter()
+            .any(|requested| requested.info == asset.info)
+        {
+            return Err(FlashLoanRouterError::DuplicatedAsset(
+                asset.info.to_string(),
+            ));
+        }
+        let vault = VAULTS
+            .may_load(deps.storage, &asset.info.to_string())?
+            .ok_or_else(|| FlashLoanRouterError::VaultNotFound(asset.info.to_string()))?;
+        // Profit share refunds are sent to the router once it already repaid the vaults, it
+        // can't pass them on to the borrower
+        let fee: EffectiveFeeResponse = deps.querier.query_wasm_smart(
+            vault.address.to_string(),
+            &VaultQueryMsg::EffectiveFee {
+                borrower: env.contract.address.to_string(),
+                amount: asset.amount,
+            },
+        )?;
+        if let LoanFee::ProfitShare { .. } = fee.fee {
+            return Err(FlashLoanRouterError::ProfitShareNotSupported(
+                asset.info.to_string(),
+            ));
+        }
+        vaults.push(vault.address);
+    }
+
+    let loan = OngoingLoan {
+        borrower: info.sender,
+        requested_assets: payload.requested_assets,
+        vaults,
+        callback: payload.callback,
+        borrowed: 0u32,
+    };
+    LOAN.save(deps.storage, &loan)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "flashloan")
+        .add_attribute("borrower", loan.borrower.to_string())
+        .add_message(borrow_next_msg(&loan)?))
+}
+
+/// Called by each vault once it lent its asset. Borrows from the next vault, or once all the
+/// assets are borrowed, lends them to the borrower and calls it. The loans are repaid in a
+/// final callback, after which each vault checks its own repayment and profit.
+pub fn handle_vault_callback(deps: DepsMut, env: Env, info: MessageInfo) -> RouterResult {
+    let mut loan = LOAN
+        .may_load(deps.storage)?
+        .ok_or(FlashLoanRouterError::NoLoanOngoing {})?;
+
+    // Only the vault currently lending can call back
+    let lending_vault = loan.vaults.get(loan.borrowed as usize);
+    if lending_vault != Some(&info.sender) {
+        return Err(FlashLoanRouterError::Unauthorized {});
+    }
+    loan.borrowed += 1;
+    LOAN.save(deps.storage, &loan)?;
+
+    if (loan.borrowed as usize) < loan.vaults.len() {
+        return Ok(Response::new().add_message(borrow_next_msg(&loan)?));
+    }
+
+    // Lend all the borrowed assets to the borrower, the transfer tax is deducted from the amount
+    let mut response = Response::new();
+    for asset in loan.requested_assets.into_iter() {
+        response = response.add_message(asset.into_msg(&deps.querier, loan.borrower.clone())?);
+    }
+
+    Ok(response
+        // Call the borrower once with all the assets
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: loan.borrower.to_string(),
+            msg: loan.callback,
+            funds: vec![],
+        }))
+        // Repay the vaults with what the borrower returned
+        .add_message(CallbackMsg::RepayLoans {}.to_cosmos_msg(&env.contract.address)?))
+}
+
+pub fn update_vaults(
+    deps: DepsMut,
+    info: MessageInfo,
+    to_add: Option<Vec<VaultInfo>>,
+    to_remove: Option<Vec<AssetInfo>>,
+) -> RouterResult {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    if let Some(vaults_to_remove) = to_remove {
+        for asset_info in vaults_to_remove.into_iter() {
+            VAULTS.remove(deps.storage, &asset_info.to_string());
+        }
+    }
+    if let Some(vaults_to_add) = to_add {
+        add_vaults(deps, vaults_to_add)?;
+    }
+
+    Ok(Response::new().add_attribute("action", "update vaults"))
+}
+
+pub fn add_to_whitelist(deps: DepsMut, info: MessageInfo, contract_addr: String) -> RouterResult {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let contract = deps.api.addr_validate(&contract_addr)?;
+    let mut whitelist = WHITELIST.load(deps.storage)?;
+    if whitelist.contains(&contract) {
+        return Err(FlashLoanRouterError::AlreadyWhitelisted {});
+    }
+    // This is a limit to prevent potentially running out of gas when doing lookups on the whitelist
+    if whitelist.len() >= LIST_SIZE_LIMIT {
+        return Err(FlashLoanRouterError::WhitelistLimitReached {});
+    }
+    whitelist.push(contract);
+    WHITELIST.save(deps.storage, &whitelist)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add to whitelist")
+        .add_attribute("contract", contract_addr))
+}
+
+pub fn remove_from_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract_addr: String,
+) -> RouterResult {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
+
+    let contract = deps.api.addr_validate(&contract_addr)?;
+    let mut whitelist = WHITELIST.load(deps.storage)?;
+    if !whitelist.contains(&contract) {
+        return Err(FlashLoanRouterError::NotWhitelisted {});
+    }
+    whitelist.retain(|addr| *addr != contract);
+    WHITELIST.save(deps.storage, &whitelist)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "remove from whitelist")
+        .add_attribute("contract", contract_addr))
+}
+
+//----------------------------------------------------------------------------------------
+//  CALLBACK FUNCTION HANDLERS
+//----------------------------------------------------------------------------------------
+
+/// Sends everything the borrower returned back to the vaults. Each vault checks the repaid
+/// amount covers its loan, fee and profit in its own after trade callback.
+fn repay_loans(deps: DepsMut, env: Env) -> RouterResult {
+    let loan = LOAN.load(deps.storage)?;
+    LOAN.remove(deps.storage);
+
+    let mut response = Response::new().add_attribute("action", "repay loans");
+    for (asset, vault) in loan.requested_assets.into_iter().zip(loan.vaults) {
+        let balance =
+            asset
+                .info
+                .query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+        if balance < asset.amount {
+            return Err(FlashLoanRouterError::LoanNotRepaid(asset.info.to_string()));
+        }
+
+        response = response.add_attribute("repaid", format!("{}{}", balance, asset.info));
+        let repay_asset = Asset {
+            info: asset.info,
+            amount: balance,
+        };
+        response = response.add_message(repay_asset.into_msg(&deps.querier, vault)?);
+    }
+
+    Ok(response)
+}
+
+//----------------------------------------------------------------------------------------
+//  HELPER FUNCTION HANDLERS
+//----------------------------------------------------------------------------------------
+
+/// Flashloan msg to the next vault to borrow from, calling the router back with the loan
+fn borrow_next_msg(loan: &OngoingLoan) -> StdResult<CosmosMsg> {
+    let index = loan.borrowed as usize;
+    // The luna vault takes the same flashloan msg as the stablecoin vault
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: loan.vaults[index].to_string(),
+        msg: to_binary(&VaultMsg::FlashLoan {
+            payload: FlashLoanPayload {
+                requested_asset: loan.requested_assets[index].clone(),
+                callback: to_binary(&ExecuteMsg::VaultCallback {})?,
+            },
+        })?,
+        funds: vec![],
+    }))
+}
+
+fn add_vaults(deps: DepsMut, vaults: Vec<VaultInfo>) -> Result<(), FlashLoanRouterError> {
+    let vault_count = VAULTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count();
+    if vault_count + vaults.len() > LIST_SIZE_LIMIT {
+        return Err(FlashLoanRouterError::VaultLimitReached {});
+    }
+
+    for vault in vaults.into_iter() {
+        VAULTS.save(
+            deps.storage,
+            &vault.asset_info.to_string(),
+            &Vault {
+                asset_info: vault.asset_info,
+                address: deps.api.addr_validate(&vault.vault_address)?,
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+//----------------------------------------------------------------------------------------
+//  QUERY HANDLERS
+//----------------------------------------------------------------------------------------
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Vaults {} => to_binary(&query_vaults(deps)?),
+        QueryMsg::Whitelist {} => to_binary(&query_whitelist(deps)?),
+    }
+}
+
+pub fn query_vaults(deps: Deps) -> StdResult<VaultsResponse> {
+    let vaults = VAULTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (_, vault) = item?;
+            Ok(VaultInfo {
+                asset_info: vault.asset_info,
+                vault_address: vault.address.to_string(),
+            })
+        })
+        .collect::<StdResult<Vec<VaultInfo>>>()?;
+    Ok(VaultsResponse { vaults })
+}
+
+pub fn query_whitelist(deps: Deps) -> StdResult<WhitelistResponse> {
+    let contracts = WHITELIST
+        .load(deps.storage)?
+        .into_iter()
+        .map(|addr| addr.into_string())
+        .collect();
+    Ok(WhitelistResponse { contracts })
+}
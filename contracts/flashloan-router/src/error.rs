@@ -0,0 +1,63 @@
+use thiserror::Error;
+
+use cosmwasm_std::StdError;
+use cw_controllers::AdminError;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum FlashLoanRouterError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Call is not a callback!")]
+    NotCallback {},
+
+    #[error("No assets were requested.")]
+    NoAssetsRequested {},
+
+    #[error("The asset {0} was requested more than once.")]
+    DuplicatedAsset(String),
+
+    #[error("There is no vault registered for {0}.")]
+    VaultNotFound(String),
+
+    #[error(
+        "The {0} vault charges the router a profit share, which can't be refunded to the borrower."
+    )]
+    ProfitShareNotSupported(String),
+
+    #[error("A flashloan is already ongoing.")]
+    LoanOngoing {},
+
+    #[error("There is no ongoing flashloan.")]
+    NoLoanOngoing {},
+
+    #[error("The borrowed {0} was not returned.")]
+    LoanNotRepaid(String),
+
+    #[error("The vault list has reached its limit, can't store more vaults.")]
+    VaultLimitReached {},
+
+    #[error("The borrower is not whitelisted.")]
+    NotWhitelisted {},
+
+    #[error("The contract is already whitelisted.")]
+    AlreadyWhitelisted {},
+
+    #[error("The whitelist has reached its limit, can't store more contracts.")]
+    WhitelistLimitReached {},
+}
+
+impl From<semver::Error> for FlashLoanRouterError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
+}
@@ -0,0 +1,7 @@
+pub mod contract;
+pub mod error;
+pub mod state;
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod tests;
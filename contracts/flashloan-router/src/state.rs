@@ -0,0 +1,34 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Binary};
+use cw_controllers::Admin;
+use cw_storage_plus::{Item, Map};
+use terraswap::asset::{Asset, AssetInfo};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vault {
+    pub asset_info: AssetInfo,
+    pub address: Addr,
+}
+
+/// Flashloan being taken through the router. The assets are borrowed one vault at a time, the
+/// borrower is called once all of them are in the router.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OngoingLoan {
+    pub borrower: Addr,
+    pub requested_assets: Vec<Asset>,
+    /// vault each of the requested assets is borrowed from
+    pub vaults: Vec<Addr>,
+    pub callback: Binary,
+    /// number of vaults that already sent their loan
+    pub borrowed: u32,
+}
+
+pub const ADMIN: Admin = Admin::new("admin");
+/// vaults by the string representation of their base asset
+pub const VAULTS: Map<&str, Vault> = Map::new("vaults");
+pub const LOAN: Item<OngoingLoan> = Item::new("loan");
+/// borrowers allowed to borrow through the router. The vaults see the router as the borrower,
+/// so their own whitelist and fees don't apply to the router's borrowers
+pub const WHITELIST: Item<Vec<Addr>> = Item::new("whitelist");
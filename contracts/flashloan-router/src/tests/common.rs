@@ -0,0 +1,4 @@
+pub(crate) const TEST_CREATOR: &str = "creator";
+pub(crate) const BORROWER: &str = "borrower";
+pub(crate) const UST_VAULT: &str = "ust_vault";
+pub(crate) const LUNA_VAULT: &str = "luna_vault";
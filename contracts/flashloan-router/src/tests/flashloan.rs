@@ -0,0 +1,295 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coin, coins, to_binary, BankMsg, Binary, CosmosMsg, Decimal, DepsMut, SubMsg, Uint128, WasmMsg,
+};
+use terraswap::asset::Asset;
+
+use white_whale::fee::Fee;
+
+use white_whale::flashloan_router::msg::*;
+use white_whale::ust_vault::msg::{ExecuteMsg as VaultMsg, FlashLoanPayload};
+use white_whale::vault::fees::LoanFee;
+
+use crate::contract::execute;
+use crate::error::FlashLoanRouterError;
+use crate::state::LOAN;
+use crate::tests::common::{BORROWER, LUNA_VAULT, UST_VAULT};
+use crate::tests::instantiate::{mock_instantiate, native};
+use crate::tests::mock_querier::mock_dependencies;
+
+fn asset(denom: &str, amount: u128) -> Asset {
+    Asset {
+        info: native(denom),
+        amount: Uint128::new(amount),
+    }
+}
+
+fn callback() -> Binary {
+    Binary::from(br#"{"arb":{}}"#)
+}
+
+fn flashloan(
+    deps: DepsMut,
+    requested_assets: Vec<Asset>,
+) -> Result<Vec<SubMsg>, FlashLoanRouterError> {
+    let msg = ExecuteMsg::FlashLoan {
+        payload: MultiFlashLoanPayload {
+            requested_assets,
+            callback: callback(),
+        },
+    };
+    let res = execute(deps, mock_env(), mock_info(BORROWER, &[]), msg)?;
+    Ok(res.messages)
+}
+
+fn vault_flashloan_msg(vault: &str, requested_asset: Asset) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: vault.to_string(),
+        msg: to_binary(&VaultMsg::FlashLoan {
+            payload: FlashLoanPayload {
+                requested_asset,
+                callback: to_binary(&ExecuteMsg::VaultCallback {}).unwrap(),
+            },
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn successful_multi_asset_flashloan() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // borrows from the first vault
+    let messages = flashloan(deps.as_mut(), vec![asset("uusd", 1000), asset("uluna", 10)]).unwrap();
+    assert_eq!(
+        messages,
+        vec![vault_flashloan_msg(UST_VAULT, asset("uusd", 1000))]
+    );
+
+    // the first vault lent, borrows from the second vault
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(UST_VAULT, &[]),
+        ExecuteMsg::VaultCallback {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![vault_flashloan_msg(LUNA_VAULT, asset("uluna", 10))]
+    );
+
+    // all the assets are borrowed, the borrower is called once with all of them
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(LUNA_VAULT, &[]),
+        ExecuteMsg::VaultCallback {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: BORROWER.to_string(),
+                amount: coins(1000, "uusd"),
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: BORROWER.to_string(),
+                amount: coins(10, "uluna"),
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: BORROWER.to_string(),
+                msg: callback(),
+                funds: vec![],
+            }),
+            SubMsg::new(
+                CallbackMsg::RepayLoans {}
+                    .to_cosmos_msg(&mock_env().contract.address)
+                    .unwrap()
+            ),
+        ]
+    );
+
+    // the borrower returned the assets with some profit, everything goes back to the vaults
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        vec![coin(1100, "uusd"), coin(10, "uluna")],
+    );
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::RepayLoans {}),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: UST_VAULT.to_string(),
+                amount: coins(1100, "uusd"),
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: LUNA_VAULT.to_string(),
+                amount: coins(10, "uluna"),
+            }),
+        ]
+    );
+    assert!(LOAN.may_load(&deps.storage).unwrap().is_none());
+}
+
+#[test]
+fn unsuccessful_flashloan_invalid_assets() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    match flashloan(deps.as_mut(), vec![]) {
+        Err(FlashLoanRouterError::NoAssetsRequested {}) => (),
+        _ => panic!("Must return FlashLoanRouterError::NoAssetsRequested"),
+    }
+    match flashloan(deps.as_mut(), vec![asset("uusd", 10), asset("uusd", 20)]) {
+        Err(FlashLoanRouterError::DuplicatedAsset(_)) => (),
+        _ => panic!("Must return FlashLoanRouterError::DuplicatedAsset"),
+    }
+    match flashloan(deps.as_mut(), vec![asset("ukrw", 10)]) {
+        Err(FlashLoanRouterError::VaultNotFound(_)) => (),
+        _ => panic!("Must return FlashLoanRouterError::VaultNotFound"),
+    }
+}
+
+#[test]
+fn unsuccessful_flashloan_not_whitelisted() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // the vaults only see the router, which would lend to anyone without its own whitelist
+    let msg = ExecuteMsg::FlashLoan {
+        payload: MultiFlashLoanPayload {
+            requested_assets: vec![asset("uusd", 1000)],
+            callback: callback(),
+        },
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("arb_bot", &[]), msg);
+    match res {
+        Err(FlashLoanRouterError::NotWhitelisted {}) => (),
+        _ => panic!("Must return FlashLoanRouterError::NotWhitelisted"),
+    }
+    assert_eq!(LOAN.may_load(&deps.storage).unwrap(), None);
+}
+
+#[test]
+fn unsuccessful_flashloan_profit_share() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // the vault would refund the borrower's share of the profit to the router
+    deps.querier.with_vault_fee(
+        LUNA_VAULT,
+        LoanFee::ProfitShare {
+            share: Fee {
+                share: Decimal::percent(10),
+            },
+        },
+    );
+    match flashloan(deps.as_mut(), vec![asset("uusd", 1000), asset("uluna", 10)]) {
+        Err(FlashLoanRouterError::ProfitShareNotSupported(_)) => (),
+        _ => panic!("Must return FlashLoanRouterError::ProfitShareNotSupported"),
+    }
+    assert_eq!(LOAN.may_load(&deps.storage).unwrap(), None);
+
+    // a flat fee is repaid by the borrower along with the loan
+    deps.querier.with_vault_fee(
+        LUNA_VAULT,
+        LoanFee::Flat {
+            fee: Fee {
+                share: Decimal::percent(1),
+            },
+        },
+    );
+    flashloan(deps.as_mut(), vec![asset("uusd", 1000), asset("uluna", 10)]).unwrap();
+}
+
+#[test]
+fn unsuccessful_flashloan_loan_ongoing() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    flashloan(deps.as_mut(), vec![asset("uusd", 1000)]).unwrap();
+    match flashloan(deps.as_mut(), vec![asset("uluna", 10)]) {
+        Err(FlashLoanRouterError::LoanOngoing {}) => (),
+        _ => panic!("Must return FlashLoanRouterError::LoanOngoing"),
+    }
+}
+
+#[test]
+fn unsuccessful_vault_callback() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(UST_VAULT, &[]),
+        ExecuteMsg::VaultCallback {},
+    );
+    match res {
+        Err(FlashLoanRouterError::NoLoanOngoing {}) => (),
+        _ => panic!("Must return FlashLoanRouterError::NoLoanOngoing"),
+    }
+
+    // only the vault currently lending can call back
+    flashloan(deps.as_mut(), vec![asset("uusd", 1000), asset("uluna", 10)]).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(LUNA_VAULT, &[]),
+        ExecuteMsg::VaultCallback {},
+    );
+    match res {
+        Err(FlashLoanRouterError::Unauthorized {}) => (),
+        _ => panic!("Must return FlashLoanRouterError::Unauthorized"),
+    }
+}
+
+#[test]
+fn unsuccessful_repay_loans() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    flashloan(deps.as_mut(), vec![asset("uusd", 1000)]).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(UST_VAULT, &[]),
+        ExecuteMsg::VaultCallback {},
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(BORROWER, &[]),
+        ExecuteMsg::Callback(CallbackMsg::RepayLoans {}),
+    );
+    match res {
+        Err(FlashLoanRouterError::NotCallback {}) => (),
+        _ => panic!("Must return FlashLoanRouterError::NotCallback"),
+    }
+
+    // the borrower didn't return the full loan
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![coin(999, "uusd")]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::RepayLoans {}),
+    );
+    match res {
+        Err(FlashLoanRouterError::LoanNotRepaid(_)) => (),
+        _ => panic!("Must return FlashLoanRouterError::LoanNotRepaid"),
+    }
+}
@@ -0,0 +1,174 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, DepsMut};
+use terraswap::asset::AssetInfo;
+
+use white_whale::flashloan_router::msg::*;
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::FlashLoanRouterError;
+use crate::tests::common::{BORROWER, LUNA_VAULT, TEST_CREATOR, UST_VAULT};
+use crate::tests::mock_querier::mock_dependencies;
+
+pub(crate) fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
+}
+
+pub(crate) fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        vaults: vec![
+            VaultInfo {
+                asset_info: native("uusd"),
+                vault_address: UST_VAULT.to_string(),
+            },
+            VaultInfo {
+                asset_info: native("uluna"),
+                vault_address: LUNA_VAULT.to_string(),
+            },
+        ],
+    }
+}
+
+/**
+ * Mocks instantiation, whitelisting the borrower.
+ */
+pub fn mock_instantiate(mut deps: DepsMut) {
+    let info = mock_info(TEST_CREATOR, &[]);
+    let _res = instantiate(deps.branch(), mock_env(), info.clone(), instantiate_msg())
+        .expect("contract successfully handles InstantiateMsg");
+
+    let msg = ExecuteMsg::AddToWhitelist {
+        contract_addr: BORROWER.to_string(),
+    };
+    execute(deps, mock_env(), info, msg).unwrap();
+}
+
+/**
+ * Tests successful instantiation of the contract.
+ */
+#[test]
+fn successful_initialization() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Vaults {}).unwrap();
+    let vaults: VaultsResponse = from_binary(&res).unwrap();
+    // the vaults are sorted by asset
+    assert_eq!(
+        vaults.vaults,
+        vec![
+            VaultInfo {
+                asset_info: native("uluna"),
+                vault_address: LUNA_VAULT.to_string(),
+            },
+            VaultInfo {
+                asset_info: native("uusd"),
+                vault_address: UST_VAULT.to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn successful_update_vaults() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::UpdateVaults {
+        to_add: Some(vec![VaultInfo {
+            asset_info: native("uusd"),
+            vault_address: "new_ust_vault".to_string(),
+        }]),
+        to_remove: Some(vec![native("uluna")]),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Vaults {}).unwrap();
+    let vaults: VaultsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        vaults.vaults,
+        vec![VaultInfo {
+            asset_info: native("uusd"),
+            vault_address: "new_ust_vault".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn unsuccessful_update_vaults_unauthorized() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::UpdateVaults {
+        to_add: None,
+        to_remove: Some(vec![native("uluna")]),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg,
+    );
+    match res {
+        Err(FlashLoanRouterError::Admin(_)) => (),
+        _ => panic!("Must return FlashLoanRouterError::Admin"),
+    }
+}
+
+#[test]
+fn successful_update_whitelist() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::AddToWhitelist {
+        contract_addr: "arb_bot".to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+    let msg = ExecuteMsg::RemoveFromWhitelist {
+        contract_addr: BORROWER.to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Whitelist {}).unwrap();
+    let whitelist: WhitelistResponse = from_binary(&res).unwrap();
+    assert_eq!(whitelist.contracts, vec!["arb_bot".to_string()]);
+}
+
+#[test]
+fn unsuccessful_update_whitelist() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::AddToWhitelist {
+        contract_addr: "arb_bot".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg,
+    );
+    match res {
+        Err(FlashLoanRouterError::Admin(_)) => (),
+        _ => panic!("Must return FlashLoanRouterError::Admin"),
+    }
+
+    let msg = ExecuteMsg::AddToWhitelist {
+        contract_addr: BORROWER.to_string(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(FlashLoanRouterError::AlreadyWhitelisted {}) => (),
+        _ => panic!("Must return FlashLoanRouterError::AlreadyWhitelisted"),
+    }
+
+    let msg = ExecuteMsg::RemoveFromWhitelist {
+        contract_addr: "arb_bot".to_string(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(FlashLoanRouterError::NotWhitelisted {}) => (),
+        _ => panic!("Must return FlashLoanRouterError::NotWhitelisted"),
+    }
+}
@@ -0,0 +1,4 @@
+mod common;
+mod flashloan;
+mod instantiate;
+mod mock_querier;
@@ -15,6 +15,7 @@ use stablecoin_vault::contract::{execute, instantiate, query, reply};
 use stablecoin_vault::pool_info::PoolInfo;
 use terraswap::asset::AssetInfo;
 use white_whale::ust_vault::msg::InstantiateMsg as VaultInstantiateMsg;
+use white_whale::ust_vault::msg::{YieldAdapterInfo, YieldAdapterKind};
 
 use cw20::{Cw20Coin, Cw20Contract, Cw20ExecuteMsg};
 use terra_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
@@ -22,8 +23,15 @@ use terra_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
 // Custom Vault Instant msg func which takes code ID
 pub fn instantiate_msg(token_code_id: u64) -> VaultInstantiateMsg {
     VaultInstantiateMsg {
-        anchor_money_market_address: "test_mm".to_string(),
-        aust_address: "test_aust".to_string(),
+        yield_adapters: vec![YieldAdapterInfo {
+            adapter: YieldAdapterKind::Anchor {
+                money_market_address: "test_mm".to_string(),
+                aust_address: "test_aust".to_string(),
+            },
+            weight: Decimal::one(),
+            redeem_priority: 0u32,
+            deposit_threshold: Decimal::percent(150),
+        }],
         treasury_addr: "treasury".to_string(),
         asset_info: AssetInfo::NativeToken {
             denom: "uusd".to_string(),
@@ -452,8 +460,6 @@ fn gov_can_update_vault_config_through_polls() {
         .unwrap();
 
     let stable_cap_change_msg = to_binary(&white_whale::ust_vault::msg::ExecuteMsg::UpdateState {
-        anchor_money_market_address: Some("market_addr".to_string()),
-        aust_address: Some("aust".to_string()),
         allow_non_whitelisted: Some(false),
     })
     .unwrap();
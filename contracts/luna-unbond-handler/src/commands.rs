@@ -1,6 +1,6 @@
 use cosmwasm_std::{
-    coins, from_binary, to_binary, BankMsg, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
-    Response, Uint128, WasmMsg,
+    coins, from_binary, to_binary, Addr, BankMsg, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Response, Uint128, WasmMsg,
 };
 use cw20::Cw20ReceiveMsg;
 use terraswap::asset::{Asset, AssetInfo};
@@ -9,14 +9,18 @@ use terraswap::querier::query_balance;
 use white_whale::anchor::{anchor_bluna_unbond_msg, anchor_withdraw_unbonded_msg};
 use white_whale::denom::LUNA_DENOM;
 use white_whale::luna_vault::luna_unbond_handler::msg::{CallbackMsg, Cw20HookMsg};
-use white_whale::luna_vault::msg::UnbondHandlerMsg;
+use white_whale::luna_vault::msg::{UnbondHandlerMsg, UnbondHub};
 use white_whale::luna_vault::queries::query_luna_vault_fees;
 use white_whale::memory::error::MemoryError;
 use white_whale::memory::queries::{
     query_asset_from_mem, query_contract_from_mem, query_contracts_from_mem,
 };
-use white_whale::memory::{ANCHOR_BLUNA_HUB_ID, BLUNA_TOKEN_MEMORY_ID, TREASURY_ADDRESS_ID};
-use white_whale::query::anchor::query_unbond_requests;
+use white_whale::memory::{
+    ANCHOR_BLUNA_HUB_ID, BLUNA_TOKEN_MEMORY_ID, CLUNA_TOKEN_MEMORY_ID, PRISM_CLUNA_HUB_ID,
+    TREASURY_ADDRESS_ID,
+};
+use white_whale::prism::{prism_cluna_unbond_msg, prism_withdraw_unbonded_msg};
+use white_whale::query::{anchor, prism};
 
 use crate::serde_option::serde_option;
 use crate::state::{ADMIN, STATE};
@@ -34,32 +38,51 @@ pub fn receive_cw20(
         Cw20HookMsg::Unbond {} => {
             let state = STATE.load(deps.storage)?;
 
-            // only bluna token contract can execute this message
-            let bluna_asset_info =
-                query_asset_from_mem(deps.as_ref(), &state.memory_contract, BLUNA_TOKEN_MEMORY_ID)?;
-            match bluna_asset_info {
-                AssetInfo::NativeToken { .. } => {
-                    return Err(UnbondHandlerError::UnsupportedToken {});
-                }
-                AssetInfo::Token { contract_addr } => {
-                    if deps.api.addr_validate(&msg_info.sender.to_string())? != contract_addr {
-                        return Err(UnbondHandlerError::UnsupportedToken {});
-                    }
-                }
-            };
-
-            unbond_bluna(deps, cw20_msg.amount)
+            // only bluna and cluna token contracts can execute this message
+            if is_token_from_mem(
+                deps.as_ref(),
+                &state.memory_contract,
+                BLUNA_TOKEN_MEMORY_ID,
+                &msg_info.sender,
+            )? {
+                unbond(deps, cw20_msg.amount, UnbondHub::Anchor)
+            } else if is_token_from_mem(
+                deps.as_ref(),
+                &state.memory_contract,
+                CLUNA_TOKEN_MEMORY_ID,
+                &msg_info.sender,
+            )? {
+                unbond(deps, cw20_msg.amount, UnbondHub::Prism)
+            } else {
+                Err(UnbondHandlerError::UnsupportedToken {})
+            }
+        }
+    }
+}
+
+/// Checks if the given sender is the cw20 token stored in memory with the given asset id
+fn is_token_from_mem(
+    deps: Deps,
+    memory_contract: &Addr,
+    asset_id: &str,
+    sender: &Addr,
+) -> Result<bool, UnbondHandlerError> {
+    match query_asset_from_mem(deps, memory_contract, asset_id)? {
+        AssetInfo::NativeToken { .. } => Ok(false),
+        AssetInfo::Token { contract_addr } => {
+            Ok(deps.api.addr_validate(&sender.to_string())? == contract_addr)
         }
     }
 }
 
-/// Triggers the unbonding process with the received bluna on Anchor
-fn unbond_bluna(deps: DepsMut, amount: Uint128) -> UnbondHandlerResult {
+/// Triggers the unbonding process with the received bluna on Anchor or cluna on Prism
+fn unbond(deps: DepsMut, amount: Uint128, hub: UnbondHub) -> UnbondHandlerResult {
     let state = STATE.load(deps.storage)?;
-    let contracts = [
-        BLUNA_TOKEN_MEMORY_ID.to_string(),
-        ANCHOR_BLUNA_HUB_ID.to_string(),
-    ];
+    let (token_id, hub_id, action) = match hub {
+        UnbondHub::Anchor => (BLUNA_TOKEN_MEMORY_ID, ANCHOR_BLUNA_HUB_ID, "unbond_bluna"),
+        UnbondHub::Prism => (CLUNA_TOKEN_MEMORY_ID, PRISM_CLUNA_HUB_ID, "unbond_cluna"),
+    };
+    let contracts = [token_id.to_string(), hub_id.to_string()];
     let contract_addresses =
         query_contracts_from_mem(deps.as_ref(), &state.memory_contract, &contracts)?;
 
@@ -70,31 +93,31 @@ fn unbond_bluna(deps: DepsMut, amount: Uint128) -> UnbondHandlerResult {
         ));
     }
 
-    let bluna_address = contract_addresses
-        .get(BLUNA_TOKEN_MEMORY_ID)
+    let token_address = contract_addresses
+        .get(token_id)
         .ok_or(UnbondHandlerError::MemoryError(
             MemoryError::NotFoundInMemory {},
         ))?
         .clone();
-    let bluna_hub_address = contract_addresses
-        .get(ANCHOR_BLUNA_HUB_ID)
+    let hub_address = contract_addresses
+        .get(hub_id)
         .ok_or(UnbondHandlerError::MemoryError(
             MemoryError::NotFoundInMemory {},
         ))?
         .clone();
 
-    // create message for unbonding bluna on anchor
-    let bluna_unbond_msg = anchor_bluna_unbond_msg(bluna_address, bluna_hub_address, amount)?;
+    // create message for unbonding bluna on anchor or cluna on prism
+    let unbond_msg = match hub {
+        UnbondHub::Anchor => anchor_bluna_unbond_msg(token_address, hub_address, amount)?,
+        UnbondHub::Prism => prism_cluna_unbond_msg(token_address, hub_address, amount)?,
+    };
     Ok(Response::new()
-        .add_attributes(vec![
-            ("action", "unbond_bluna"),
-            ("amount", &amount.to_string()),
-        ])
-        .add_message(bluna_unbond_msg))
+        .add_attributes(vec![("action", action), ("amount", &amount.to_string())])
+        .add_message(unbond_msg))
 }
 
-/// Withdraws the unbonded bluna from Anchor
-pub fn withdraw_unbonded_bluna(
+/// Withdraws the unbonded luna from Anchor and Prism
+pub fn withdraw_unbonded(
     deps: DepsMut,
     env: Env,
     triggered_by_addr: String,
@@ -103,19 +126,56 @@ pub fn withdraw_unbonded_bluna(
     let triggered_by = deps.api.addr_validate(&triggered_by_addr)?;
     let bluna_hub_address =
         query_contract_from_mem(deps.as_ref(), &state.memory_contract, ANCHOR_BLUNA_HUB_ID)?;
+    let cluna_hub_address =
+        query_contract_from_mem(deps.as_ref(), &state.memory_contract, PRISM_CLUNA_HUB_ID)?;
+
+    // create the messages for withdrawing unbonded luna from the hubs that have any
+    let mut withdraw_unbonded_msgs = vec![];
+    if !anchor::query_withdrawable_unbonded(
+        deps.as_ref(),
+        bluna_hub_address.clone(),
+        env.contract.address.clone(),
+    )?
+    .withdrawable
+    .is_zero()
+    {
+        withdraw_unbonded_msgs.push(anchor_withdraw_unbonded_msg(bluna_hub_address)?);
+    }
+    if !prism::query_withdrawable_unbonded(
+        deps.as_ref(),
+        cluna_hub_address.clone(),
+        env.contract.address.clone(),
+    )?
+    .withdrawable
+    .is_zero()
+    {
+        withdraw_unbonded_msgs.push(prism_withdraw_unbonded_msg(cluna_hub_address)?);
+    }
 
-    // create the message for withdrawing unbonded bluna from anchor
-    let withdraw_unbonded_msg = anchor_withdraw_unbonded_msg(bluna_hub_address)?;
+    // with nothing to withdraw from the hubs, the luna already held is still sent back and the
+    // handler released, so an expired handler can always be liquidated
+    if withdraw_unbonded_msgs.is_empty()
+        && query_balance(
+            &deps.querier,
+            env.contract.address.clone(),
+            LUNA_DENOM.to_string(),
+        )?
+        .is_zero()
+        && has_pending_unbond_requests(deps.as_ref(), &env, &state.memory_contract)?
+    {
+        return Err(UnbondHandlerError::NothingToWithdraw {});
+    }
 
-    // Callback for after withdrawing the unbonded bluna
+    // Callback for after withdrawing the unbonded luna
     let after_withdraw_msg = CallbackMsg::AfterWithdraw {
         triggered_by_addr: triggered_by.to_string(),
     }
     .to_cosmos_msg(&env.contract.address)?;
 
     Ok(Response::new()
-        .add_attributes(vec![("action", "withdraw_unbonded_bluna")])
-        .add_messages(vec![withdraw_unbonded_msg, after_withdraw_msg]))
+        .add_attributes(vec![("action", "withdraw_unbonded")])
+        .add_messages(withdraw_unbonded_msgs)
+        .add_message(after_withdraw_msg))
 }
 
 /// Sets a new admin
@@ -182,7 +242,7 @@ pub(crate) fn handle_callback(
     }
 }
 
-/// Sends luna to its owner after it is withdrawn from Anchor, along with any liquidation fee
+/// Sends luna to its owner after it is withdrawn from Anchor and Prism, along with any liquidation fee
 fn after_withdraw(deps: DepsMut, env: Env, triggered_by_addr: String) -> UnbondHandlerResult {
     let state = STATE.load(deps.storage)?;
     let triggered_by = deps.api.addr_validate(&triggered_by_addr)?;
@@ -193,7 +253,7 @@ fn after_withdraw(deps: DepsMut, env: Env, triggered_by_addr: String) -> UnbondH
 
     let mut response = Response::new().add_attribute("action", "after_withdraw");
 
-    // get amount of luna obtained from Anchor
+    // get amount of luna obtained from Anchor and Prism
     let refund_amount = query_balance(
         &deps.querier,
         env.contract.address.clone(),
@@ -216,20 +276,22 @@ fn after_withdraw(deps: DepsMut, env: Env, triggered_by_addr: String) -> UnbondH
             false => Uint128::zero(),
         };
 
-    // Construct refund message
-    let refund_msg = Asset {
-        info: AssetInfo::NativeToken {
-            denom: LUNA_DENOM.to_string(),
-        },
-        amount: refund_amount.checked_sub(liquidation_fee_amount)?,
-    }
-    .into_msg(&deps.querier, owner.clone())?;
+    // Construct refund message, if the handler holds any luna
     response = response.add_attribute(
         "refund_amount",
         refund_amount.checked_sub(liquidation_fee_amount)?,
     );
     response = response.add_attribute("liquidation_fee", liquidation_fee_amount);
-    response = response.add_message(refund_msg);
+    if !refund_amount.is_zero() {
+        let refund_msg = Asset {
+            info: AssetInfo::NativeToken {
+                denom: LUNA_DENOM.to_string(),
+            },
+            amount: refund_amount.checked_sub(liquidation_fee_amount)?,
+        }
+        .into_msg(&deps.querier, owner.clone())?;
+        response = response.add_message(refund_msg);
+    }
 
     // Construct liquidation reward message if withdrawal wasn't triggered by the owner
     if !liquidation_fee_amount.is_zero() {
@@ -256,19 +318,8 @@ fn after_withdraw(deps: DepsMut, env: Env, triggered_by_addr: String) -> UnbondH
         response = response.add_messages(vec![reward_msg, treasury_fee_msg]);
     }
 
-    // check if there are no more pending unbonds on Anchor for this handler, so that it can be reused
-    let bluna_hub_address =
-        query_contract_from_mem(deps.as_ref(), &state.memory_contract, ANCHOR_BLUNA_HUB_ID)?;
-
     // check if there are pending unbond requests for this handler. If not, clear the unbond handler to make it reusable
-    if query_unbond_requests(
-        deps.as_ref(),
-        bluna_hub_address,
-        env.contract.address.clone(),
-    )?
-    .requests
-    .is_empty()
-    {
+    if !has_pending_unbond_requests(deps.as_ref(), &env, &state.memory_contract)? {
         // clean state so that the handler can be reused
         let mut state = state;
         state.owner = None;
@@ -292,3 +343,26 @@ fn after_withdraw(deps: DepsMut, env: Env, triggered_by_addr: String) -> UnbondH
 
     Ok(response)
 }
+
+/// Checks if the handler still has unbond requests pending on Anchor or Prism
+fn has_pending_unbond_requests(
+    deps: Deps,
+    env: &Env,
+    memory_contract: &Addr,
+) -> Result<bool, UnbondHandlerError> {
+    let bluna_hub_address = query_contract_from_mem(deps, memory_contract, ANCHOR_BLUNA_HUB_ID)?;
+    let cluna_hub_address = query_contract_from_mem(deps, memory_contract, PRISM_CLUNA_HUB_ID)?;
+
+    Ok(
+        !anchor::query_unbond_requests(deps, bluna_hub_address, env.contract.address.clone())?
+            .requests
+            .is_empty()
+            || !prism::query_unbond_requests(
+                deps,
+                cluna_hub_address,
+                env.contract.address.clone(),
+            )?
+            .requests
+            .is_empty(),
+    )
+}
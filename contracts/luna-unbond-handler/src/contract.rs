@@ -63,7 +63,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> U
     match msg {
         ExecuteMsg::Receive(msg) => commands::receive_cw20(deps, env, info, msg),
         ExecuteMsg::WithdrawUnbonded { triggered_by_addr } => {
-            commands::withdraw_unbonded_bluna(deps, env, triggered_by_addr)
+            commands::withdraw_unbonded(deps, env, triggered_by_addr)
         }
         ExecuteMsg::SetAdmin { admin } => commands::set_admin(deps, info, admin),
         ExecuteMsg::UpdateState {
@@ -32,6 +32,9 @@ pub enum UnbondHandlerError {
     #[error("Contract is not owned by any address")]
     UnownedHandler {},
 
+    #[error("There is no unbonded luna to withdraw from Anchor or Prism")]
+    NothingToWithdraw {},
+
     #[error("Expiration time was impossible to calculate")]
     WrongExpirationTime {},
 
@@ -1,15 +1,17 @@
 use crate::state::{State, STATE};
 use cosmwasm_std::{Deps, Env, StdResult};
+use white_whale::luna_vault::luna_unbond_handler::msg::UnbondRequestsResponse;
 use white_whale::memory::queries::query_contract_from_mem;
-use white_whale::memory::ANCHOR_BLUNA_HUB_ID;
-use white_whale::query::anchor::{UnbondRequestsResponse, WithdrawableUnbondedResponse};
+use white_whale::memory::{ANCHOR_BLUNA_HUB_ID, PRISM_CLUNA_HUB_ID};
+use white_whale::query::anchor::WithdrawableUnbondedResponse;
+use white_whale::query::{anchor, prism};
 
 /// Gets the state of the contract
 pub(crate) fn query_state(deps: Deps) -> StdResult<State> {
     STATE.load(deps.storage)
 }
 
-/// Gets the state of the contract
+/// Gets the luna withdrawable from Anchor and Prism by the unbond handler
 pub(crate) fn query_withdrawable_unbonded(
     deps: Deps,
     env: Env,
@@ -17,21 +19,41 @@ pub(crate) fn query_withdrawable_unbonded(
     let state = STATE.load(deps.storage)?;
     let bluna_hub_address =
         query_contract_from_mem(deps, &state.memory_contract, ANCHOR_BLUNA_HUB_ID)?;
+    let cluna_hub_address =
+        query_contract_from_mem(deps, &state.memory_contract, PRISM_CLUNA_HUB_ID)?;
+
+    // query how much withdrawable_unbonded is on anchor and prism for the given unbond handler
+    let anchor_withdrawable =
+        anchor::query_withdrawable_unbonded(deps, bluna_hub_address, env.contract.address.clone())?
+            .withdrawable;
+    let prism_withdrawable =
+        prism::query_withdrawable_unbonded(deps, cluna_hub_address, env.contract.address)?
+            .withdrawable;
 
-    // query how much withdrawable_unbonded is on anchor for the given unbond handler
-    white_whale::query::anchor::query_withdrawable_unbonded(
-        deps,
-        bluna_hub_address,
-        env.contract.address,
-    )
+    Ok(WithdrawableUnbondedResponse {
+        withdrawable: anchor_withdrawable.checked_add(prism_withdrawable)?,
+    })
 }
 
-/// Gets the state of the contract
+/// Gets the unbond requests on Anchor and Prism of the unbond handler
 pub(crate) fn query_unbond_requests(deps: Deps, env: Env) -> StdResult<UnbondRequestsResponse> {
     let state = STATE.load(deps.storage)?;
     let bluna_hub_address =
         query_contract_from_mem(deps, &state.memory_contract, ANCHOR_BLUNA_HUB_ID)?;
+    let cluna_hub_address =
+        query_contract_from_mem(deps, &state.memory_contract, PRISM_CLUNA_HUB_ID)?;
+
+    // query unbond requests on anchor and prism for the given unbond handler
+    let anchor_requests =
+        anchor::query_unbond_requests(deps, bluna_hub_address, env.contract.address.clone())?
+            .requests;
+    let prism_requests =
+        prism::query_unbond_requests(deps, cluna_hub_address, env.contract.address.clone())?
+            .requests;
 
-    // query unbond requests on anchor for the given unbond handler
-    white_whale::query::anchor::query_unbond_requests(deps, bluna_hub_address, env.contract.address)
+    Ok(UnbondRequestsResponse {
+        address: env.contract.address.to_string(),
+        anchor_requests,
+        prism_requests,
+    })
 }
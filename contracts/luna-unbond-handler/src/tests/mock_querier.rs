@@ -0,0 +1,202 @@
+#![allow(dead_code)]
+
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Addr, Coin, ContractResult, Decimal, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute};
+use terraswap::asset::AssetInfo;
+
+use white_whale::fee::{Fee, VaultFee};
+use white_whale::luna_vault::msg::FeeResponse;
+use white_whale::memory::{
+    ANCHOR_BLUNA_HUB_ID, BLUNA_TOKEN_MEMORY_ID, CLUNA_TOKEN_MEMORY_ID, PRISM_CLUNA_HUB_ID,
+    TREASURY_ADDRESS_ID,
+};
+use white_whale::query::anchor::{AnchorQuery, UnbondRequestsResponse as AnchorUnbondRequests};
+use white_whale::query::prism::{PrismQuery, UnbondRequestsResponse as PrismUnbondRequests};
+
+use crate::tests::common::{TEST_CREATOR, TEST_MEMORY_CONTRACT};
+
+pub const TEST_BLUNA: &str = "bluna";
+pub const TEST_CLUNA: &str = "cluna";
+pub const TEST_BLUNA_HUB: &str = "bluna_hub";
+pub const TEST_CLUNA_HUB: &str = "cluna_hub";
+pub const TEST_TREASURY: &str = "treasury";
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        api: MockApi::default(),
+        storage: MockStorage::default(),
+        querier: custom_querier,
+    }
+}
+
+/// Mocks the memory contract, the Anchor bLuna and Prism cLuna hubs and the luna vault fees
+pub struct WasmMockQuerier {
+    base: MockQuerier<TerraQueryWrapper>,
+    anchor_withdrawable: Uint128,
+    prism_withdrawable: Uint128,
+    anchor_requests: Vec<(u64, Uint128, Uint128)>,
+    prism_requests: Vec<(u64, Uint128)>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<TerraQueryWrapper> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                });
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<TerraQueryWrapper>) -> QuerierResult {
+        match &request {
+            QueryRequest::Custom(TerraQueryWrapper { route, query_data })
+                if route == &TerraRoute::Treasury =>
+            {
+                match query_data {
+                    TerraQuery::TaxRate {} => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&TaxRateResponse {
+                            rate: Decimal::zero(),
+                        })))
+                    }
+                    TerraQuery::TaxCap { .. } => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&TaxCapResponse {
+                            cap: Uint128::zero(),
+                        })))
+                    }
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            // the memory contract, returning the mocked hubs, tokens and treasury
+            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key })
+                if contract_addr == TEST_MEMORY_CONTRACT =>
+            {
+                let key = key.as_slice();
+                let response = if key.ends_with(ANCHOR_BLUNA_HUB_ID.as_bytes()) {
+                    to_binary(&Addr::unchecked(TEST_BLUNA_HUB))
+                } else if key.ends_with(PRISM_CLUNA_HUB_ID.as_bytes()) {
+                    to_binary(&Addr::unchecked(TEST_CLUNA_HUB))
+                } else if key.ends_with(TREASURY_ADDRESS_ID.as_bytes()) {
+                    to_binary(&Addr::unchecked(TEST_TREASURY))
+                } else if key.ends_with(BLUNA_TOKEN_MEMORY_ID.as_bytes()) {
+                    to_binary(&AssetInfo::Token {
+                        contract_addr: TEST_BLUNA.to_string(),
+                    })
+                } else if key.ends_with(CLUNA_TOKEN_MEMORY_ID.as_bytes()) {
+                    to_binary(&AssetInfo::Token {
+                        contract_addr: TEST_CLUNA.to_string(),
+                    })
+                } else {
+                    panic!("DO NOT ENTER HERE")
+                };
+                SystemResult::Ok(ContractResult::from(response))
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if contract_addr == TEST_BLUNA_HUB =>
+            {
+                match from_binary(msg).unwrap() {
+                    AnchorQuery::WithdrawableUnbonded { .. } => {
+                        SystemResult::Ok(ContractResult::from(to_binary(
+                            &white_whale::query::anchor::WithdrawableUnbondedResponse {
+                                withdrawable: self.anchor_withdrawable,
+                            },
+                        )))
+                    }
+                    AnchorQuery::UnbondRequests { address } => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&AnchorUnbondRequests {
+                            address,
+                            requests: self.anchor_requests.clone(),
+                        })))
+                    }
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if contract_addr == TEST_CLUNA_HUB =>
+            {
+                match from_binary(msg).unwrap() {
+                    PrismQuery::WithdrawableUnbonded { .. } => {
+                        SystemResult::Ok(ContractResult::from(to_binary(
+                            &white_whale::query::prism::WithdrawableUnbondedResponse {
+                                withdrawable: self.prism_withdrawable,
+                            },
+                        )))
+                    }
+                    PrismQuery::UnbondRequests { address } => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&PrismUnbondRequests {
+                            address,
+                            requests: self.prism_requests.clone(),
+                        })))
+                    }
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            // the luna vault, which is the admin of the handler
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, .. })
+                if contract_addr == TEST_CREATOR =>
+            {
+                SystemResult::Ok(ContractResult::from(to_binary(&FeeResponse {
+                    fees: VaultFee {
+                        flash_loan_fee: Fee {
+                            share: Decimal::permille(5),
+                        },
+                        treasury_fee: Fee {
+                            share: Decimal::percent(10),
+                        },
+                        commission_fee: Fee {
+                            share: Decimal::percent(50),
+                        },
+                        treasury_addr: Addr::unchecked(TEST_TREASURY),
+                    },
+                    instant_withdraw_fee: Fee {
+                        share: Decimal::zero(),
+                    },
+                })))
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+
+    pub fn new(base: MockQuerier<TerraQueryWrapper>) -> Self {
+        WasmMockQuerier {
+            base,
+            anchor_withdrawable: Uint128::zero(),
+            prism_withdrawable: Uint128::zero(),
+            anchor_requests: vec![],
+            prism_requests: vec![],
+        }
+    }
+
+    // configure the luna withdrawable from the Anchor and Prism hubs
+    pub fn with_withdrawable(&mut self, anchor: Uint128, prism: Uint128) {
+        self.anchor_withdrawable = anchor;
+        self.prism_withdrawable = prism;
+    }
+
+    // configure the pending unbond requests on the Anchor and Prism hubs
+    pub fn with_unbond_requests(
+        &mut self,
+        anchor_requests: Vec<(u64, Uint128, Uint128)>,
+        prism_requests: Vec<(u64, Uint128)>,
+    ) {
+        self.anchor_requests = anchor_requests;
+        self.prism_requests = prism_requests;
+    }
+}
@@ -4,4 +4,7 @@ mod common_integration;
 mod instantiate;
 mod integration;
 mod mock_memory;
+mod mock_querier;
+mod unbond;
 mod update_state;
+mod withdraw_unbonded;
@@ -0,0 +1,88 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, to_binary, Addr, Response, Uint128};
+use cw20::Cw20ReceiveMsg;
+use white_whale::luna_vault::luna_unbond_handler::msg::{
+    Cw20HookMsg, ExecuteMsg, QueryMsg, UnbondRequestsResponse,
+};
+use white_whale::prism::prism_cluna_unbond_msg;
+
+use crate::contract::{execute, query};
+use crate::UnbondHandlerError;
+
+use super::instantiate::mock_instantiate;
+use super::mock_querier::{mock_dependencies, TEST_CLUNA, TEST_CLUNA_HUB};
+
+fn unbond_msg(amount: u128) -> ExecuteMsg {
+    ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "luna_vault".to_string(),
+        amount: Uint128::new(amount),
+        msg: to_binary(&Cw20HookMsg::Unbond {}).unwrap(),
+    })
+}
+
+#[test]
+fn does_unbond_cluna_on_prism() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CLUNA, &[]),
+        unbond_msg(1000),
+    )
+    .unwrap();
+
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attributes(vec![("action", "unbond_cluna"), ("amount", "1000")])
+            .add_message(
+                prism_cluna_unbond_msg(
+                    Addr::unchecked(TEST_CLUNA),
+                    Addr::unchecked(TEST_CLUNA_HUB),
+                    Uint128::new(1000),
+                )
+                .unwrap()
+            )
+    );
+}
+
+#[test]
+fn unsuccessful_unbond_unsupported_token() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other_token", &[]),
+        unbond_msg(1000),
+    );
+    match res {
+        Err(UnbondHandlerError::UnsupportedToken {}) => (),
+        _ => panic!("Must return UnbondHandlerError::UnsupportedToken"),
+    }
+}
+
+#[test]
+fn does_query_unbond_requests() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    deps.querier.with_unbond_requests(
+        vec![(1u64, Uint128::new(100), Uint128::zero())],
+        vec![(2u64, Uint128::new(20))],
+    );
+
+    let res: UnbondRequestsResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::UnbondRequests {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        res,
+        UnbondRequestsResponse {
+            address: mock_env().contract.address.to_string(),
+            anchor_requests: vec![(1u64, Uint128::new(100), Uint128::zero())],
+            prism_requests: vec![(2u64, Uint128::new(20))],
+        }
+    );
+}
@@ -0,0 +1,173 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coins, to_binary, Addr, BankMsg, CosmosMsg, Uint128, WasmMsg};
+use white_whale::luna_vault::luna_unbond_handler::msg::{CallbackMsg, ExecuteMsg};
+use white_whale::luna_vault::msg::UnbondHandlerMsg;
+use white_whale::prism::prism_withdraw_unbonded_msg;
+
+use crate::contract::execute;
+use crate::state::STATE;
+use crate::tests::common::{TEST_CREATOR, TEST_OWNER};
+use crate::UnbondHandlerError;
+
+use super::instantiate::mock_instantiate;
+use super::mock_querier::{mock_dependencies, TEST_CLUNA_HUB, TEST_TREASURY};
+
+const TEST_LIQUIDATOR: &str = "liquidator";
+
+fn withdraw_msg(triggered_by: &str) -> ExecuteMsg {
+    ExecuteMsg::WithdrawUnbonded {
+        triggered_by_addr: triggered_by.to_string(),
+    }
+}
+
+fn after_withdraw_msg(triggered_by: &str) -> CosmosMsg {
+    CallbackMsg::AfterWithdraw {
+        triggered_by_addr: triggered_by.to_string(),
+    }
+    .to_cosmos_msg(&mock_env().contract.address)
+    .unwrap()
+}
+
+#[test]
+fn does_withdraw_unbonded_from_prism() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    deps.querier
+        .with_withdrawable(Uint128::zero(), Uint128::new(1000));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        withdraw_msg(TEST_OWNER),
+    )
+    .unwrap();
+
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|msg| msg.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            prism_withdraw_unbonded_msg(Addr::unchecked(TEST_CLUNA_HUB)).unwrap(),
+            after_withdraw_msg(TEST_OWNER),
+        ]
+    );
+}
+
+#[test]
+fn unsuccessful_withdraw_unbonded_nothing_to_withdraw() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    // still unbonding, nothing was released nor is held by the handler
+    deps.querier
+        .with_unbond_requests(vec![(1u64, Uint128::new(100), Uint128::zero())], vec![]);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        withdraw_msg(TEST_OWNER),
+    );
+    match res {
+        Err(UnbondHandlerError::NothingToWithdraw {}) => (),
+        _ => panic!("Must return UnbondHandlerError::NothingToWithdraw"),
+    }
+}
+
+#[test]
+fn does_liquidate_held_luna_when_hubs_have_nothing_to_withdraw() {
+    let mut deps = mock_dependencies(&coins(1000, "uluna"));
+    mock_instantiate(deps.as_mut());
+
+    // past the expiration time, with the luna already withdrawn from the hubs
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_CREATOR, &[]),
+        withdraw_msg(TEST_LIQUIDATOR),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].msg, after_withdraw_msg(TEST_LIQUIDATOR));
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::AfterWithdraw {
+            triggered_by_addr: TEST_LIQUIDATOR.to_string(),
+        }),
+    )
+    .unwrap();
+
+    // 10% liquidation fee, half of it going to the liquidator and the rest to the treasury
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|msg| msg.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_OWNER.to_string(),
+                amount: coins(900, "uluna"),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_LIQUIDATOR.to_string(),
+                amount: coins(50, "uluna"),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: TEST_TREASURY.to_string(),
+                amount: coins(50, "uluna"),
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TEST_CREATOR.to_string(),
+                msg: to_binary(&UnbondHandlerMsg::AfterUnbondHandlerReleased {
+                    unbond_handler_addr: MOCK_CONTRACT_ADDR.to_string(),
+                    previous_owner: TEST_OWNER.to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // the handler is released to be reused
+    let state = STATE.load(&deps.storage).unwrap();
+    assert_eq!(state.owner, None);
+    assert_eq!(state.expiration_time, None);
+}
+
+#[test]
+fn does_release_empty_handler() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // nothing left to withdraw nor held, the handler is still released
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        withdraw_msg(TEST_OWNER),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::AfterWithdraw {
+            triggered_by_addr: TEST_OWNER.to_string(),
+        }),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "unbond_handler_released" && attr.value == "true"));
+}
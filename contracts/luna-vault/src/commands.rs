@@ -1,34 +1,53 @@
 use astroport::querier::query_token_balance;
 use cosmwasm_std::{
-    attr, from_binary, to_binary, Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
-    ReplyOn, Response, SubMsg, Uint128, WasmMsg,
+    attr, from_binary, to_binary, Addr, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Order,
+    ReplyOn, Response, StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::U64Key;
 use terraswap::asset::{Asset, AssetInfo};
-use terraswap::querier::query_supply;
+use terraswap::querier::{query_balance, query_supply};
 
 use white_whale::anchor::anchor_withdraw_unbonded_msg;
 use white_whale::denom::LUNA_DENOM;
 use white_whale::fee::Fee;
 use white_whale::luna_vault::luna_unbond_handler::msg::InstantiateMsg;
-use white_whale::luna_vault::msg::{Cw20HookMsg, UnbondHandlerMsg};
+use white_whale::luna_vault::luna_unbond_handler::queries::query_handler_withdrawable_unbonded;
+use white_whale::luna_vault::msg::{
+    Cw20HookMsg, DepositCaps, HarvestConfig, PassiveStrategyKind, StrategyInfo, UnbondHandlerMsg,
+    UnbondHub, UnbondReservation,
+};
 use white_whale::memory::queries::query_contract_from_mem;
 use white_whale::memory::{ANCHOR_BLUNA_HUB_ID, LIST_SIZE_LIMIT, PRISM_CLUNA_HUB_ID};
 use white_whale::prism::prism_withdraw_unbonded_msg;
 use white_whale::query::{anchor, prism};
+use white_whale::vault::fees::{self, FeeTier, LoanFee};
+use white_whale::vault::flashloan::assert_no_loan_ongoing;
+use white_whale::vault::pause::{assert_not_paused, PausableOperation};
+use white_whale::vault::performance_fee;
 
 use crate::contract::{VaultResult, INSTANTIATE_UNBOND_HANDLER_REPLY_ID};
 use crate::error::LunaVaultError;
+use crate::flashloan::LunaVault;
 use crate::helpers::{
-    check_fee, compute_total_value, get_lp_token_address, get_share_amount, get_treasury_fee,
-    unbond_bluna_with_handler_msg, update_unbond_handler_state_msg, withdraw_luna_from_handler_msg,
-    ConversionAsset,
+    add_reservations, check_fee, compute_total_value, get_treasury_fee,
+    query_expected_release_time, query_hub_exchange_rates, query_released_reserved_unbonds,
+    query_vault_unbond_requests, record_harvest, record_snapshot, remove_reservations,
+    reserved_batch_amount, save_unbond_ticket, split_released_reservations,
+    total_reserved_luna_amount, unbond_bluna_with_handler_msg, unbond_cluna_with_handler_msg,
+    update_unbond_handler_state_msg, withdraw_luna_from_handler_msg,
 };
 use crate::pool_info::PoolInfoRaw;
-use crate::queries::{query_unbond_handler_expiration_time, query_withdrawable_unbonded};
+use crate::queries::{query_simulate_withdraw, query_unbond_handler_expiration_time};
 use crate::state::{
-    UnbondDataCache, ADMIN, DEPOSIT_INFO, FEE, POOL_INFO, PROFIT, STATE, UNBOND_CACHE,
-    UNBOND_HANDLERS_ASSIGNED, UNBOND_HANDLERS_AVAILABLE, UNBOND_HANDLER_EXPIRATION_TIMES,
+    UnbondDataCache, UnbondTicket, ADMIN, DEPOSIT_CAPS, DEPOSIT_INFO, FEE, HARVEST_CONFIG,
+    INSTANT_WITHDRAW_FEE, LAST_UNBOND_TICKET_ID, POOL_INFO, RESERVED_UNBONDS, STATE, STRATEGIES,
+    UNBOND_CACHE, UNBOND_HANDLERS_ASSIGNED, UNBOND_HANDLERS_AVAILABLE,
+    UNBOND_HANDLER_EXPIRATION_TIMES, UNBOND_RESERVATIONS, UNBOND_TICKETS,
+};
+use crate::strategies::{
+    deposit_into_strategies, load_strategies, load_strategy, query_strategies_values,
+    validate_strategies, withdraw_from_strategies, withdraw_from_vault, RewardsSwap,
 };
 
 /// handler function invoked when the luna-vault contract receives
@@ -49,6 +68,14 @@ pub fn receive_cw20(
             }
             unbond(deps, env, cw20_msg.amount, cw20_msg.sender)
         }
+        Cw20HookMsg::InstantWithdraw {} => {
+            // only vLuna token contract can execute this message
+            let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+            if deps.api.addr_validate(&msg_info.sender.to_string())? != info.liquidity_token {
+                return Err(LunaVaultError::Unauthorized {});
+            }
+            instant_withdraw(deps, env, cw20_msg.amount, cw20_msg.sender)
+        }
     }
 }
 
@@ -60,13 +87,10 @@ pub fn provide_liquidity(
     asset: Asset,
 ) -> VaultResult<Response> {
     let deposit_info = DEPOSIT_INFO.load(deps.storage)?;
-    let profit = PROFIT.load(deps.storage)?;
-    let state = STATE.load(deps.storage)?;
     let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
 
-    if profit.last_balance != Uint128::zero() {
-        return Err(LunaVaultError::DepositDuringLoan {});
-    }
+    assert_no_loan_ongoing(&LunaVault, deps.storage)?;
+    assert_not_paused(deps.storage, PausableOperation::Deposits)?;
 
     // Init vector for logging
     let mut attrs = vec![];
@@ -85,6 +109,29 @@ pub fn provide_liquidity(
     // Get total supply of vLuna tokens and calculate share
     let total_share = query_supply(&deps.querier, info.liquidity_token.clone())?;
 
+    // Check the deposit against the vault caps
+    let deposit_caps = DEPOSIT_CAPS.may_load(deps.storage)?.unwrap_or_default();
+    if let Some(tvl_cap) = deposit_caps.tvl_cap {
+        if total_deposits_in_luna > tvl_cap {
+            return Err(LunaVaultError::TvlCapExceeded {});
+        }
+    }
+    if let Some(per_address_cap) = deposit_caps.per_address_cap {
+        let sender_share = query_token_balance(
+            &deps.querier,
+            info.liquidity_token.clone(),
+            msg_info.sender.clone(),
+        )?;
+        let sender_value = if total_share.is_zero() {
+            Uint128::zero()
+        } else {
+            sender_share.multiply_ratio(total_deposits_in_luna.checked_sub(deposit)?, total_share)
+        };
+        if sender_value.checked_add(deposit)? > per_address_cap {
+            return Err(LunaVaultError::AddressCapExceeded {});
+        }
+    }
+
     let share = if total_share == Uint128::zero()
         || total_deposits_in_luna.checked_sub(deposit)? == Uint128::zero()
     {
@@ -104,159 +151,108 @@ pub fn provide_liquidity(
         funds: vec![],
     });
 
-    let response = Response::new().add_attributes(attrs).add_message(mint_msg);
-    // Deposit liquid luna into passive strategy
-    deposit_passive_strategy(
-        &deps.as_ref(),
-        deposit,
-        state.bluna_address,
-        &state.astro_lp_address,
-        response,
-    )
-}
-
-// Deposits Luna into the passive strategy (Astroport) -> luna-bluna LP
-pub(crate) fn deposit_passive_strategy(
-    deps: &Deps,
-    deposit_amount: Uint128,
-    bluna_address: Addr,
-    astro_lp_address: &Addr,
-    response: Response,
-) -> VaultResult<Response> {
-    // split luna into half so half goes to purchase bLuna, remaining half is used as liquidity
-    let luna_asset = astroport::asset::Asset {
-        amount: deposit_amount.checked_div(Uint128::from(2_u8))?,
-        info: astroport::asset::AssetInfo::NativeToken {
-            denom: LUNA_DENOM.to_string(),
-        },
-    };
+    // Deposit liquid luna into the passive strategies
+    let deposit_msgs = deposit_into_strategies(deps.as_ref(), &env, deposit)?;
 
-    // simulate the luna swap so we know the bluna return amount when we later provide liquidity
-    let bluna_return: astroport::pair::SimulationResponse = deps.querier.query_wasm_smart(
-        astro_lp_address,
-        &astroport::pair::QueryMsg::Simulation {
-            offer_asset: luna_asset.clone(),
-        },
+    record_snapshot(
+        deps.storage,
+        &env,
+        total_deposits_in_luna.checked_sub(deposit)?,
+        total_deposits_in_luna,
+        total_share.checked_add(share)?,
+        Uint128::zero(),
     )?;
 
-    let bluna_asset = astroport::asset::Asset {
-        amount: bluna_return.return_amount,
-        info: astroport::asset::AssetInfo::Token {
-            contract_addr: bluna_address,
-        },
-    };
-
-    let bluna_purchase_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: astro_lp_address.to_string(),
-        msg: to_binary(&astroport::pair::ExecuteMsg::Swap {
-            offer_asset: luna_asset.clone(),
-            belief_price: None,
-            max_spread: None,
-            to: None,
-        })?,
-        funds: vec![],
-    });
-
-    let deposit_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: astro_lp_address.to_string(),
-        msg: to_binary(&astroport::pair::ExecuteMsg::ProvideLiquidity {
-            assets: [luna_asset, bluna_asset],
-            slippage_tolerance: None,
-            auto_stake: None,
-            receiver: None,
-        })?,
-        funds: vec![],
-    });
-
-    let response = response.add_messages(vec![
-        bluna_purchase_msg, // 1. purchase bluna
-        deposit_msg,        // 2. deposit bLuna/Luna to the LP as liquidity
-    ]);
-
-    Ok(response)
+    Ok(Response::new()
+        .add_attributes(attrs)
+        .add_message(mint_msg)
+        .add_messages(deposit_msgs))
 }
 
-// Withdraws Luna from the passive strategy (Astroport): luna-bluna LP -> Luna + bLuna
-pub(crate) fn withdraw_passive_strategy(
-    deps: &Deps,
-    withdraw_amount: Uint128,
-    requested_info: AssetInfo,
-    astro_lp_token_address: &Addr,
-    astro_lp_address: &Addr,
-    response: Response,
+/// This message must be called by receive_cw20
+/// This message burns the vluna token and sends the luna back to the user right away, taking it
+/// from the vault's liquid luna first and then from the passive strategies. The bLuna side of the
+/// Astroport LP is swapped back into the stable pool. The vault keeps the instant withdraw fee.
+fn instant_withdraw(
+    deps: DepsMut,
+    env: Env,
+    amount: Uint128,
+    sender: String, // human who sent the vluna to us
 ) -> VaultResult<Response> {
-    // convert requested_info into an Astroport asset
-    let requested_info = requested_info.to_astroport(deps)?;
+    assert_no_loan_ongoing(&LunaVault, deps.storage)?;
+    assert_not_paused(deps.storage, PausableOperation::Withdrawals)?;
 
-    let pool_info: astroport::pair::PoolResponse = deps.querier.query_wasm_smart(
-        astro_lp_address,
-        &astroport::pair_stable_bluna::QueryMsg::Pool {},
-    )?;
+    let sender_addr = deps.api.addr_validate(&sender)?;
+    let withdraw = query_simulate_withdraw(deps.as_ref(), env.clone(), amount)?;
 
-    let requested_asset = astroport::asset::Asset {
-        amount: withdraw_amount,
-        info: requested_info,
+    // Get treasury fee in LP tokens
+    let treasury_fee = get_treasury_fee(deps.as_ref(), amount)?;
+    let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+    let lp_token_treasury_fee = Asset {
+        info: AssetInfo::Token {
+            contract_addr: info.liquidity_token.to_string(),
+        },
+        amount: treasury_fee,
     };
+    let fee_config = FEE.load(deps.storage)?;
+    let treasury_fee_msg = fee_config.treasury_fee.msg(
+        deps.as_ref(),
+        lp_token_treasury_fee,
+        fee_config.treasury_addr,
+    )?;
 
-    let share_to_withdraw =
-        get_share_amount(deps, astro_lp_address.clone(), requested_asset.clone())?;
-
-    // cw20 send message that transfers the LP tokens to the pair address and withdraws liquidity
-    let cw20_withdraw_liquidity_msg: CosmosMsg = WasmMsg::Execute {
-        contract_addr: astro_lp_token_address.clone().into_string(),
-        msg: to_binary(&Cw20ExecuteMsg::Send {
-            contract: astro_lp_address.clone().into_string(),
-            amount: share_to_withdraw,
-            msg: to_binary(&astroport::pair::Cw20HookMsg::WithdrawLiquidity {})?,
+    // Send Burn message to vluna contract
+    let burn_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: info.liquidity_token.to_string(),
+        // Burn excludes treasury fee
+        msg: to_binary(&Cw20ExecuteMsg::Burn {
+            amount: amount - treasury_fee,
         })?,
         funds: vec![],
-    }
-    .into();
+    });
 
-    // provide liquidity with the remaining bLuna as single-sided LP provision
-    let pool_non_desired_asset = pool_info
-        .assets
-        .iter()
-        .find(|asset| asset.info != requested_asset.info)
-        .ok_or_else(|| {
-            LunaVaultError::generic_err(
-                "Failed to get non-desired asset when providing single-sided liquidity",
-            )
-        })?;
-
-    // reference: https://github.com/astroport-fi/astroport-core/blob/c0a121798157ea3e5540a19b8061eb0196b15667/contracts/pair_stable_bluna/src/contract.rs#L729-L737
-    let unwanted_amount_returned = Decimal::from_ratio(share_to_withdraw, pool_info.total_share)
-        * pool_non_desired_asset.amount;
-
-    let bluna_lp_msg = WasmMsg::Execute {
-        contract_addr: astro_lp_address.clone().into_string(),
-        msg: to_binary(
-            &astroport::pair_stable_bluna::ExecuteMsg::ProvideLiquidity {
-                assets: [
-                    astroport::asset::Asset {
-                        amount: Uint128::zero(),
-                        info: requested_asset.info.clone(),
-                    },
-                    astroport::asset::Asset {
-                        amount: unwanted_amount_returned,
-                        info: pool_non_desired_asset.info.clone(),
-                    },
-                ],
-                slippage_tolerance: None,
-                auto_stake: None,
-                receiver: None,
-            },
-        )?,
-        funds: vec![],
-    }
-    .into();
+    // the user is paid the luna the strategies return after slippage, not the value withdrawn from them
+    let instant_value = withdraw
+        .unbond_amount
+        .checked_sub(withdraw.instant_withdraw_fee)?;
+    let withdrawal = withdraw_from_vault(deps.as_ref(), &env, instant_value)?;
 
-    let response = response.add_messages(vec![
-        cw20_withdraw_liquidity_msg, // 1. withdraw bluna and Luna from LP.
-        bluna_lp_msg,                // 2. provide single-sided LP provision to astroport pool
-    ]);
+    let refund_asset = Asset {
+        info: AssetInfo::NativeToken {
+            denom: LUNA_DENOM.to_string(),
+        },
+        amount: withdrawal.luna_return,
+    };
+    let refund_msg = refund_asset.into_msg(&deps.querier, sender_addr)?;
+
+    // the instant withdraw fee stays in the vault
+    let total_value_in_luna = compute_total_value(&env, deps.as_ref(), &info)?.total_value_in_luna;
+    let total_share = query_supply(&deps.querier, info.liquidity_token)?;
+    record_snapshot(
+        deps.storage,
+        &env,
+        total_value_in_luna,
+        total_value_in_luna.checked_sub(instant_value)?,
+        total_share.checked_sub(amount - treasury_fee)?,
+        Uint128::zero(),
+    )?;
 
-    Ok(response)
+    Ok(Response::new()
+        .add_message(treasury_fee_msg)
+        .add_message(burn_msg)
+        .add_messages(withdrawal.messages)
+        .add_message(refund_msg)
+        .add_attributes(vec![
+            ("action", "instant_withdraw".to_string()),
+            ("from", sender),
+            ("burnt_amount", amount.to_string()),
+            ("treasury_fee", treasury_fee.to_string()),
+            (
+                "instant_withdraw_fee",
+                withdraw.instant_withdraw_fee.to_string(),
+            ),
+            ("luna_returned", withdrawal.luna_return.to_string()),
+        ]))
 }
 
 /// This message must be called by receive_cw20
@@ -268,10 +264,8 @@ fn unbond(
     sender: String, // human who sent the vluna to us
 ) -> VaultResult<Response> {
     let state = STATE.load(deps.storage)?;
-    let profit = PROFIT.load(deps.storage)?;
-    if profit.last_balance != Uint128::zero() {
-        return Err(LunaVaultError::DepositDuringLoan {});
-    }
+    assert_no_loan_ongoing(&LunaVault, deps.storage)?;
+    assert_not_paused(deps.storage, PausableOperation::Withdrawals)?;
 
     // Logging var
     let mut response = Response::new().add_attribute("action", "unbond");
@@ -318,115 +312,160 @@ fn unbond(
         funds: vec![],
     });
 
-    // withdraw shares from the LP, sending the Luna to the withdraw user and the bLuna to the unbond handler
-    let bluna_luna_lp_token = get_lp_token_address(&deps.as_ref(), state.astro_lp_address.clone())?;
-    let bluna_luna_lp_amount = query_token_balance(
+    let rates = query_hub_exchange_rates(deps.as_ref())?;
+    let strategies = query_strategies_values(deps.as_ref(), &env, &rates)?;
+    let strategies_value_in_luna = strategies
+        .iter()
+        .fold(Uint128::zero(), |acc, (_, value)| acc + value.value_in_luna);
+
+    // user's share of the cLuna held by the vault, to be unbonded on Prism by the unbond handler
+    let cluna_amount = share_ratio
+        * query_token_balance(
+            &deps.querier,
+            state.cluna_address.clone(),
+            env.contract.address.clone(),
+        )?;
+    attrs.push(("cluna_amount", cluna_amount.to_string()));
+
+    // user's share of the bLuna held by the vault, to be unbonded on Anchor by the unbond handler.
+    // It's withdrawn along with the other strategies instead when the held bLuna strategy values it
+    let bluna_amount = if strategies
+        .iter()
+        .any(|(strategy, _)| strategy.strategy == PassiveStrategyKind::HeldBluna)
+    {
+        Uint128::zero()
+    } else {
+        share_ratio
+            * query_token_balance(
+                &deps.querier,
+                state.bluna_address.clone(),
+                env.contract.address.clone(),
+            )?
+    };
+    attrs.push(("bluna_amount", bluna_amount.to_string()));
+
+    // user's share of the liquid luna, leaving out the luna reserved for users from released unbonds
+    let luna_balance = query_balance(
         &deps.querier,
-        bluna_luna_lp_token,
         env.contract.address.clone(),
+        LUNA_DENOM.to_string(),
     )?;
+    let liquid_luna_amount = share_ratio
+        * luna_balance.saturating_sub(query_released_reserved_unbonds(deps.as_ref(), &env)?);
 
-    let mut refund_shares_amount = share_ratio * bluna_luna_lp_amount;
-
-    let luna_asset_info = astroport::asset::AssetInfo::NativeToken {
-        denom: LUNA_DENOM.to_string(),
-    };
+    // user's share of the passive strategies, withdrawn as luna
+    let mut strategies_withdraw_amount = share_ratio * strategies_value_in_luna;
 
     // reserve any pending unbonds from anchor and prism using share_ratio
-    // if there is anything unbonding, check if we can use the refund_shares_amount to withdraw
-    // otherwise, reserve from the anchor/prism unbonds
-    let bluna_hub_address =
-        query_contract_from_mem(deps.as_ref(), &state.memory_address, ANCHOR_BLUNA_HUB_ID)?;
-    let prism_hub_address =
-        query_contract_from_mem(deps.as_ref(), &state.memory_address, PRISM_CLUNA_HUB_ID)?;
-
-    let anchor_unbond_requests = anchor::query_unbond_requests(
-        deps.as_ref(),
-        bluna_hub_address,
-        env.contract.address.clone(),
-    )?
-    .requests;
-    let anchor_unbond_amount = anchor_unbond_requests.iter().map(|request| request.1).sum();
-
-    let prism_unbond_requests = prism::query_unbond_requests(
-        deps.as_ref(),
-        prism_hub_address,
-        env.contract.address.clone(),
-    )?
-    .requests;
-    let prism_unbond_amount = prism_unbond_requests.iter().map(|request| request.1).sum();
-
-    let user_anchor_unbond_amount = share_ratio * anchor_unbond_amount;
-    let user_prism_unbond_amount = share_ratio * prism_unbond_amount;
-
-    let anchor_shares_amount = get_share_amount(
-        &deps.as_ref(),
-        state.astro_lp_address.clone(),
-        astroport::asset::Asset {
-            amount: user_anchor_unbond_amount,
-            info: luna_asset_info.clone(),
-        },
-    )?;
-    let prism_shares_amount = get_share_amount(
-        &deps.as_ref(),
-        state.astro_lp_address.clone(),
-        astroport::asset::Asset {
-            amount: user_prism_unbond_amount,
-            info: luna_asset_info.clone(),
-        },
-    )?;
+    // if there is anything unbonding, check if the strategies can cover the user's part of it
+    // otherwise, reserve the user's pro-rata part of the anchor/prism unbonds
+    let unbond_requests = query_vault_unbond_requests(deps.as_ref(), &env.contract.address)?;
+    let mut reserved_unbonds = RESERVED_UNBONDS.may_load(deps.storage)?.unwrap_or_default();
+
+    // user's part of each pending request, leaving out what was already reserved for other users
+    let mut user_unbond_requests = vec![];
+    for request in unbond_requests {
+        let available_amount = request.amount.checked_sub(reserved_batch_amount(
+            &reserved_unbonds,
+            request.hub,
+            request.batch_id,
+        ))?;
+        let amount = share_ratio * available_amount;
+        if !amount.is_zero() {
+            // the luna owed is fixed at the current hub rate, so the claims can't exceed the value
+            // taken out of the vault
+            let luna_amount = match request.hub {
+                UnbondHub::Anchor => rates.bluna * amount,
+                UnbondHub::Prism => rates.cluna * amount,
+            };
+            user_unbond_requests.push(UnbondReservation {
+                amount,
+                luna_amount,
+                ..request
+            });
+        }
+    }
 
-    if refund_shares_amount + anchor_shares_amount < bluna_luna_lp_amount {
-        // we have enough shares to use instead of reserving from anchor unbonds
-        refund_shares_amount += anchor_shares_amount;
-    } else {
-        // add the difference
-        refund_shares_amount += refund_shares_amount + anchor_shares_amount - bluna_luna_lp_amount;
+    let mut reservations: Vec<UnbondReservation> = vec![];
+    for hub in [UnbondHub::Anchor, UnbondHub::Prism].iter() {
+        let user_hub_unbond_requests: Vec<UnbondReservation> = user_unbond_requests
+            .iter()
+            .filter(|request| request.hub == *hub)
+            .cloned()
+            .collect();
+        let user_hub_unbond_value_in_luna = total_reserved_luna_amount(&user_hub_unbond_requests);
+        if user_hub_unbond_value_in_luna.is_zero() {
+            continue;
+        }
 
-        // todo: reserve for withdrawal from Anchor hub
-        // todo: convert the remaining shares back into luna amount
+        if strategies_withdraw_amount + user_hub_unbond_value_in_luna <= strategies_value_in_luna {
+            // we have enough value in the strategies to use instead of reserving from the hub unbonds
+            strategies_withdraw_amount += user_hub_unbond_value_in_luna;
+        } else {
+            // not enough value left, reserve the user's part of the hub unbonds for withdrawal
+            reservations.extend(user_hub_unbond_requests);
+        }
     }
 
-    if refund_shares_amount + prism_shares_amount < bluna_luna_lp_amount {
-        // we have enough shares to use instead of reserving from prism unbonds
-        refund_shares_amount += prism_shares_amount;
-    } else {
-        // add the difference
-        refund_shares_amount += refund_shares_amount + anchor_shares_amount - bluna_luna_lp_amount;
+    if !reservations.is_empty() {
+        let mut user_reservations = UNBOND_RESERVATIONS
+            .may_load(deps.storage, sender_addr.clone())?
+            .unwrap_or_default();
+        add_reservations(&mut user_reservations, &reservations);
+        UNBOND_RESERVATIONS.save(deps.storage, sender_addr.clone(), &user_reservations)?;
 
-        // todo: reserve for withdrawal from Prism hub
-        // todo: convert the remaining shares back into luna amount to reserve
+        add_reservations(&mut reserved_unbonds, &reservations);
+        RESERVED_UNBONDS.save(deps.storage, &reserved_unbonds)?;
+
+        attrs.push((
+            "reserved_unbonds",
+            total_reserved_luna_amount(&reservations).to_string(),
+        ));
     }
 
-    // get underlying bluna/luna amount with given shares
-    let underlying_assets: [astroport::asset::Asset; 2] = deps.querier.query_wasm_smart(
-        state.astro_lp_address,
-        &astroport::pair_stable_bluna::QueryMsg::Share {
-            amount: refund_shares_amount,
+    // withdraw the user's share of the passive strategies as luna, sent to the unbond handler along
+    // with the user's share of the liquid luna
+    let withdrawal =
+        withdraw_from_strategies(deps.as_ref(), &env, &rates, strategies_withdraw_amount)?;
+    response = response.add_messages(withdrawal.messages);
+    let luna_asset = astroport::asset::Asset {
+        info: astroport::asset::AssetInfo::NativeToken {
+            denom: LUNA_DENOM.to_string(),
         },
-    )?;
-
-    // luna amount of the LP
-    let luna_asset = underlying_assets
-        .iter()
-        .find(|asset| asset.info == luna_asset_info)
-        .ok_or(LunaVaultError::NotLunaToken {})?
-        .clone();
+        amount: liquid_luna_amount + withdrawal.luna_return,
+    };
+    attrs.push(("luna_amount", luna_asset.amount.to_string()));
+
+    // every unbond gets its own ticket, backed by an unbond handler the luna, bluna and cluna are sent to
+    let ticket_id = LAST_UNBOND_TICKET_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(1u64)
+        .ok_or_else(|| LunaVaultError::generic_err("Unbond ticket id overflow"))?;
+    LAST_UNBOND_TICKET_ID.save(deps.storage, &ticket_id)?;
+    let expected_release_time = query_expected_release_time(deps.as_ref(), &env)?;
+    attrs.push(("ticket_id", ticket_id.to_string()));
+    attrs.push(("expected_release_time", expected_release_time.to_string()));
+
+    // check if there are handlers available to be used
+    if let Some((first, others)) = UNBOND_HANDLERS_AVAILABLE.load(deps.storage)?.split_first() {
+        // store remaining unbond handler addresses
+        UNBOND_HANDLERS_AVAILABLE.save(deps.storage, &others.to_vec())?;
+
+        // assign unbond handler to the user's ticket
+        save_unbond_ticket(
+            deps.storage,
+            &sender_addr,
+            &UnbondTicket {
+                id: ticket_id,
+                unbond_handler: first.clone(),
+                created_at: env.block.time.seconds(),
+                expected_release_time,
+            },
+        )?;
 
-    // bluna amount of the LP
-    let bluna_amount = underlying_assets
-        .iter()
-        .find(|asset| asset.info != luna_asset_info)
-        .ok_or_else(|| {
-            LunaVaultError::generic_err("Failed to get non-uluna asset when unbonding from LP")
-        })?
-        .amount;
-
-    // Check if there's a handler assigned to the user, send luna_amount and bluna_amount + unbond msg to it
-    if let Some(unbond_handler) =
-        UNBOND_HANDLERS_ASSIGNED.may_load(deps.storage, sender_addr.clone())?
-    {
-        // update the expiration time on the assigned handler
+        // update state of the selected unbond handler, make sender_addr the owner
+        // and update the expiration_time
         let expiration_time = env
             .block
             .time
@@ -434,102 +473,142 @@ fn unbond(
             .checked_add(query_unbond_handler_expiration_time(deps.storage)?)
             .ok_or(LunaVaultError::ExpirationTimeUnSet {})?;
 
-        let unbond_handler_update_state_msg =
-            update_unbond_handler_state_msg(unbond_handler.clone(), None, Some(expiration_time))?;
-        UNBOND_HANDLER_EXPIRATION_TIMES.save(
-            deps.storage,
-            unbond_handler.clone(),
-            &expiration_time,
+        let unbond_handler_update_state_msg = update_unbond_handler_state_msg(
+            first.clone(),
+            Some(sender_addr.to_string()),
+            Some(expiration_time),
         )?;
+        UNBOND_HANDLER_EXPIRATION_TIMES.save(deps.storage, first.clone(), &expiration_time)?;
 
-        // send bluna to unbond handler
-        let unbond_msg =
-            unbond_bluna_with_handler_msg(deps.storage, bluna_amount, &unbond_handler)?;
+        response = response.add_message(unbond_handler_update_state_msg);
 
-        // send luna portion of LP to unbond handler
-        let send_luna_to_handler_msg = luna_asset.into_msg(&deps.querier, unbond_handler)?;
+        // send bluna to unbond handler
+        if !bluna_amount.is_zero() {
+            response = response.add_message(unbond_bluna_with_handler_msg(
+                deps.storage,
+                bluna_amount,
+                first,
+            )?);
+        }
 
-        response = response.add_messages(vec![
-            unbond_handler_update_state_msg,
-            unbond_msg,
-            send_luna_to_handler_msg,
-        ]);
-    } else {
-        // there's no unbond handlers assigned to the user
-        // check if there are handlers available to be used
-        if let Some((first, others)) = UNBOND_HANDLERS_AVAILABLE.load(deps.storage)?.split_first() {
-            // assign unbond handler to user
-            UNBOND_HANDLERS_ASSIGNED.save(deps.storage, sender_addr.clone(), first)?;
-
-            // store remaining unbond handler addresses
-            UNBOND_HANDLERS_AVAILABLE.save(deps.storage, &others.to_vec())?;
-
-            // update state of the selected unbond handler, make sender_addr the owner
-            // and update the expiration_time
-            let expiration_time = env
-                .block
-                .time
-                .seconds()
-                .checked_add(query_unbond_handler_expiration_time(deps.storage)?)
-                .ok_or(LunaVaultError::ExpirationTimeUnSet {})?;
-
-            let unbond_handler_update_state_msg = update_unbond_handler_state_msg(
-                first.clone(),
-                Some(sender_addr.to_string()),
-                Some(expiration_time),
-            )?;
-            UNBOND_HANDLER_EXPIRATION_TIMES.save(deps.storage, first.clone(), &expiration_time)?;
-
-            // send bluna to unbond handler
-            let unbond_msg = unbond_bluna_with_handler_msg(deps.storage, bluna_amount, first)?;
-
-            // send luna portion of LP to unbond handler
-            let send_luna_to_handler_msg = luna_asset.into_msg(&deps.querier, first.clone())?;
-
-            response = response.add_messages(vec![
-                unbond_handler_update_state_msg,
-                unbond_msg,
-                send_luna_to_handler_msg,
-            ]);
-        } else {
-            // create a new unbond handler if there are no handlers available
-            let state = STATE.load(deps.storage)?;
-            let unbond_handler_instantiation_msg = SubMsg {
-                id: INSTANTIATE_UNBOND_HANDLER_REPLY_ID,
-                msg: WasmMsg::Instantiate {
-                    admin: Some(env.contract.address.to_string()),
-                    code_id: state.unbond_handler_code_id,
-                    msg: to_binary(&InstantiateMsg {
-                        owner: Some(sender_addr.to_string()),
-                        memory_contract: state.memory_address.to_string(),
-                        expires_in: Some(query_unbond_handler_expiration_time(deps.storage)?),
-                    })?,
-                    funds: vec![],
-                    label: "White Whale Unbond Handler".to_string(),
-                }
-                .into(),
-                gas_limit: None,
-                reply_on: ReplyOn::Success,
-            };
+        // send the luna withdrawn for the user to unbond handler
+        if !luna_asset.amount.is_zero() {
+            response = response.add_message(luna_asset.into_msg(&deps.querier, first.clone())?);
+        }
 
-            // temporarily store unbond data in cache to be used in the reply handler
-            UNBOND_CACHE.save(
+        // send cluna to unbond handler
+        if !cluna_amount.is_zero() {
+            response = response.add_message(unbond_cluna_with_handler_msg(
                 deps.storage,
-                &UnbondDataCache {
-                    owner: sender_addr,
-                    bluna_amount,
-                    luna_asset,
-                },
-            )?;
-            response = response.add_submessage(unbond_handler_instantiation_msg);
+                cluna_amount,
+                first,
+            )?);
         }
+    } else {
+        // create a new unbond handler if there are no handlers available
+        let state = STATE.load(deps.storage)?;
+        let unbond_handler_instantiation_msg = SubMsg {
+            id: INSTANTIATE_UNBOND_HANDLER_REPLY_ID,
+            msg: WasmMsg::Instantiate {
+                admin: Some(env.contract.address.to_string()),
+                code_id: state.unbond_handler_code_id,
+                msg: to_binary(&InstantiateMsg {
+                    owner: Some(sender_addr.to_string()),
+                    memory_contract: state.memory_address.to_string(),
+                    expires_in: Some(query_unbond_handler_expiration_time(deps.storage)?),
+                })?,
+                funds: vec![],
+                label: "White Whale Unbond Handler".to_string(),
+            }
+            .into(),
+            gas_limit: None,
+            reply_on: ReplyOn::Success,
+        };
+
+        // temporarily store unbond data in cache to be used in the reply handler
+        UNBOND_CACHE.save(
+            deps.storage,
+            &UnbondDataCache {
+                owner: sender_addr,
+                ticket_id,
+                expected_release_time,
+                bluna_amount,
+                cluna_amount,
+                luna_asset,
+            },
+        )?;
+        response = response.add_submessage(unbond_handler_instantiation_msg);
     }
 
+    let total_value_in_luna = compute_total_value(&env, deps.as_ref(), &info)?.total_value_in_luna;
+    record_snapshot(
+        deps.storage,
+        &env,
+        total_value_in_luna,
+        total_value_in_luna.checked_sub(share_ratio * total_value_in_luna)?,
+        total_share.checked_sub(amount - treasury_fee)?,
+        Uint128::zero(),
+    )?;
+
     Ok(response
         .add_messages(vec![burn_msg, treasury_fee_msg])
         .add_attributes(attrs))
 }
 
+/// Sends the luna reserved for the user from the vault's unbonds, once the hubs released it
+/// and the vault withdrew it
+pub fn claim_reserved_unbonds(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+) -> VaultResult<Response> {
+    assert_not_paused(deps.storage, PausableOperation::Withdrawals)?;
+
+    let reservations = UNBOND_RESERVATIONS
+        .may_load(deps.storage, msg_info.sender.clone())?
+        .ok_or(LunaVaultError::NoUnbondReservations {})?;
+
+    // reservations are released once their batch is not pending on the hub anymore
+    let unbond_requests = query_vault_unbond_requests(deps.as_ref(), &env.contract.address)?;
+    let (released, pending) = split_released_reservations(reservations, &unbond_requests);
+    // the reservations are paid out in luna at the hub rate they were made at, not 1:1
+    let claim_amount = total_reserved_luna_amount(&released);
+    if claim_amount.is_zero() {
+        return Err(LunaVaultError::NoClaimableReservedUnbonds {});
+    }
+
+    let luna_balance = query_balance(&deps.querier, env.contract.address, LUNA_DENOM.to_string())?;
+    if luna_balance < claim_amount {
+        return Err(LunaVaultError::Broke {});
+    }
+
+    if pending.is_empty() {
+        UNBOND_RESERVATIONS.remove(deps.storage, msg_info.sender.clone());
+    } else {
+        UNBOND_RESERVATIONS.save(deps.storage, msg_info.sender.clone(), &pending)?;
+    }
+
+    let mut reserved_unbonds = RESERVED_UNBONDS.may_load(deps.storage)?.unwrap_or_default();
+    remove_reservations(&mut reserved_unbonds, &released)?;
+    RESERVED_UNBONDS.save(deps.storage, &reserved_unbonds)?;
+
+    let refund_msg = Asset {
+        info: AssetInfo::NativeToken {
+            denom: LUNA_DENOM.to_string(),
+        },
+        amount: claim_amount,
+    }
+    .into_msg(&deps.querier, msg_info.sender.clone())?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "claim_reserved_unbonds"),
+            attr("recipient", msg_info.sender.to_string()),
+            attr("claimed_amount", claim_amount.to_string()),
+        ])
+        .add_message(refund_msg))
+}
+
 /// Withdraws unbonded luna after unbond has been called and the time lock period expired
 pub fn withdraw_unbonded(
     deps: DepsMut,
@@ -537,30 +616,113 @@ pub fn withdraw_unbonded(
     liquidation: bool,
     liquidate_addr: Option<String>,
 ) -> VaultResult<Response> {
-    let unbond_handler = if liquidation {
+    assert_not_paused(deps.storage, PausableOperation::Withdrawals)?;
+
+    let unbond_handlers = if liquidation {
         // validate liquidate_addr
         let liquidate_addr = liquidate_addr.ok_or(LunaVaultError::UnbondHandlerError {})?;
-        deps.api.addr_validate(&liquidate_addr)?
+        vec![deps.api.addr_validate(&liquidate_addr)?]
     } else {
-        // get unbond handler assigned to user
-        UNBOND_HANDLERS_ASSIGNED
-            .may_load(deps.storage, msg_info.sender.clone())?
-            .ok_or(LunaVaultError::NoUnbondHandlerAssigned {})?
+        // get the unbond handlers backing the user's tickets
+        let tickets = UNBOND_TICKETS
+            .prefix(msg_info.sender.clone())
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| Ok(item?.1.unbond_handler))
+            .collect::<StdResult<Vec<Addr>>>()?;
+        if tickets.is_empty() {
+            return Err(LunaVaultError::NoUnbondHandlerAssigned {});
+        }
+        tickets
     };
 
-    // create the withdraw unbonded msg with the assigned unbond handler
-    let withdraw_unbonded_msg =
-        withdraw_luna_from_handler_msg(unbond_handler.clone(), msg_info.sender)?;
-    let withadrawable_amount =
-        query_withdrawable_unbonded(deps.as_ref(), unbond_handler.to_string())?.withdrawable;
+    let mut response = Response::new().add_attribute("action", "withdraw_unbonded");
+    for unbond_handler in unbond_handlers {
+        let withdrawable_amount =
+            query_handler_withdrawable_unbonded(deps.as_ref(), &unbond_handler)?.withdrawable;
+        // only the liquidation goes through the handler when there's nothing to withdraw,
+        // as an expired handler gets released even if it has nothing left
+        if withdrawable_amount.is_zero() && !liquidation {
+            continue;
+        }
+
+        // create the withdraw unbonded msg with the assigned unbond handler
+        response = response
+            .add_message(withdraw_luna_from_handler_msg(
+                unbond_handler.clone(),
+                msg_info.sender.clone(),
+            )?)
+            .add_attributes(vec![
+                attr("unbond_handler", unbond_handler.to_string()),
+                attr("withdrawable_amount", withdrawable_amount.to_string()),
+            ]);
+    }
+
+    if response.messages.is_empty() {
+        return Err(LunaVaultError::NoClaimableUnbondTickets {});
+    }
+
+    Ok(response)
+}
+
+/// Sends back the unbonded luna of the given unbond ticket of the user
+pub fn claim_unbond_ticket(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    ticket_id: u64,
+) -> VaultResult<Response> {
+    assert_not_paused(deps.storage, PausableOperation::Withdrawals)?;
+
+    let ticket = UNBOND_TICKETS
+        .may_load(
+            deps.storage,
+            (msg_info.sender.clone(), U64Key::new(ticket_id)),
+        )?
+        .ok_or(LunaVaultError::UnbondTicketNotFound {})?;
+
+    let withdrawable_amount =
+        query_handler_withdrawable_unbonded(deps.as_ref(), &ticket.unbond_handler)?.withdrawable;
+    if withdrawable_amount.is_zero() {
+        return Err(LunaVaultError::NoClaimableUnbondTickets {});
+    }
 
     Ok(Response::new()
         .add_attributes(vec![
-            attr("action", "withdraw_unbonded"),
-            attr("unbond_handler", unbond_handler.to_string()),
-            attr("withadrawable_amount", withadrawable_amount.to_string()),
+            attr("action", "claim_unbond_ticket"),
+            attr("ticket_id", ticket_id.to_string()),
+            attr("unbond_handler", ticket.unbond_handler.to_string()),
+            attr("withdrawable_amount", withdrawable_amount.to_string()),
         ])
-        .add_message(withdraw_unbonded_msg))
+        .add_message(withdraw_luna_from_handler_msg(
+            ticket.unbond_handler,
+            msg_info.sender,
+        )?))
+}
+
+/// Sets the performance fee, or disables it if none. The gains made before are not charged
+pub fn set_performance_fee(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    fee: Option<Fee>,
+) -> VaultResult<Response> {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let fee = fee.map(check_fee).transpose()?;
+
+    let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+    let total_value_in_luna = compute_total_value(&env, deps.as_ref(), &info)?.total_value_in_luna;
+    let total_share = query_supply(&deps.querier, info.liquidity_token)?;
+    let share_price = if total_share.is_zero() {
+        Decimal::one()
+    } else {
+        Decimal::from_ratio(total_value_in_luna, total_share)
+    };
+    performance_fee::set_performance_fee(deps.storage, fee.clone(), share_price)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_performance_fee"),
+        attr("fee", format!("{:?}", fee)),
+    ]))
 }
 
 /// Sets a new admin
@@ -580,6 +742,7 @@ pub fn set_fee(
     flash_loan_fee: Option<Fee>,
     treasury_fee: Option<Fee>,
     commission_fee: Option<Fee>,
+    instant_withdraw_fee: Option<Fee>,
 ) -> VaultResult<Response> {
     // Only the admin should be able to call this
     ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
@@ -595,6 +758,10 @@ pub fn set_fee(
         fee_config.commission_fee = check_fee(fee)?;
     }
 
+    if let Some(fee) = instant_withdraw_fee {
+        INSTANT_WITHDRAW_FEE.save(deps.storage, &check_fee(fee)?)?;
+    }
+
     FEE.save(deps.storage, &fee_config)?;
     Ok(Response::default())
 }
@@ -721,117 +888,246 @@ pub fn update_state(
     Ok(Response::new().add_attributes(attrs))
 }
 
-pub fn swap_rewards(deps: DepsMut, env: Env, msg_info: MessageInfo) -> VaultResult<Response> {
+/// Swaps the passive strategy token rewards for luna and deposits it back into the strategies
+pub fn swap_rewards(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    max_spread: Option<Decimal>,
+    minimum_receive: Option<Uint128>,
+) -> VaultResult<Response> {
     let state = STATE.load(deps.storage)?;
     // Check if sender is in whitelist, i.e. bot or bot proxy
     if !state.whitelisted_contracts.contains(&msg_info.sender) {
         return Err(LunaVaultError::NotWhitelisted {});
     }
+    if max_spread.map_or(false, |max_spread| max_spread > Decimal::one()) {
+        return Err(LunaVaultError::InvalidMaxSpread {});
+    }
 
-    let mut response = Response::new();
+    let swap = RewardsSwap {
+        config: HARVEST_CONFIG.may_load(deps.storage)?.unwrap_or_default(),
+        max_spread,
+    };
+    let mut response = Response::new().add_attribute("action", "swap_rewards");
+
+    // harvest the rewards of each passive strategy into luna
+    let mut rewards = vec![];
+    let mut luna_return = Uint128::zero();
+    for strategy in load_strategies(deps.storage)? {
+        let harvest = load_strategy(deps.as_ref(), &env, &strategy.strategy)?
+            .harvest(deps.as_ref(), &swap)?;
+        // dust isn't worth the gas nor the swap fees
+        if harvest.luna_return.is_zero() || harvest.luna_return < swap.config.dust_threshold {
+            continue;
+        }
 
-    let passive_lp_token_address =
-        get_lp_token_address(&deps.as_ref(), state.astro_lp_address.clone())?;
+        rewards.extend(harvest.rewards);
+        luna_return += harvest.luna_return;
+        response = response
+            .add_messages(harvest.messages)
+            .add_attributes(harvest.attributes);
+    }
 
-    // swap ASTRO rewards for Luna to stay in the vault
-    let pending_tokens: astroport::generator::PendingTokenResponse =
-        deps.querier.query_wasm_smart(
-            state.astro_lp_address.clone(),
-            &astroport::generator::QueryMsg::PendingToken {
-                lp_token: passive_lp_token_address.clone().into_string(),
-                user: env.contract.address.into_string(),
-            },
-        )?;
+    if luna_return.is_zero() {
+        return Ok(response.add_attribute("harvest_skipped", "true"));
+    }
+    if minimum_receive.map_or(false, |minimum_receive| luna_return < minimum_receive) {
+        return Err(LunaVaultError::HarvestBelowMinimumReceive {});
+    }
 
-    // get generator address
-    let astro_factory_config: astroport::factory::ConfigResponse = deps.querier.query_wasm_smart(
-        state.astro_factory_address.clone(),
-        &astroport::factory::QueryMsg::Config {},
-    )?;
-    let astro_generator_address = astro_factory_config.generator_address.ok_or_else(|| {
-        LunaVaultError::generic_err("Astroport generator was not set in factory config")
-    })?;
-
-    // get ASTRO token address
-    let astro_generator_config: astroport::generator_proxy::ConfigResponse =
-        deps.querier.query_wasm_smart(
-            astro_generator_address.clone(),
-            &astroport::generator::QueryMsg::Config {},
-        )?;
-    let astro_token_address = deps
-        .api
-        .addr_validate(&*astro_generator_config.reward_token_addr)?;
-    let astro_pending = astroport::asset::Asset {
-        amount: pending_tokens.pending,
-        info: astroport::asset::AssetInfo::Token {
-            contract_addr: astro_token_address.clone(),
-        },
-    };
+    let harvest_id = record_harvest(deps.storage, &env, rewards, luna_return)?;
 
-    // withdraw ASTRO rewards
-    let withdraw_rewards_msg: CosmosMsg = WasmMsg::Execute {
-        contract_addr: astro_generator_address.into_string(),
-        msg: to_binary(&astroport::generator::ExecuteMsg::ClaimRewards {
-            lp_tokens: vec![passive_lp_token_address.into_string()],
-        })?,
-        funds: vec![],
-    }
-    .into();
-
-    // swap ASTRO into Luna
-    // first, get the address of the pool from Astroport
-    // then, perform the swap, and finally perform passive strategy with the gained luna
-    let astro_luna_pool_address: astroport::asset::PairInfo = deps.querier.query_wasm_smart(
-        state.astro_factory_address,
-        &astroport::factory::QueryMsg::Pair {
-            asset_infos: [
-                astroport::asset::AssetInfo::Token {
-                    contract_addr: astro_token_address,
-                },
-                astroport::asset::AssetInfo::NativeToken {
-                    denom: LUNA_DENOM.to_string(),
-                },
-            ],
-        },
-    )?;
+    // Deposit luna into the passive strategies
+    let deposit_msgs = deposit_into_strategies(deps.as_ref(), &env, luna_return)?;
 
-    let swap_simulation_response = astroport::querier::simulate(
-        &deps.querier,
-        astro_luna_pool_address.contract_addr.clone(),
-        &astro_pending,
-    )?;
-    let swap_luna_return = swap_simulation_response.return_amount;
-
-    let swap_astro_message = WasmMsg::Execute {
-        contract_addr: astro_luna_pool_address.contract_addr.into_string(),
-        msg: to_binary(&astroport::pair::ExecuteMsg::Swap {
-            offer_asset: astro_pending.clone(),
-            belief_price: None,
-            max_spread: None,
-            to: None,
-        })?,
-        funds: vec![],
+    Ok(response.add_messages(deposit_msgs).add_attributes(vec![
+        attr("harvest_id", harvest_id.to_string()),
+        attr("total_luna_return", luna_return),
+    ]))
+}
+
+/// Sets how the passive strategy token rewards are swapped for luna
+pub fn set_harvest_config(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    router_address: Option<String>,
+    swap_route: Vec<AssetInfo>,
+    dust_threshold: Uint128,
+) -> VaultResult<Response> {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let router_address = router_address
+        .map(|router_address| deps.api.addr_validate(&router_address))
+        .transpose()?;
+    if !swap_route.is_empty() && router_address.is_none() {
+        return Err(LunaVaultError::HarvestRouterNotSet {});
+    }
+    for asset_info in swap_route.iter() {
+        if let AssetInfo::Token { contract_addr } = asset_info {
+            deps.api.addr_validate(contract_addr)?;
+        }
     }
-    .into();
 
-    response = response.add_messages([withdraw_rewards_msg, swap_astro_message]);
+    let harvest_config = HarvestConfig {
+        router_address,
+        swap_route,
+        dust_threshold,
+    };
+    HARVEST_CONFIG.save(deps.storage, &harvest_config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_harvest_config"),
+        attr(
+            "swap_route_hops",
+            harvest_config.swap_route.len().to_string(),
+        ),
+        attr("dust_threshold", dust_threshold),
+    ]))
+}
 
-    // Deposit luna into passive strategy
-    response = deposit_passive_strategy(
-        &deps.as_ref(),
-        swap_luna_return,
-        state.bluna_address,
-        &state.astro_lp_address,
-        response,
-    )?;
+/// Sets the passive strategies of the vault along with their target weights
+pub fn set_strategies(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    strategies: Vec<StrategyInfo>,
+) -> VaultResult<Response> {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let strategies = validate_strategies(deps.api, strategies)?;
+    STRATEGIES.save(deps.storage, &strategies)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_strategies"),
+        attr("strategies", strategies.len().to_string()),
+    ]))
+}
+
+/// Sets the deposit caps of the vault
+pub fn set_deposit_caps(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    tvl_cap: Option<Uint128>,
+    per_address_cap: Option<Uint128>,
+) -> VaultResult<Response> {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let previous_caps = DEPOSIT_CAPS.may_load(deps.storage)?.unwrap_or_default();
+    let deposit_caps = DepositCaps {
+        tvl_cap,
+        per_address_cap,
+    };
+    DEPOSIT_CAPS.save(deps.storage, &deposit_caps)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_deposit_caps"),
+        attr("new tvl cap", format!("{:?}", deposit_caps.tvl_cap)),
+        attr("previous tvl cap", format!("{:?}", previous_caps.tvl_cap)),
+        attr(
+            "new per address cap",
+            format!("{:?}", deposit_caps.per_address_cap),
+        ),
+        attr(
+            "previous per address cap",
+            format!("{:?}", previous_caps.per_address_cap),
+        ),
+    ]))
+}
+
+/// Sets the flashloan fee of the borrower, or removes its override if none
+pub fn set_fee_override(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    borrower: String,
+    fee: Option<LoanFee>,
+) -> VaultResult<Response> {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let borrower = deps.api.addr_validate(&borrower)?;
+    fees::set_fee_override(deps.storage, &borrower, fee.clone())?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_fee_override"),
+        attr("borrower", borrower),
+        attr("fee", format!("{:?}", fee)),
+    ]))
+}
+
+/// Sets the flashloan fee tiers of the non-whitelisted borrowers
+pub fn set_fee_tiers(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    tiers: Vec<FeeTier>,
+) -> VaultResult<Response> {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let tier_count = tiers.len();
+    fees::set_fee_tiers(deps.storage, tiers)?;
 
-    Ok(response.add_attributes(vec![
-        attr("action", "swap_rewards"),
-        attr("astro_swapped", astro_pending.amount),
-        attr("luna_return", swap_luna_return),
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_fee_tiers"),
+        attr("tiers", tier_count.to_string()),
     ]))
 }
 
+/// Moves luna between the passive strategies so they match their target weights
+pub fn rebalance(deps: DepsMut, env: Env, msg_info: MessageInfo) -> VaultResult<Response> {
+    let state = STATE.load(deps.storage)?;
+    // Check if sender is in whitelist, i.e. bot or bot proxy
+    if !state.whitelisted_contracts.contains(&msg_info.sender) {
+        return Err(LunaVaultError::NotWhitelisted {});
+    }
+
+    let rates = query_hub_exchange_rates(deps.as_ref())?;
+    let strategies = query_strategies_values(deps.as_ref(), &env, &rates)?;
+    let strategies_value_in_luna = strategies
+        .iter()
+        .fold(Uint128::zero(), |acc, (_, value)| acc + value.value_in_luna);
+
+    // withdraw the excess from the strategies above their target first, so the luna is available
+    // to be deposited into the strategies below their target
+    let mut withdraw_msgs = vec![];
+    let mut luna_returned = Uint128::zero();
+    let mut deficits = vec![];
+    for (strategy, value) in strategies {
+        let target_value = strategy.target_weight * strategies_value_in_luna;
+        let passive_strategy = load_strategy(deps.as_ref(), &env, &strategy.strategy)?;
+        if value.value_in_luna > target_value {
+            let withdrawal =
+                passive_strategy.withdraw(deps.as_ref(), value.value_in_luna - target_value)?;
+            withdraw_msgs.extend(withdrawal.messages);
+            luna_returned += withdrawal.luna_return;
+        } else if value.value_in_luna < target_value {
+            deficits.push((passive_strategy, target_value - value.value_in_luna));
+        }
+    }
+
+    // only the luna returned by the withdrawals is deposited, which can fall short of the deficits
+    // because of the slippage
+    let mut deposit_msgs = vec![];
+    for (passive_strategy, deficit) in deficits {
+        let deposit_amount = deficit.min(luna_returned);
+        if deposit_amount.is_zero() {
+            break;
+        }
+        deposit_msgs.extend(passive_strategy.deposit(deps.as_ref(), deposit_amount)?);
+        luna_returned = luna_returned.checked_sub(deposit_amount)?;
+    }
+
+    Ok(Response::new()
+        .add_messages(withdraw_msgs)
+        .add_messages(deposit_msgs)
+        .add_attributes(vec![
+            attr("action", "rebalance"),
+            attr("strategies_value_in_luna", strategies_value_in_luna),
+        ]))
+}
+
 pub(crate) fn handle_unbond_handler_msg(
     deps: DepsMut,
     info: MessageInfo,
@@ -846,15 +1142,19 @@ pub(crate) fn handle_unbond_handler_msg(
             let previous_owner = deps.api.addr_validate(&previous_owner)?;
 
             // make sure an assigned handler sent this message
-            let assigned_handler = UNBOND_HANDLERS_ASSIGNED
-                .may_load(deps.storage, previous_owner.clone())?
+            let (owner, ticket_id) = UNBOND_HANDLERS_ASSIGNED
+                .may_load(deps.storage, unbond_handler.clone())?
                 .ok_or(LunaVaultError::UnbondHandlerNotAssigned {})?;
-            if assigned_handler != info.sender || assigned_handler != unbond_handler {
+            if owner != previous_owner {
+                return Err(LunaVaultError::UnbondHandlerNotAssigned {});
+            }
+            if unbond_handler != info.sender {
                 return Err(LunaVaultError::UnbondHandlerReleaseMismatch {});
             }
 
-            // remove handler from assigned handlers map
-            UNBOND_HANDLERS_ASSIGNED.remove(deps.storage, previous_owner);
+            // the ticket is claimed, remove it along with the handler assignment
+            UNBOND_TICKETS.remove(deps.storage, (previous_owner, U64Key::new(ticket_id)));
+            UNBOND_HANDLERS_ASSIGNED.remove(deps.storage, unbond_handler.clone());
 
             // clear the expiration time for the unbond handler in state
             UNBOND_HANDLER_EXPIRATION_TIMES.remove(deps.storage, unbond_handler.clone());
@@ -867,6 +1167,7 @@ pub(crate) fn handle_unbond_handler_msg(
             Ok(Response::new().add_attributes(vec![
                 attr("action", "after_unbond_handler_released"),
                 attr("unbond_handler", unbond_handler.to_string()),
+                attr("ticket_id", ticket_id.to_string()),
             ]))
         }
     }
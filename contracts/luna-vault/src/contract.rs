@@ -7,6 +7,7 @@ use cw20::MinterResponse;
 use protobuf::Message;
 use semver::Version;
 use serde::Serialize;
+use signed_integer::SignedInt;
 use terraswap::asset::AssetInfo;
 use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
 
@@ -15,13 +16,17 @@ use white_whale::deposit_info::DepositInfo;
 use white_whale::fee::{Fee, VaultFee};
 use white_whale::luna_vault::msg::VaultQueryMsg as QueryMsg;
 use white_whale::luna_vault::msg::*;
+use white_whale::vault::fees;
+use white_whale::vault::flashloan::ProfitCheck;
+use white_whale::vault::pause;
+use white_whale::vault::performance_fee;
 
 use crate::commands::set_fee;
 use crate::error::LunaVaultError;
 use crate::helpers::{get_lp_token_address, unwrap_data, unwrap_reply};
 use crate::pool_info::PoolInfoRaw;
 use crate::response::MsgInstantiateContractResponse;
-use crate::state::{ProfitCheck, State, ADMIN, DEPOSIT_INFO, FEE, POOL_INFO, PROFIT, STATE};
+use crate::state::{State, ADMIN, DEPOSIT_INFO, FEE, POOL_INFO, PROFIT, STATE};
 use crate::{commands, flashloan, helpers, queries, replies};
 
 const INSTANTIATE_REPLY_ID: u64 = 1u64;
@@ -121,6 +126,9 @@ pub fn instantiate(
     let profit = ProfitCheck {
         last_balance: Uint128::zero(),
         last_profit: Uint128::zero(),
+        last_value: Uint128::zero(),
+        last_pnl: SignedInt::zero(),
+        cumulative_pnl: SignedInt::zero(),
     };
     PROFIT.save(deps.storage, &profit)?;
 
@@ -161,13 +169,16 @@ pub fn instantiate(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> VaultResult<Response> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> VaultResult<Response> {
     let version: Version = CONTRACT_VERSION.parse()?;
     let storage_version: Version = get_contract_version(deps.storage)?.version.parse()?;
 
     if storage_version < version {
         set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     }
+
+    // turn the unbond handlers assigned before the unbond tickets were introduced into tickets
+    helpers::migrate_legacy_unbond_handlers(deps, &env)?;
     Ok(Response::default())
 }
 
@@ -184,23 +195,65 @@ pub fn execute(
             commands::provide_liquidity(deps, env, info, asset)
         }
         ExecuteMsg::WithdrawUnbonded {} => commands::withdraw_unbonded(deps, info, false, None),
+        ExecuteMsg::ClaimUnbondTicket { ticket_id } => {
+            commands::claim_unbond_ticket(deps, info, ticket_id)
+        }
+        ExecuteMsg::ClaimReservedUnbonds {} => commands::claim_reserved_unbonds(deps, env, info),
         ExecuteMsg::WithdrawUnbondedFlashloan {} => {
             commands::withdraw_unbonded_from_flashloan(deps, info, env)
         }
+        ExecuteMsg::SetPerformanceFee { fee } => {
+            commands::set_performance_fee(deps, env, info, fee)
+        }
         ExecuteMsg::SetAdmin { admin } => commands::set_admin(deps, info, admin),
         ExecuteMsg::SetFee {
             flash_loan_fee,
             treasury_fee,
             commission_fee,
-        } => set_fee(deps, info, flash_loan_fee, treasury_fee, commission_fee),
+            instant_withdraw_fee,
+        } => set_fee(
+            deps,
+            info,
+            flash_loan_fee,
+            treasury_fee,
+            commission_fee,
+            instant_withdraw_fee,
+        ),
         ExecuteMsg::AddToWhitelist { contract_addr } => {
             commands::add_to_whitelist(deps, info, contract_addr)
         }
         ExecuteMsg::RemoveFromWhitelist { contract_addr } => {
             commands::remove_from_whitelist(deps, info, contract_addr)
         }
+        ExecuteMsg::SetFeeOverride { borrower, fee } => {
+            commands::set_fee_override(deps, info, borrower, fee)
+        }
+        ExecuteMsg::SetFeeTiers { tiers } => commands::set_fee_tiers(deps, info, tiers),
+        ExecuteMsg::SetGuardian { guardian } => {
+            Ok(pause::set_guardian(deps, &ADMIN, info, guardian)?)
+        }
+        ExecuteMsg::Pause { operation, reason } => {
+            Ok(pause::pause(deps, &ADMIN, info, operation, reason)?)
+        }
+        ExecuteMsg::Unpause { operation } => Ok(pause::unpause(deps, &ADMIN, info, operation)?),
         ExecuteMsg::FlashLoan { payload } => flashloan::handle_flashloan(deps, env, info, payload),
-        ExecuteMsg::SwapRewards {} => commands::swap_rewards(deps, env, info),
+        ExecuteMsg::SwapRewards {
+            max_spread,
+            minimum_receive,
+        } => commands::swap_rewards(deps, env, info, max_spread, minimum_receive),
+        ExecuteMsg::SetHarvestConfig {
+            router_address,
+            swap_route,
+            dust_threshold,
+        } => commands::set_harvest_config(deps, info, router_address, swap_route, dust_threshold),
+        ExecuteMsg::SetStrategies { strategies } => {
+            commands::set_strategies(deps, info, strategies)
+        }
+        ExecuteMsg::Rebalance {} => commands::rebalance(deps, env, info),
+        ExecuteMsg::SetDepositCaps {
+            tvl_cap,
+            per_address_cap,
+        } => commands::set_deposit_caps(deps, info, tvl_cap, per_address_cap),
         ExecuteMsg::UpdateState {
             bluna_address,
             cluna_address,
@@ -227,7 +280,7 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> VaultResult<Response> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> VaultResult<Response> {
     let res = unwrap_reply(msg.clone())?;
     let data = unwrap_data(res.clone())?;
 
@@ -240,7 +293,7 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> VaultResult<Response> {
         INSTANTIATE_REPLY_ID => replies::after_token_instantiation(deps, response),
         INSTANTIATE_UNBOND_HANDLER_REPLY_ID => {
             let events = res.events;
-            replies::after_unbond_handler_instantiation(deps, response, events)
+            replies::after_unbond_handler_instantiation(deps, env, response, events)
         }
         _ => Ok(Response::default()),
     }
@@ -253,12 +306,16 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> VaultResult<Binary> {
         QueryMsg::PoolState {} => to_binary(&queries::try_query_pool_state(env, deps)?),
         QueryMsg::State {} => to_binary(&queries::query_state(deps)?),
         QueryMsg::Fees {} => to_binary(&queries::query_fees(deps)?),
+        QueryMsg::PerformanceFee {} => {
+            to_binary(&performance_fee::query_performance_fee(deps.storage)?)
+        }
         QueryMsg::VaultValue {} => to_binary(&queries::query_total_value(env, deps)?),
         QueryMsg::EstimateWithdrawFee { amount } => {
             to_binary(&queries::estimate_withdraw_fee(deps, amount)?)
         }
         QueryMsg::LastBalance {} => to_binary(&queries::query_last_balance(deps)?),
         QueryMsg::LastProfit {} => to_binary(&queries::query_last_profit(deps)?),
+        QueryMsg::Pnl {} => to_binary(&queries::query_pnl(deps)?),
         QueryMsg::WithdrawableUnbonded { address } => {
             to_binary(&queries::query_withdrawable_unbonded(deps, address)?)
         }
@@ -268,6 +325,61 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> VaultResult<Binary> {
         QueryMsg::UnbondHandlerExpirationTime {} => to_binary(
             &queries::query_unbond_handler_expiration_time(deps.storage)?,
         ),
+        QueryMsg::ReservedUnbonds { address } => {
+            to_binary(&queries::query_reserved_unbonds(deps, env, address)?)
+        }
+        QueryMsg::DepositCaps {} => to_binary(&queries::query_deposit_caps(deps)?),
+        QueryMsg::HarvestConfig {} => to_binary(&queries::query_harvest_config(deps)?),
+        QueryMsg::HarvestHistory { start_after, limit } => {
+            to_binary(&queries::query_harvest_history(deps, start_after, limit)?)
+        }
+        QueryMsg::SimulateWithdraw { amount } => {
+            to_binary(&queries::query_simulate_withdraw(deps, env, amount)?)
+        }
+        QueryMsg::UnbondTickets {
+            address,
+            start_after,
+            limit,
+        } => to_binary(&queries::query_unbond_tickets(
+            deps,
+            address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AssignedUnbondHandlers { start_after, limit } => to_binary(
+            &queries::query_assigned_unbond_handlers(deps, start_after, limit)?,
+        ),
+        QueryMsg::AvailableUnbondHandlers { start_after, limit } => to_binary(
+            &queries::query_available_unbond_handlers(deps, start_after, limit)?,
+        ),
+        QueryMsg::ExpiredUnbondHandlers {
+            expired_at,
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&queries::query_expired_unbond_handlers(
+            deps,
+            env,
+            expired_at,
+            owner,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::SharePrice { timestamp } => {
+            to_binary(&queries::query_share_price(deps, env, timestamp)?)
+        }
+        QueryMsg::Apr { window } => to_binary(&queries::query_apr(deps, env, window)?),
+        QueryMsg::Snapshots { start_after, limit } => {
+            to_binary(&queries::query_snapshots(deps, start_after, limit)?)
+        }
+        QueryMsg::EffectiveFee { borrower, amount } => {
+            to_binary(&queries::query_effective_fee(deps, borrower, amount)?)
+        }
+        QueryMsg::FeeTiers {} => to_binary(&fees::query_fee_tiers(deps.storage)?),
+        QueryMsg::BorrowerStats { borrower } => {
+            to_binary(&queries::query_borrower_stats(deps, borrower)?)
+        }
+        QueryMsg::PauseInfo {} => to_binary(&pause::query_pause_info(deps)?),
     }
 }
 
@@ -4,6 +4,9 @@ use thiserror::Error;
 use cosmwasm_std::{DivideByZeroError, OverflowError, StdError};
 use cw_controllers::AdminError;
 
+use white_whale::vault::flashloan::FlashLoanError;
+use white_whale::vault::pause::{PausableOperation, PauseError};
+
 #[derive(Error, Debug, PartialEq)]
 pub enum LunaVaultError {
     #[error("{0}")]
@@ -45,6 +48,9 @@ pub enum LunaVaultError {
     #[error("The provided fee is invalid")]
     InvalidFee {},
 
+    #[error("The fee tiers need strictly ascending minimum loans.")]
+    InvalidFeeTiers {},
+
     #[error("The requesting contract is not whitelisted.")]
     NotWhitelisted {},
 
@@ -84,8 +90,50 @@ pub enum LunaVaultError {
     #[error("Couldn't get unbond handler.")]
     UnbondHandlerError {},
 
+    #[error("There are no unbonds reserved for the given address.")]
+    NoUnbondReservations {},
+
+    #[error("None of the reserved unbonds have been released by the hubs yet.")]
+    NoClaimableReservedUnbonds {},
+
+    #[error("The passive strategies target weights must add up to 1.")]
+    InvalidStrategyWeights {},
+
+    #[error("The same passive strategy can't be set more than once.")]
+    DuplicatedStrategy {},
+
+    #[error("There's no unbond ticket with the given id for the sender.")]
+    UnbondTicketNotFound {},
+
+    #[error("None of the unbond tickets have luna released by the hubs yet.")]
+    NoClaimableUnbondTickets {},
+
+    #[error("The max spread can't be greater than 1.")]
+    InvalidMaxSpread {},
+
+    #[error("The rewards would return less luna than the minimum receive.")]
+    HarvestBelowMinimumReceive {},
+
+    #[error("A router address is needed to swap the rewards along a swap route.")]
+    HarvestRouterNotSet {},
+
+    #[error("Deposit exceeds the vault's TVL cap.")]
+    TvlCapExceeded {},
+
+    #[error("Deposit exceeds the per-address deposit cap.")]
+    AddressCapExceeded {},
+
     #[error("Last balance is non-zero, you can only call this function once.")]
     Nonzero {},
+
+    #[error("No snapshot of the vault was recorded yet.")]
+    NoSnapshots {},
+
+    #[error("The vault {operation} are paused: {reason}")]
+    Paused {
+        operation: PausableOperation,
+        reason: String,
+    },
 }
 
 impl From<semver::Error> for LunaVaultError {
@@ -94,6 +142,33 @@ impl From<semver::Error> for LunaVaultError {
     }
 }
 
+impl From<FlashLoanError> for LunaVaultError {
+    fn from(err: FlashLoanError) -> Self {
+        match err {
+            FlashLoanError::Std(err) => Self::Std(err),
+            FlashLoanError::NotWhitelisted {} => Self::NotWhitelisted {},
+            FlashLoanError::Broke {} => Self::Broke {},
+            FlashLoanError::Nonzero {} => Self::Nonzero {},
+            FlashLoanError::CancelLosingTrade {} => Self::CancelLosingTrade {},
+            FlashLoanError::NotCallback {} => Self::NotCallback {},
+            FlashLoanError::DepositDuringLoan {} => Self::DepositDuringLoan {},
+            FlashLoanError::InvalidFee {} => Self::InvalidFee {},
+            FlashLoanError::InvalidFeeTiers {} => Self::InvalidFeeTiers {},
+        }
+    }
+}
+
+impl From<PauseError> for LunaVaultError {
+    fn from(err: PauseError) -> Self {
+        match err {
+            PauseError::Std(err) => Self::Std(err),
+            PauseError::Admin(err) => Self::Admin(err),
+            PauseError::Unauthorized {} => Self::Unauthorized {},
+            PauseError::Paused { operation, reason } => Self::Paused { operation, reason },
+        }
+    }
+}
+
 impl LunaVaultError {
     pub fn generic_err(msg: impl Into<String>) -> Self {
         Self::Std(StdError::GenericErr {
@@ -1,269 +1,178 @@
 use core::result::Result::Err;
 
-use cosmwasm_std::{CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, Uint128, WasmMsg};
-use terraswap::asset::{Asset, AssetInfo};
+use cosmwasm_std::{
+    CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint128,
+};
+use terraswap::asset::AssetInfo;
+use terraswap::querier::query_supply;
 
-use white_whale::anchor::anchor_bluna_unbond_msg;
 use white_whale::denom::LUNA_DENOM;
 use white_whale::luna_vault::msg::{CallbackMsg, FlashLoanPayload};
-use white_whale::memory::queries::query_contract_from_mem;
-use white_whale::memory::{ANCHOR_BLUNA_HUB_ID, PRISM_CLUNA_HUB_ID};
-use white_whale::prism::prism_cluna_unbond_msg;
-use white_whale::tax::into_msg_without_tax;
+use white_whale::vault::flashloan::{
+    self as engine, FlashLoanConfig, FlashLoanVault, ProfitCheck, TradeSettlement,
+};
+use white_whale::vault::pause::{assert_not_paused, PausableOperation};
+use white_whale::vault::performance_fee::{charge_performance_fee, performance_fee_msg};
 
-use crate::commands::{deposit_passive_strategy, withdraw_passive_strategy};
 use crate::contract::VaultResult;
 use crate::error::LunaVaultError;
-use crate::helpers::{compute_total_value, get_lp_token_address};
+use crate::helpers::{
+    compute_total_value, query_hub_exchange_rates, query_released_reserved_unbonds, record_snapshot,
+};
 use crate::pool_info::PoolInfoRaw;
 use crate::state::{FEE, POOL_INFO, PROFIT, STATE};
-
-const ROUNDING_ERR_COMPENSATION: u32 = 10u32;
-
-pub fn handle_flashloan(
-    mut deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    payload: FlashLoanPayload,
-) -> VaultResult<Response> {
-    let state = STATE.load(deps.storage)?;
-    let fees = FEE.load(deps.storage)?;
-    let whitelisted_contracts = state.whitelisted_contracts;
-    let whitelisted: bool;
-
-    // Check if sender is whitelisted
-    if !whitelisted_contracts.contains(&deps.api.addr_validate(&info.sender.to_string())?) {
-        // Check if non-whitelisted are allowed to borrow
-        if state.allow_non_whitelisted {
-            whitelisted = false;
-        } else {
-            return Err(LunaVaultError::NotWhitelisted {});
-        }
-    } else {
-        whitelisted = true;
+use crate::strategies::{deposit_into_strategies, withdraw_from_strategies};
+
+/// The luna vault lends its luna, withdrawing it from the passive strategies when needed
+pub struct LunaVault;
+
+impl FlashLoanVault for LunaVault {
+    type Error = LunaVaultError;
+
+    fn config(&self, deps: Deps) -> StdResult<FlashLoanConfig> {
+        let state = STATE.load(deps.storage)?;
+        let fees = FEE.load(deps.storage)?;
+        Ok(FlashLoanConfig {
+            whitelisted_contracts: state.whitelisted_contracts,
+            allow_non_whitelisted: state.allow_non_whitelisted,
+            flash_loan_fee: fees.flash_loan_fee,
+            commission_fee: fees.commission_fee,
+            treasury_addr: fees.treasury_addr,
+        })
     }
 
-    // Do we have enough funds?
-    let pool_info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
-    let total_value = compute_total_value(&env, deps.as_ref(), &pool_info)?.total_value_in_luna;
-    let requested_asset = payload.requested_asset;
-
-    // check if the request_asset is uluna
-    match requested_asset.info.clone() {
-        AssetInfo::Token { .. } => return Err(LunaVaultError::NotLunaToken {}),
-        AssetInfo::NativeToken { denom } => {
-            if denom != LUNA_DENOM {
-                return Err(LunaVaultError::NotLunaToken {});
-            }
-        }
-    };
-
-    // Max tax buffer will be 2 transfers of the borrowed assets
-    // Passive Strategy -> Vault -> Caller
-    let tax_buffer = Uint128::from(2u32) * requested_asset.compute_tax(&deps.querier)?
-        + Uint128::from(ROUNDING_ERR_COMPENSATION);
+    fn load_profit_check(&self, storage: &dyn Storage) -> StdResult<ProfitCheck> {
+        PROFIT.load(storage)
+    }
 
-    if total_value < requested_asset.amount + tax_buffer {
-        return Err(LunaVaultError::Broke {});
+    fn save_profit_check(
+        &self,
+        storage: &mut dyn Storage,
+        profit_check: &ProfitCheck,
+    ) -> StdResult<()> {
+        PROFIT.save(storage, profit_check)
     }
-    // Init response
-    let mut response = Response::new().add_attribute("Action", "Flashloan");
 
-    // withdraw from passive strategy, initially defined as the bLuna-Luna LP. This returns the requested
-    // amount of luna and deposits the remaining bluna shares taken from the LP directly into the single-sided Astroport LP
-    withdraw_passive_strategy(
-        &deps.as_ref(),
-        requested_asset.amount,
-        AssetInfo::NativeToken {
+    fn base_asset_info(&self, _deps: Deps) -> VaultResult<AssetInfo> {
+        Ok(AssetInfo::NativeToken {
             denom: LUNA_DENOM.to_string(),
-        },
-        &get_lp_token_address(&deps.as_ref(), state.astro_lp_address.clone())?,
-        &state.astro_lp_address,
-        response.clone(),
-    )?;
-
-    // If caller not whitelisted, calculate flashloan fee
-    let loan_fee: Uint128 = if whitelisted {
-        Uint128::zero()
-    } else {
-        fees.flash_loan_fee.compute(requested_asset.amount)
-    };
-
-    // NOTE: Forget the whitelist and just charge everyone, why should anyone get free flashloans ?
-    fees.flash_loan_fee.compute(requested_asset.amount);
-    // Construct transfer of funds msg, tax is accounted for by buffer
-    let loan_msg = into_msg_without_tax(requested_asset, info.sender.clone())?;
-    response = response.add_message(loan_msg);
-
-    // Construct return call with received binary
-    let return_call = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: info.sender.into(),
-        msg: payload.callback,
-        funds: vec![],
-    });
-
-    response = response.add_message(return_call);
-
-    // Sets the current value of the vault and save logs
-    response = response.add_attributes(before_trade(deps.branch(), env.clone())?);
-
-    // Call encapsulate function
-    encapsulate_payload(deps.as_ref(), env, response, loan_fee)
-}
-
-/// Resets last trade and sets current UST balance of caller
-pub fn before_trade(deps: DepsMut, env: Env) -> Result<Vec<(&str, String)>, LunaVaultError> {
-    let mut profit_check = PROFIT.load(deps.storage)?;
-
-    // last_balance call can not be reset until after the loan.
-    if profit_check.last_balance != Uint128::zero() {
-        return Err(LunaVaultError::Nonzero {});
+        })
     }
 
-    profit_check.last_profit = Uint128::zero();
-
-    // Index 0 = total_value
-    let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
-    profit_check.last_balance =
-        compute_total_value(&env, deps.as_ref(), &info)?.total_value_in_luna;
-    PROFIT.save(deps.storage, &profit_check)?;
-
-    Ok(vec![(
-        "value before trade: ",
-        profit_check.last_balance.to_string(),
-    )])
-}
-
-/// Checks if balance increased after the trade
-#[allow(unreachable_code)]
-pub fn after_trade(
-    deps: DepsMut,
-    env: Env,
-    msg_info: MessageInfo,
-    loan_fee: Uint128,
-) -> VaultResult<Response> {
-    let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
-
-    let total_value = compute_total_value(&env, deps.as_ref(), &info)?;
-    let mut conf = PROFIT.load(deps.storage)?;
-
-    // Check if total_value_in_luna increased with expected fee, otherwise cancel everything. Assuming 1 bluna = 1 cluna = 1 luna
-    if total_value.total_value_in_luna < conf.last_balance + loan_fee {
-        return Err(LunaVaultError::CancelLosingTrade {});
+    fn assert_loan_asset(&self, _deps: Deps, asset_info: &AssetInfo) -> VaultResult<()> {
+        // check if the request_asset is uluna
+        match asset_info {
+            AssetInfo::NativeToken { denom } if denom == LUNA_DENOM => Ok(()),
+            _ => Err(LunaVaultError::NotLunaToken {}),
+        }
     }
 
-    let profit = total_value.total_value_in_luna - conf.last_balance;
-
-    conf.last_profit = profit;
-    conf.last_balance = Uint128::zero();
-    PROFIT.save(deps.storage, &conf)?;
-
-    let state = STATE.load(deps.storage)?;
-    let mut response = Response::default();
-
-    // check in which asset the flashloan was paid back
-    if total_value.luna_amount > Uint128::zero() {
-        // flashloan was paid back in luna, deposit back to passive strategy
-        response = deposit_passive_strategy(
-            &deps.as_ref(),
-            total_value.luna_amount,
-            state.bluna_address.clone(),
-            &state.astro_lp_address,
-            response.clone(),
-        )?;
+    /// bluna and cluna are valued at the Anchor and Prism hub exchange rates
+    fn total_value(&self, deps: Deps, env: &Env) -> VaultResult<Uint128> {
+        let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+        Ok(compute_total_value(env, deps, &info)?.total_value_in_luna)
     }
 
-    if total_value.bluna_value_in_luna > Uint128::zero() {
-        // flashloan was paid back in bluna, burn it on Anchor
-
-        // disallow to pay flashloans back in bluna for now
-        return Err(LunaVaultError::generic_err(
-            "Can't pay back flashloan in anything but uluna",
-        ));
-        let bluna_hub_address =
-            query_contract_from_mem(deps.as_ref(), &state.memory_address, ANCHOR_BLUNA_HUB_ID)?;
-
-        let bluna_unbond_msg = anchor_bluna_unbond_msg(
-            state.bluna_address.clone(),
-            bluna_hub_address,
-            total_value.bluna_value_in_luna,
-        )?;
-        response = response.add_message(bluna_unbond_msg);
+    fn prepare_loan(
+        &self,
+        deps: Deps,
+        env: &Env,
+        amount: Uint128,
+        _tax_buffer: Uint128,
+    ) -> VaultResult<Response> {
+        // withdraw the requested amount of luna from the passive strategies
+        let rates = query_hub_exchange_rates(deps)?;
+        Ok(Response::new()
+            .add_messages(withdraw_from_strategies(deps, env, &rates, amount)?.messages))
     }
-    if total_value.cluna_value_in_luna > Uint128::zero() {
-        // flashloan was paid back in cluna, burn it on Prism
 
-        // disallow to pay flashloans back in bluna for now
-        return Err(LunaVaultError::generic_err(
-            "Can't pay back flashloan in anything but uluna",
-        ));
-        let cluna_hub_address =
-            query_contract_from_mem(deps.as_ref(), &state.memory_address, PRISM_CLUNA_HUB_ID)?;
+    fn settle_trade(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        settlement: &TradeSettlement,
+    ) -> VaultResult<Response> {
+        let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+        let total_value = compute_total_value(env, deps.as_ref(), &info)?;
+        let mut response = Response::default();
+
+        // disallow to pay flashloans back in bluna or cluna for now
+        if !total_value.bluna_amount.is_zero() || !total_value.cluna_amount.is_zero() {
+            return Err(LunaVaultError::generic_err(
+                "Can't pay back flashloan in anything but uluna",
+            ));
+        }
+
+        // luna released by the hubs for reserved unbonds stays liquid so users can claim it, as
+        // does the refund of the borrower
+        let released_reserved_unbonds = query_released_reserved_unbonds(deps.as_ref(), env)?;
+        let luna_to_deposit = total_value
+            .luna_amount
+            .saturating_sub(released_reserved_unbonds + settlement.refund);
+
+        // check in which asset the flashloan was paid back
+        if luna_to_deposit > Uint128::zero() {
+            // flashloan was paid back in luna, deposit back to the passive strategies
+            response = response.add_messages(deposit_into_strategies(
+                deps.as_ref(),
+                env,
+                luna_to_deposit,
+            )?);
+        }
 
-        let cluna_unbond_msg = prism_cluna_unbond_msg(
-            state.cluna_address,
-            cluna_hub_address,
-            total_value.cluna_value_in_luna,
+        // the commission on the profit and the refund leave the vault
+        let vault_value = settlement
+            .total_value
+            .checked_sub(settlement.commission + settlement.refund)?;
+        let total_share = query_supply(&deps.querier, info.liquidity_token.clone())?;
+        // the performance fee is paid in vLuna
+        let performance_fee = charge_performance_fee(deps.storage, vault_value, total_share)?;
+        // the profit of the trade is part of the PnL since the last event
+        record_snapshot(
+            deps.storage,
+            env,
+            vault_value,
+            vault_value,
+            total_share.checked_add(performance_fee)?,
+            settlement.profit,
         )?;
-        response = response.add_message(cluna_unbond_msg);
-    }
+        if !performance_fee.is_zero() {
+            let treasury_addr = FEE.load(deps.storage)?.treasury_addr;
+            response = response
+                .add_message(performance_fee_msg(
+                    &info.liquidity_token,
+                    &treasury_addr,
+                    performance_fee,
+                )?)
+                .add_attribute("performance_fee", performance_fee.to_string());
+        }
 
-    let commission_response = send_commissions(deps.as_ref(), msg_info, profit)?;
-    Ok(response
-        // Send commission of profit to Treasury
-        .add_submessages(commission_response.messages)
-        .add_attributes(commission_response.attributes)
-        .add_attribute(
+        Ok(response.add_attribute(
             "total_value_in_luna",
             total_value.total_value_in_luna.to_string(),
         ))
-}
-
-fn send_commissions(deps: Deps, _info: MessageInfo, profit: Uint128) -> VaultResult<Response> {
-    let fees = FEE.load(deps.storage)?;
-
-    let commission_amount = fees.commission_fee.compute(profit);
-
-    // Construct commission msg
-    let refund_asset = Asset {
-        info: AssetInfo::NativeToken {
-            denom: LUNA_DENOM.to_string(),
-        },
-        amount: commission_amount,
-    };
-    let commission_msg = refund_asset.into_msg(&deps.querier, fees.treasury_addr)?;
+    }
 
-    Ok(Response::new()
-        .add_attribute("commission_amount", commission_amount.to_string())
-        .add_message(commission_msg))
+    fn after_trade_msg(&self, env: &Env, loan_fee: Uint128) -> StdResult<CosmosMsg> {
+        CallbackMsg::AfterTrade { loan_fee }.to_cosmos_msg(&env.contract.address)
+    }
 }
 
-/// Helper method which encapsulates the requested funds.
-/// This function prevents callers from doing unprofitable actions
-/// with the vault funds and makes sure the funds are returned by
-/// the borrower.
-pub fn encapsulate_payload(
-    _deps: Deps,
+pub fn handle_flashloan(
+    deps: DepsMut,
     env: Env,
-    response: Response,
-    loan_fee: Uint128,
+    info: MessageInfo,
+    payload: FlashLoanPayload,
 ) -> VaultResult<Response> {
-    let total_response: Response = Response::new().add_attributes(response.attributes);
-
-    // Callback for after the loan
-    let after_trade = CallbackMsg::AfterTrade { loan_fee }.to_cosmos_msg(&env.contract.address)?;
-
-    Ok(total_response
-        // Add response that:
-        // 1. Withdraws funds from Passive Strategy if needed
-        // 2. Sends funds to the borrower
-        // 3. Calls the borrow contract through the provided callback msg
-        .add_submessages(response.messages)
-        // After borrower actions, deposit the received funds back into
-        // Passive Strategy if applicable
-        // Call profit-check to cancel the borrow if
-        // no profit is made.
-        .add_message(after_trade))
+    assert_not_paused(deps.storage, PausableOperation::FlashLoans)?;
+    engine::handle_flashloan(
+        &LunaVault,
+        deps,
+        env,
+        info,
+        payload.requested_asset,
+        payload.callback,
+    )
 }
 
 /// Handles the callback after using a flashloan
@@ -274,10 +183,10 @@ pub fn _handle_callback(
     msg: CallbackMsg,
 ) -> VaultResult<Response> {
     // Callback functions can only be called this contract itself
-    if info.sender != env.contract.address {
-        return Err(LunaVaultError::NotCallback {});
-    }
+    engine::assert_callback(&env, &info)?;
     match msg {
-        CallbackMsg::AfterTrade { loan_fee } => after_trade(deps, env, info, loan_fee),
+        CallbackMsg::AfterTrade { loan_fee } => {
+            engine::after_trade(&LunaVault, deps, env, loan_fee)
+        }
     }
 }
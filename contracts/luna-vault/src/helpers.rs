@@ -2,10 +2,11 @@ use std::fmt;
 
 use astroport::asset::Asset;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, Env, Event, Reply, StdError,
-    StdResult, Storage, SubMsgExecutionResponse, Uint128, WasmMsg,
+    to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, Event, Order, Reply,
+    StdError, StdResult, Storage, SubMsgExecutionResponse, Uint128, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::U64Key;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -14,100 +15,440 @@ use white_whale::fee::Fee;
 use white_whale::luna_vault::luna_unbond_handler::msg::Cw20HookMsg::Unbond as UnbondHandlerUnbondMsg;
 use white_whale::luna_vault::luna_unbond_handler::msg::ExecuteMsg;
 use white_whale::luna_vault::luna_unbond_handler::msg::ExecuteMsg::WithdrawUnbonded as UnbondHandlerWithdrawMsg;
+use white_whale::luna_vault::msg::{
+    HarvestRecord, PassiveStrategyKind, StrategyResponse, UnbondHub, UnbondReservation,
+};
 use white_whale::memory::queries::query_contract_from_mem;
 use white_whale::memory::{ANCHOR_BLUNA_HUB_ID, PRISM_CLUNA_HUB_ID};
 use white_whale::query::terraswap::query_asset_balance;
 use white_whale::query::{anchor, prism};
+use white_whale::snapshot::VaultSnapshot;
 use white_whale::tax::compute_tax;
 
 use crate::contract::VaultResult;
 use crate::error::LunaVaultError;
 use crate::pool_info::PoolInfoRaw;
-use crate::state::{FEE, STATE};
+use crate::state::{
+    UnbondTicket, FEE, HARVESTS, HARVEST_TOTALS, INSTANT_WITHDRAW_FEE, LAST_SNAPSHOT_ID,
+    LAST_UNBOND_TICKET_ID, LEGACY_UNBOND_HANDLERS_ASSIGNED, PROFIT, RESERVED_UNBONDS, SNAPSHOTS,
+    SNAPSHOT_IDS, STATE, UNBOND_HANDLERS_ASSIGNED, UNBOND_TICKETS,
+};
+use crate::strategies::{query_strategies_values, strategies_breakdown};
 
-/// Represents the total value in the vault
+/// Represents the total value in the vault. The `*_value_in_luna` figures are valued at the
+/// Anchor bLuna and Prism cLuna hub exchange rates, the `nominal_*` figures and the token amounts
+/// assume 1 bLuna = 1 cLuna = 1 Luna.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct TotalValue {
     pub total_value_in_luna: Uint128,
+    pub nominal_total_value_in_luna: Uint128,
     pub luna_amount: Uint128,
-    pub astroport_lp_value_in_luna: Uint128,
+    pub strategies_value_in_luna: Uint128,
+    pub nominal_strategies_value_in_luna: Uint128,
+    pub strategies: Vec<StrategyResponse>,
+    pub bluna_amount: Uint128,
     pub bluna_value_in_luna: Uint128,
+    pub cluna_amount: Uint128,
     pub cluna_value_in_luna: Uint128,
+    pub bluna_burning_amount: Uint128,
     pub bluna_value_burning_in_luna: Uint128,
+    pub cluna_burning_amount: Uint128,
     pub cluna_value_burning_in_luna: Uint128,
+    pub reserved_unbonds_in_luna: Uint128,
+    pub nominal_reserved_unbonds_in_luna: Uint128,
+    pub bluna_exchange_rate: Decimal,
+    pub cluna_exchange_rate: Decimal,
 }
 
-/// compute total vault value of deposits in LUNA and return a tuple with those values.
-/// (total, luna, astro lp, bluna, cluna)
-pub fn compute_total_value(_env: &Env, deps: Deps, info: &PoolInfoRaw) -> VaultResult<TotalValue> {
-    let state = STATE.load(deps.storage)?;
+/// compute total vault value of deposits in LUNA, valuing bLuna and cLuna at the hub exchange rates
+pub fn compute_total_value(env: &Env, deps: Deps, info: &PoolInfoRaw) -> VaultResult<TotalValue> {
+    let rates = query_hub_exchange_rates(deps)?;
+
     // get liquid Luna in the vault
     let luna_info = info.asset_infos[0].to_normal(deps.api)?;
     let luna_amount = query_asset_balance(deps, &luna_info, info.contract_addr.clone())?;
 
-    // get Luna from the passive strategy
-    // first, get the amount of LP tokens that we have
-    // then, query the pool to find out the underlying Luna assets we are entitled to
-    let astro_lp_info = info.asset_infos[1].to_normal(deps.api)?;
-    let astro_lp_amount = query_asset_balance(deps, &astro_lp_info, info.contract_addr.clone())?;
-    let astro_lp_assets: [astroport::asset::Asset; 2] = deps.querier.query_wasm_smart(
-        state.astro_lp_address.clone(),
-        &astroport::pair_stable_bluna::QueryMsg::Share {
-            amount: astro_lp_amount,
-        },
-    )?;
-    // NOTICE: we are assuming that the assets in the LP are equivalent to 1 Luna
-    let astroport_lp_value_in_luna = astro_lp_assets
+    // get Luna from the passive strategies
+    let strategies = query_strategies_values(deps, env, &rates)?;
+    let strategies_value_in_luna = strategies
         .iter()
-        .fold(Uint128::zero(), |accum, asset| accum + asset.amount);
-
-    // NOTICE: we are assuming that bLuna is equivalent to 1 Luna
-    let bluna_value_in_luna = astro_lp_assets
+        .fold(Uint128::zero(), |acc, (_, value)| acc + value.value_in_luna);
+    let nominal_strategies_value_in_luna =
+        strategies.iter().fold(Uint128::zero(), |acc, (_, value)| {
+            acc + value.nominal_value_in_luna
+        });
+
+    // get bLuna and cLuna held by the vault. bLuna is already valued by the held bLuna strategy if there is one
+    let bluna_amount = if strategies
         .iter()
-        .find(|asset| asset.info == astroport::asset::token_asset_info(state.bluna_address.clone()))
-        .ok_or_else(|| LunaVaultError::generic_err("Failed to get bLuna asset from astro LP"))?
-        .amount;
-
-    // NOTICE: we are assuming that cLuna is equivalent to 1 Luna
+        .any(|(strategy, _)| strategy.strategy == PassiveStrategyKind::HeldBluna)
+    {
+        Uint128::zero()
+    } else {
+        let bluna_info = info.asset_infos[2].to_normal(deps.api)?;
+        query_asset_balance(deps, &bluna_info, info.contract_addr.clone())?
+    };
+    let bluna_value_in_luna = rates.bluna * bluna_amount;
     let cluna_info = info.asset_infos[3].to_normal(deps.api)?;
-    let cluna_value_in_luna = query_asset_balance(deps, &cluna_info, info.contract_addr.clone())?;
+    let cluna_amount = query_asset_balance(deps, &cluna_info, info.contract_addr.clone())?;
+    let cluna_value_in_luna = rates.cluna * cluna_amount;
 
-    // amount of bluna burning on Anchor
-    let bluna_hub_address =
-        query_contract_from_mem(deps, &state.memory_address, ANCHOR_BLUNA_HUB_ID)?;
-    let bluna_value_burning_in_luna =
-        anchor::query_unbond_requests(deps, bluna_hub_address, info.contract_addr.clone())?
-            .requests
-            .iter()
-            .fold(Uint128::zero(), |acc, unbond_request| {
-                acc + unbond_request.1 // pending unbond amount
-            });
-
-    // amount of cluna burning on Prism
-    let cluna_hub_address =
-        query_contract_from_mem(deps, &state.memory_address, PRISM_CLUNA_HUB_ID)?;
-    let cluna_value_burning_in_luna =
-        prism::query_unbond_requests(deps, cluna_hub_address, info.contract_addr.clone())?
-            .requests
-            .iter()
-            .fold(Uint128::zero(), |acc, unbond_request| {
-                acc + unbond_request.1 // pending unbond amount
-            });
-
-    let total_deposits_in_luna = luna_amount
-        + astroport_lp_value_in_luna
+    // amount of bluna burning on Anchor and cluna burning on Prism
+    let unbond_requests = query_vault_unbond_requests(deps, &info.contract_addr)?;
+    let bluna_burning_amount = unbond_requests
+        .iter()
+        .filter(|request| request.hub == UnbondHub::Anchor)
+        .fold(Uint128::zero(), |acc, request| acc + request.amount);
+    let bluna_value_burning_in_luna = rates.bluna * bluna_burning_amount;
+    let cluna_burning_amount = unbond_requests
+        .iter()
+        .filter(|request| request.hub == UnbondHub::Prism)
+        .fold(Uint128::zero(), |acc, request| acc + request.amount);
+    let cluna_value_burning_in_luna = rates.cluna * cluna_burning_amount;
+
+    // unbonds reserved for users that already left the vault, either still burning or released
+    let reserved_unbonds = RESERVED_UNBONDS.may_load(deps.storage)?.unwrap_or_default();
+    let nominal_reserved_unbonds_in_luna = total_reserved_amount(&reserved_unbonds);
+    let reserved_unbonds_in_luna =
+        reserved_unbonds_value_in_luna(&reserved_unbonds, &unbond_requests, &rates);
+
+    let total_deposits_in_luna = (luna_amount
+        + strategies_value_in_luna
         + bluna_value_in_luna
         + cluna_value_in_luna
         + bluna_value_burning_in_luna
-        + cluna_value_burning_in_luna;
+        + cluna_value_burning_in_luna)
+        .checked_sub(reserved_unbonds_in_luna)?;
+    let nominal_total_deposits_in_luna = (luna_amount
+        + nominal_strategies_value_in_luna
+        + bluna_amount
+        + cluna_amount
+        + bluna_burning_amount
+        + cluna_burning_amount)
+        .checked_sub(nominal_reserved_unbonds_in_luna)?;
     Ok(TotalValue {
         total_value_in_luna: total_deposits_in_luna,
+        nominal_total_value_in_luna: nominal_total_deposits_in_luna,
         luna_amount,
-        astroport_lp_value_in_luna,
+        strategies_value_in_luna,
+        nominal_strategies_value_in_luna,
+        strategies: strategies_breakdown(strategies, strategies_value_in_luna),
+        bluna_amount,
         bluna_value_in_luna,
+        cluna_amount,
         cluna_value_in_luna,
+        bluna_burning_amount,
         bluna_value_burning_in_luna,
+        cluna_burning_amount,
         cluna_value_burning_in_luna,
+        reserved_unbonds_in_luna,
+        nominal_reserved_unbonds_in_luna,
+        bluna_exchange_rate: rates.bluna,
+        cluna_exchange_rate: rates.cluna,
+    })
+}
+
+/// Gets the amount of luna reserved for users that was already withdrawn from the hubs by the vault
+pub fn query_released_reserved_unbonds(deps: Deps, env: &Env) -> VaultResult<Uint128> {
+    let reserved_unbonds = RESERVED_UNBONDS.may_load(deps.storage)?.unwrap_or_default();
+    if reserved_unbonds.is_empty() {
+        return Ok(Uint128::zero());
+    }
+
+    let unbond_requests = query_vault_unbond_requests(deps, &env.contract.address)?;
+    let (released, _) = split_released_reservations(reserved_unbonds, &unbond_requests);
+    Ok(total_reserved_luna_amount(&released))
+}
+
+/// Saves the given unbond ticket of the owner, assigning its unbond handler to it
+pub fn save_unbond_ticket(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    ticket: &UnbondTicket,
+) -> StdResult<()> {
+    UNBOND_TICKETS.save(storage, (owner.clone(), U64Key::new(ticket.id)), ticket)?;
+    UNBOND_HANDLERS_ASSIGNED.save(
+        storage,
+        ticket.unbond_handler.clone(),
+        &(owner.clone(), ticket.id),
+    )
+}
+
+/// Records a snapshot of the vault value and vLuna supply, to track the share price over time.
+/// The PnL is measured from the value before the event, i.e. before a deposit or withdrawal
+pub fn record_snapshot(
+    storage: &mut dyn Storage,
+    env: &Env,
+    value_before: Uint128,
+    total_value: Uint128,
+    total_share: Uint128,
+    profit: Uint128,
+) -> StdResult<()> {
+    let mut profit_check = PROFIT.load(storage)?;
+    let pnl = profit_check.record_pnl(value_before, total_value)?;
+    PROFIT.save(storage, &profit_check)?;
+
+    let id = LAST_SNAPSHOT_ID.may_load(storage)?.unwrap_or_default() + 1;
+    LAST_SNAPSHOT_ID.save(storage, &id)?;
+    let timestamp = env.block.time.seconds();
+    SNAPSHOT_IDS.save(storage, U64Key::new(timestamp), &id)?;
+    SNAPSHOTS.save(
+        storage,
+        U64Key::new(id),
+        &VaultSnapshot {
+            id,
+            timestamp,
+            total_value,
+            total_share,
+            profit,
+            pnl,
+        },
+    )
+}
+
+/// Records a harvest of the passive strategy rewards, adding it to the cumulative rewards.
+/// Returns the id of the harvest
+pub fn record_harvest(
+    storage: &mut dyn Storage,
+    env: &Env,
+    rewards: Vec<terraswap::asset::Asset>,
+    luna_return: Uint128,
+) -> StdResult<u64> {
+    let mut totals = HARVEST_TOTALS.may_load(storage)?.unwrap_or_default();
+    totals.harvests += 1;
+    totals.luna_return += luna_return;
+    for reward in rewards.iter() {
+        match totals
+            .rewards
+            .iter_mut()
+            .find(|total| total.info == reward.info)
+        {
+            Some(total) => total.amount += reward.amount,
+            None => totals.rewards.push(reward.clone()),
+        }
+    }
+    HARVEST_TOTALS.save(storage, &totals)?;
+
+    HARVESTS.save(
+        storage,
+        U64Key::new(totals.harvests),
+        &HarvestRecord {
+            id: totals.harvests,
+            timestamp: env.block.time.seconds(),
+            rewards,
+            luna_return,
+        },
+    )?;
+
+    Ok(totals.harvests)
+}
+
+/// Turns the unbond handlers assigned to users before the unbond tickets were introduced into
+/// tickets, so they can be claimed like any other ticket
+pub fn migrate_legacy_unbond_handlers(deps: DepsMut, env: &Env) -> VaultResult<()> {
+    let legacy_unbond_handlers = LEGACY_UNBOND_HANDLERS_ASSIGNED
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (owner, unbond_handler) = item?;
+            Ok((
+                Addr::unchecked(String::from_utf8(owner).map_err(StdError::from)?),
+                unbond_handler,
+            ))
+        })
+        .collect::<StdResult<Vec<(Addr, Addr)>>>()?;
+    if legacy_unbond_handlers.is_empty() {
+        return Ok(());
+    }
+
+    // the original unbond times are unknown, so the tickets are expected to be released a full
+    // unbonding period from now at the latest
+    let expected_release_time = query_expected_release_time(deps.as_ref(), env)?;
+    let mut ticket_id = LAST_UNBOND_TICKET_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    for (owner, unbond_handler) in legacy_unbond_handlers {
+        ticket_id += 1;
+        save_unbond_ticket(
+            deps.storage,
+            &owner,
+            &UnbondTicket {
+                id: ticket_id,
+                unbond_handler,
+                created_at: env.block.time.seconds(),
+                expected_release_time,
+            },
+        )?;
+        LEGACY_UNBOND_HANDLERS_ASSIGNED.remove(deps.storage, owner);
+    }
+    LAST_UNBOND_TICKET_ID.save(deps.storage, &ticket_id)?;
+
+    Ok(())
+}
+
+/// Gets the time at which the luna unbonded now is expected to be released by the hubs, i.e. after
+/// the current Anchor epoch and unbonding periods. The Prism cLuna hub follows the same periods.
+pub fn query_expected_release_time(deps: Deps, env: &Env) -> VaultResult<u64> {
+    let state = STATE.load(deps.storage)?;
+    let bluna_hub_address =
+        query_contract_from_mem(deps, &state.memory_address, ANCHOR_BLUNA_HUB_ID)?;
+    let parameters = anchor::query_hub_parameters(deps, bluna_hub_address)?;
+
+    Ok(env.block.time.seconds() + parameters.epoch_period + parameters.unbonding_period)
+}
+
+/// Exchange rates of bLuna and cLuna to Luna on the Anchor bLuna and Prism cLuna hubs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HubExchangeRates {
+    pub bluna: Decimal,
+    pub cluna: Decimal,
+}
+
+/// Queries the bLuna and cLuna to Luna exchange rates on the Anchor bLuna and Prism cLuna hubs
+pub fn query_hub_exchange_rates(deps: Deps) -> VaultResult<HubExchangeRates> {
+    let state = STATE.load(deps.storage)?;
+    let bluna_hub_address =
+        query_contract_from_mem(deps, &state.memory_address, ANCHOR_BLUNA_HUB_ID)?;
+    let cluna_hub_address =
+        query_contract_from_mem(deps, &state.memory_address, PRISM_CLUNA_HUB_ID)?;
+
+    Ok(HubExchangeRates {
+        bluna: anchor::query_bluna_exchange_rate(deps, bluna_hub_address)?,
+        cluna: prism::query_cluna_exchange_rate(deps, cluna_hub_address)?,
+    })
+}
+
+/// Queries the pending unbond requests of the given address on the Anchor bLuna and Prism cLuna hubs.
+/// Each request is returned as an [UnbondReservation] of the full batch amount, valued at the
+/// current hub exchange rates.
+pub fn query_vault_unbond_requests(
+    deps: Deps,
+    address: &Addr,
+) -> VaultResult<Vec<UnbondReservation>> {
+    let state = STATE.load(deps.storage)?;
+    let rates = query_hub_exchange_rates(deps)?;
+
+    let bluna_hub_address =
+        query_contract_from_mem(deps, &state.memory_address, ANCHOR_BLUNA_HUB_ID)?;
+    let anchor_requests = anchor::query_unbond_requests(deps, bluna_hub_address, address.clone())?
+        .requests
+        .into_iter()
+        .map(|request| UnbondReservation {
+            hub: UnbondHub::Anchor,
+            batch_id: request.0,
+            amount: request.1, // pending unbond amount
+            luna_amount: rates.bluna * request.1,
+        });
+
+    let cluna_hub_address =
+        query_contract_from_mem(deps, &state.memory_address, PRISM_CLUNA_HUB_ID)?;
+    let prism_requests = prism::query_unbond_requests(deps, cluna_hub_address, address.clone())?
+        .requests
+        .into_iter()
+        .map(|request| UnbondReservation {
+            hub: UnbondHub::Prism,
+            batch_id: request.0,
+            amount: request.1, // pending unbond amount
+            luna_amount: rates.cluna * request.1,
+        });
+
+    Ok(anchor_requests.chain(prism_requests).collect())
+}
+
+/// Sums the amounts of the given reservations
+pub fn total_reserved_amount(reservations: &[UnbondReservation]) -> Uint128 {
+    reservations
+        .iter()
+        .fold(Uint128::zero(), |acc, reservation| acc + reservation.amount)
+}
+
+/// Sums the luna owed for the given reservations
+pub fn total_reserved_luna_amount(reservations: &[UnbondReservation]) -> Uint128 {
+    reservations
+        .iter()
+        .fold(Uint128::zero(), |acc, reservation| {
+            acc + reservation.luna_amount
+        })
+}
+
+/// Values the reservations in luna. The released ones are owed at the hub rate they were
+/// reserved at. The pending ones are valued at the lower of that rate and the current hub rate,
+/// so that they never exceed the value of the bLuna and cLuna still burning for them
+pub fn reserved_unbonds_value_in_luna(
+    reservations: &[UnbondReservation],
+    unbond_requests: &[UnbondReservation],
+    rates: &HubExchangeRates,
+) -> Uint128 {
+    let (released, pending) = split_released_reservations(reservations.to_vec(), unbond_requests);
+    pending
+        .iter()
+        .fold(total_reserved_luna_amount(&released), |acc, reservation| {
+            let rate = match reservation.hub {
+                UnbondHub::Anchor => rates.bluna,
+                UnbondHub::Prism => rates.cluna,
+            };
+            acc + reservation.luna_amount.min(rate * reservation.amount)
+        })
+}
+
+/// Gets the amount already reserved from the given hub batch
+pub fn reserved_batch_amount(
+    reservations: &[UnbondReservation],
+    hub: UnbondHub,
+    batch_id: u64,
+) -> Uint128 {
+    reservations
+        .iter()
+        .filter(|reservation| reservation.hub == hub && reservation.batch_id == batch_id)
+        .fold(Uint128::zero(), |acc, reservation| acc + reservation.amount)
+}
+
+/// Adds the given reservations to `aggregate`, merging the ones for the same hub batch
+pub fn add_reservations(
+    aggregate: &mut Vec<UnbondReservation>,
+    reservations: &[UnbondReservation],
+) {
+    for reservation in reservations {
+        match aggregate.iter_mut().find(|aggregated| {
+            aggregated.hub == reservation.hub && aggregated.batch_id == reservation.batch_id
+        }) {
+            Some(aggregated) => {
+                aggregated.amount += reservation.amount;
+                aggregated.luna_amount += reservation.luna_amount;
+            }
+            None => aggregate.push(reservation.clone()),
+        }
+    }
+}
+
+/// Removes the given reservations from `aggregate`, dropping the hub batches that are fully claimed
+pub fn remove_reservations(
+    aggregate: &mut Vec<UnbondReservation>,
+    reservations: &[UnbondReservation],
+) -> VaultResult<()> {
+    for reservation in reservations {
+        if let Some(aggregated) = aggregate.iter_mut().find(|aggregated| {
+            aggregated.hub == reservation.hub && aggregated.batch_id == reservation.batch_id
+        }) {
+            aggregated.amount = aggregated.amount.checked_sub(reservation.amount)?;
+            aggregated.luna_amount = aggregated
+                .luna_amount
+                .checked_sub(reservation.luna_amount)?;
+        }
+    }
+    aggregate.retain(|aggregated| !aggregated.amount.is_zero());
+    Ok(())
+}
+
+/// Splits the given reservations into the ones whose hub batch was already withdrawn by the vault and
+/// the ones still pending, based on the vault's `unbond_requests`. Returns (released, pending).
+pub fn split_released_reservations(
+    reservations: Vec<UnbondReservation>,
+    unbond_requests: &[UnbondReservation],
+) -> (Vec<UnbondReservation>, Vec<UnbondReservation>) {
+    reservations.into_iter().partition(|reservation| {
+        !unbond_requests.iter().any(|request| {
+            request.hub == reservation.hub && request.batch_id == reservation.batch_id
+        })
     })
 }
 
@@ -132,6 +473,13 @@ pub fn get_treasury_fee(deps: Deps, amount: Uint128) -> VaultResult<Uint128> {
     Ok(fee)
 }
 
+/// Gets the extra fee charged on instant withdrawals, zero if it was never set
+pub fn get_instant_withdraw_fee(storage: &dyn Storage) -> StdResult<Fee> {
+    Ok(INSTANT_WITHDRAW_FEE.may_load(storage)?.unwrap_or(Fee {
+        share: Decimal::zero(),
+    }))
+}
+
 /// Checks that the given [Fee] is valid, i.e. it's lower than 100%
 pub fn check_fee(fee: Fee) -> VaultResult<Fee> {
     if fee.share >= Decimal::percent(100) {
@@ -217,6 +565,24 @@ pub fn unbond_bluna_with_handler_msg<T: Clone + fmt::Debug + PartialEq + JsonSch
     }))
 }
 
+/// Builds message to send cluna to a handler triggering the unbond action
+pub fn unbond_cluna_with_handler_msg<T: Clone + fmt::Debug + PartialEq + JsonSchema>(
+    storage: &dyn Storage,
+    cluna_amount: Uint128,
+    unbond_handler: &Addr,
+) -> StdResult<CosmosMsg<T>> {
+    let state = STATE.load(storage)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: state.cluna_address.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: unbond_handler.to_string(),
+            amount: cluna_amount,
+            msg: to_binary(&UnbondHandlerUnbondMsg {})?,
+        })?,
+        funds: vec![],
+    }))
+}
+
 /// Builds message to withdraw luna from a handler triggering the withdraw_unbonded action
 pub fn withdraw_luna_from_handler_msg<T: Clone + fmt::Debug + PartialEq + JsonSchema>(
     unbond_handler: Addr,
@@ -8,6 +8,7 @@ mod queries;
 mod replies;
 pub mod response;
 pub mod state;
+mod strategies;
 
 #[cfg(test)]
 #[cfg(not(target_arch = "wasm32"))]
@@ -1,23 +1,42 @@
-use cosmwasm_std::{Coin, Deps, Env, Storage, Uint128};
+use cosmwasm_std::{Addr, Coin, Deps, Env, Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, U64Key};
 use terraswap::asset::Asset;
 use terraswap::querier::query_supply;
 
+use white_whale::luna_vault::luna_unbond_handler::msg::UnbondRequestsResponse;
+use white_whale::luna_vault::luna_unbond_handler::queries::{
+    query_handler_unbond_requests, query_handler_withdrawable_unbonded,
+};
 use white_whale::luna_vault::msg::{
-    EstimateWithdrawFeeResponse, FeeResponse, LastBalanceResponse, LastProfitResponse,
-    PoolResponse, ValueResponse,
+    DepositCaps, EstimateWithdrawFeeResponse, ExpiredUnbondHandlersResponse, FeeResponse,
+    HarvestConfig, HarvestHistoryResponse, HarvestRecord, LastBalanceResponse, LastProfitResponse,
+    PoolResponse, ReservedUnbondsResponse, SimulateWithdrawResponse, UnbondHandlerInfo,
+    UnbondHandlersResponse, UnbondTicketResponse, UnbondTicketStatus, UnbondTicketsResponse,
+    ValueResponse,
+};
+use white_whale::query::anchor::WithdrawableUnbondedResponse;
+use white_whale::snapshot::{
+    compute_apr, AprResponse, SharePriceResponse, SnapshotsResponse, VaultSnapshot,
 };
-use white_whale::memory::queries::query_contract_from_mem;
-use white_whale::memory::ANCHOR_BLUNA_HUB_ID;
-use white_whale::query::anchor::{UnbondRequestsResponse, WithdrawableUnbondedResponse};
+use white_whale::vault::fees::{self, BorrowerStats, EffectiveFeeResponse};
+use white_whale::vault::flashloan::{FlashLoanVault, PnlResponse};
 
 use crate::contract::VaultResult;
 use crate::error::LunaVaultError;
-use crate::helpers::{compute_total_value, get_withdraw_fee};
+use crate::flashloan::LunaVault;
+use crate::helpers::{
+    compute_total_value, get_instant_withdraw_fee, get_treasury_fee, get_withdraw_fee,
+    query_vault_unbond_requests, split_released_reservations, total_reserved_luna_amount,
+};
 use crate::pool_info::{PoolInfo, PoolInfoRaw};
 use crate::state::{
-    State, DEFAULT_UNBOND_EXPIRATION_TIME, DEPOSIT_INFO, FEE, POOL_INFO, PROFIT, STATE,
-    UNBOND_HANDLERS_ASSIGNED, UNBOND_HANDLER_EXPIRATION_TIME,
+    State, UnbondTicket, DEFAULT_UNBOND_EXPIRATION_TIME, DEPOSIT_CAPS, DEPOSIT_INFO, FEE, HARVESTS,
+    HARVEST_CONFIG, HARVEST_TOTALS, POOL_INFO, PROFIT, SNAPSHOTS, SNAPSHOT_IDS, STATE,
+    UNBOND_HANDLERS_ASSIGNED, UNBOND_HANDLERS_AVAILABLE, UNBOND_HANDLERS_DEFAULT_READ_LIMIT,
+    UNBOND_HANDLER_EXPIRATION_TIME, UNBOND_HANDLER_EXPIRATION_TIMES,
+    UNBOND_HANDLER_EXPIRATION_TIMES_READ_LIMIT, UNBOND_RESERVATIONS, UNBOND_TICKETS,
 };
+use crate::strategies::withdraw_from_vault;
 
 /// Queries the PoolInfo configuration
 pub fn query_pool_info(deps: Deps) -> VaultResult<PoolInfo> {
@@ -31,16 +50,137 @@ pub fn try_query_pool_state(env: Env, deps: Deps) -> VaultResult<PoolResponse> {
     let assets: [Asset; 4] = info.query_pools(deps, info.contract_addr.clone())?;
     let total_share: Uint128 = query_supply(&deps.querier, info.liquidity_token.clone())?;
 
-    let total_value_in_luna = compute_total_value(&env, deps, &info)?.total_value_in_luna;
+    let total_value = compute_total_value(&env, deps, &info)?;
 
     Ok(PoolResponse {
         assets,
-        total_value_in_luna,
+        total_value_in_luna: total_value.total_value_in_luna,
         total_share,
         liquidity_token: info.liquidity_token.into(),
+        strategies: total_value.strategies,
+    })
+}
+
+/// Queries the deposit caps of the vault
+pub fn query_deposit_caps(deps: Deps) -> VaultResult<DepositCaps> {
+    Ok(DEPOSIT_CAPS.may_load(deps.storage)?.unwrap_or_default())
+}
+
+/// Queries how the passive strategy token rewards are swapped for luna
+pub fn query_harvest_config(deps: Deps) -> VaultResult<HarvestConfig> {
+    Ok(HARVEST_CONFIG.may_load(deps.storage)?.unwrap_or_default())
+}
+
+/// Queries the cumulative rewards harvested by the vault, along with the harvests made after the
+/// given harvest id
+pub fn query_harvest_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> VaultResult<HarvestHistoryResponse> {
+    let totals = HARVEST_TOTALS.may_load(deps.storage)?.unwrap_or_default();
+    let start = start_after.map(Bound::exclusive_int);
+
+    let harvests = HARVESTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(read_limit(limit))
+        .map(|item| Ok(item?.1))
+        .collect::<StdResult<Vec<HarvestRecord>>>()?;
+
+    Ok(HarvestHistoryResponse {
+        total_harvests: totals.harvests,
+        total_rewards: totals.rewards,
+        total_luna_return: totals.luna_return,
+        harvests,
     })
 }
 
+/// Queries the vLuna share price at the given timestamp, defaulting to the current block time
+pub fn query_share_price(
+    deps: Deps,
+    env: Env,
+    timestamp: Option<u64>,
+) -> VaultResult<SharePriceResponse> {
+    let snapshot = snapshot_at(
+        deps.storage,
+        timestamp.unwrap_or_else(|| env.block.time.seconds()),
+    )?;
+    Ok(SharePriceResponse {
+        share_price: snapshot.share_price(),
+        snapshot,
+    })
+}
+
+/// Queries the APR of the vault over the trailing window, in seconds
+pub fn query_apr(deps: Deps, env: Env, window: u64) -> VaultResult<AprResponse> {
+    let now = env.block.time.seconds();
+    let start = snapshot_at(deps.storage, now.saturating_sub(window))?;
+    let end = snapshot_at(deps.storage, now)?;
+    Ok(AprResponse {
+        apr: compute_apr(&start, &end),
+        start,
+        end,
+    })
+}
+
+/// Queries the share price snapshots recorded after the given snapshot id
+pub fn query_snapshots(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> VaultResult<SnapshotsResponse> {
+    let start = start_after.map(Bound::exclusive_int);
+    let snapshots = SNAPSHOTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(read_limit(limit))
+        .map(|item| Ok(item?.1))
+        .collect::<StdResult<Vec<VaultSnapshot>>>()?;
+
+    Ok(SnapshotsResponse { snapshots })
+}
+
+/// Queries the flashloan fee the borrower pays for the given amount
+pub fn query_effective_fee(
+    deps: Deps,
+    borrower: String,
+    amount: Uint128,
+) -> VaultResult<EffectiveFeeResponse> {
+    let borrower = deps.api.addr_validate(&borrower)?;
+    let fee = fees::effective_fee(deps.storage, &LunaVault.config(deps)?, &borrower)?;
+    Ok(EffectiveFeeResponse {
+        loan_fee: fee.upfront_fee(amount),
+        fee,
+    })
+}
+
+/// Queries the successful flashloans of the borrower and the fees it paid
+pub fn query_borrower_stats(deps: Deps, borrower: String) -> VaultResult<BorrowerStats> {
+    let borrower = deps.api.addr_validate(&borrower)?;
+    Ok(fees::load_borrower_stats(deps.storage, &borrower)?)
+}
+
+/// Gets the latest snapshot recorded at or before the given timestamp, or the earliest one if
+/// they were all recorded after it
+fn snapshot_at(storage: &dyn Storage, timestamp: u64) -> VaultResult<VaultSnapshot> {
+    let snapshot = match SNAPSHOT_IDS
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive_int(timestamp)),
+            Order::Descending,
+        )
+        .next()
+    {
+        Some(item) => SNAPSHOTS.may_load(storage, U64Key::new(item?.1))?,
+        None => SNAPSHOTS
+            .range(storage, None, None, Order::Ascending)
+            .next()
+            .transpose()?
+            .map(|(_, snapshot)| snapshot),
+    };
+    snapshot.ok_or(LunaVaultError::NoSnapshots {})
+}
+
 /// Queries contract [State]
 pub fn query_state(deps: Deps) -> VaultResult<State> {
     Ok(STATE.load(deps.storage)?)
@@ -50,14 +190,55 @@ pub fn query_state(deps: Deps) -> VaultResult<State> {
 pub fn query_fees(deps: Deps) -> VaultResult<FeeResponse> {
     Ok(FeeResponse {
         fees: FEE.load(deps.storage)?,
+        instant_withdraw_fee: get_instant_withdraw_fee(deps.storage)?,
+    })
+}
+
+/// Simulates the luna returned for the given amount of vLuna, through the unbond handler and
+/// through the instant withdrawal
+pub fn query_simulate_withdraw(
+    deps: Deps,
+    env: Env,
+    amount: Uint128,
+) -> VaultResult<SimulateWithdrawResponse> {
+    let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+    let total_share = query_supply(&deps.querier, info.liquidity_token.clone())?;
+    if total_share.is_zero() {
+        return Ok(SimulateWithdrawResponse {
+            unbond_amount: Uint128::zero(),
+            instant_amount: Uint128::zero(),
+            instant_withdraw_fee: Uint128::zero(),
+        });
+    }
+
+    // the treasury fee is paid in vLuna in both modes
+    let treasury_fee = get_treasury_fee(deps, amount)?;
+    let total_value_in_luna = compute_total_value(&env, deps, &info)?.total_value_in_luna;
+    let unbond_amount = amount
+        .checked_sub(treasury_fee)?
+        .multiply_ratio(total_value_in_luna, total_share);
+
+    // the instant amount is what the vault gets back from the strategies after slippage
+    let instant_withdraw_fee = get_instant_withdraw_fee(deps.storage)?.compute(unbond_amount);
+    let withdrawal =
+        withdraw_from_vault(deps, &env, unbond_amount.checked_sub(instant_withdraw_fee)?)?;
+    Ok(SimulateWithdrawResponse {
+        unbond_amount,
+        instant_amount: withdrawal.luna_return,
+        instant_withdraw_fee,
     })
 }
 
 /// Queries total luna value in vault
 pub fn query_total_value(env: Env, deps: Deps) -> VaultResult<ValueResponse> {
     let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
-    let total_luna_value = compute_total_value(&env, deps, &info)?.total_value_in_luna;
-    Ok(ValueResponse { total_luna_value })
+    let total_value = compute_total_value(&env, deps, &info)?;
+    Ok(ValueResponse {
+        total_luna_value: total_value.total_value_in_luna,
+        nominal_luna_value: total_value.nominal_total_value_in_luna,
+        bluna_exchange_rate: total_value.bluna_exchange_rate,
+        cluna_exchange_rate: total_value.cluna_exchange_rate,
+    })
 }
 
 /// Queries estimated withdrawal fee
@@ -82,6 +263,11 @@ pub fn query_last_profit(deps: Deps) -> VaultResult<LastProfitResponse> {
     })
 }
 
+/// Queries the profit and loss of the vault
+pub fn query_pnl(deps: Deps) -> VaultResult<PnlResponse> {
+    Ok(PROFIT.load(deps.storage)?.into())
+}
+
 /// Queries vault's last balance
 pub fn query_last_balance(deps: Deps) -> VaultResult<LastBalanceResponse> {
     let conf = PROFIT.load(deps.storage)?;
@@ -90,45 +276,101 @@ pub fn query_last_balance(deps: Deps) -> VaultResult<LastBalanceResponse> {
     })
 }
 
-/// Queries withdrawable unbonded amount for the unbond handler associated with the given address
+/// Queries withdrawable unbonded amount for the unbond handlers backing the tickets of the given address
 pub fn query_withdrawable_unbonded(
     deps: Deps,
     address: String,
 ) -> VaultResult<WithdrawableUnbondedResponse> {
-    let address = deps.api.addr_validate(&address)?;
-    let unbond_handler = UNBOND_HANDLERS_ASSIGNED
-        .may_load(deps.storage, address)?
-        .ok_or(LunaVaultError::NoUnbondHandlerAssigned {})?;
+    let tickets = load_unbond_tickets(deps, address, None, None)?;
+    if tickets.is_empty() {
+        return Err(LunaVaultError::NoUnbondHandlerAssigned {});
+    }
 
-    let state = STATE.load(deps.storage)?;
-    let bluna_hub_address =
-        query_contract_from_mem(deps, &state.memory_address, ANCHOR_BLUNA_HUB_ID)?;
+    // query how much withdrawable_unbonded is on anchor and prism for each unbond handler
+    let mut withdrawable = Uint128::zero();
+    for ticket in tickets {
+        withdrawable +=
+            query_handler_withdrawable_unbonded(deps, &ticket.unbond_handler)?.withdrawable;
+    }
 
-    // query how much withdrawable_unbonded is on anchor for the given unbond handler
-    Ok(white_whale::query::anchor::query_withdrawable_unbonded(
-        deps,
-        bluna_hub_address,
-        unbond_handler,
-    )?)
+    Ok(WithdrawableUnbondedResponse { withdrawable })
 }
 
-/// Queries unbond requests for the unbond handler associated with the given address
+/// Queries unbond requests for the unbond handlers backing the tickets of the given address
 pub fn query_unbond_requests(deps: Deps, address: String) -> VaultResult<UnbondRequestsResponse> {
+    let tickets = load_unbond_tickets(deps, address.clone(), None, None)?;
+    if tickets.is_empty() {
+        return Err(LunaVaultError::NoUnbondHandlerAssigned {});
+    }
+
+    // query unbond requests on anchor and prism for each unbond handler
+    let mut unbond_requests = UnbondRequestsResponse {
+        address,
+        anchor_requests: vec![],
+        prism_requests: vec![],
+    };
+    for ticket in tickets {
+        let handler_unbond_requests = query_handler_unbond_requests(deps, &ticket.unbond_handler)?;
+        unbond_requests
+            .anchor_requests
+            .extend(handler_unbond_requests.anchor_requests);
+        unbond_requests
+            .prism_requests
+            .extend(handler_unbond_requests.prism_requests);
+    }
+
+    Ok(unbond_requests)
+}
+
+/// Queries the unbond tickets of the given address, along with their status
+pub fn query_unbond_tickets(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> VaultResult<UnbondTicketsResponse> {
+    let tickets = load_unbond_tickets(deps, address, start_after, Some(read_limit(limit)))?
+        .into_iter()
+        .map(|ticket| {
+            let (pending_amount, withdrawable_amount) =
+                query_unbond_handler_amounts(deps, &ticket.unbond_handler)?;
+            Ok(UnbondTicketResponse {
+                id: ticket.id,
+                unbond_handler: ticket.unbond_handler.into_string(),
+                created_at: ticket.created_at,
+                expected_release_time: ticket.expected_release_time,
+                status: if withdrawable_amount.is_zero() {
+                    UnbondTicketStatus::Unbonding
+                } else {
+                    UnbondTicketStatus::Claimable
+                },
+                pending_amount,
+                withdrawable_amount,
+            })
+        })
+        .collect::<VaultResult<Vec<UnbondTicketResponse>>>()?;
+
+    Ok(UnbondTicketsResponse { tickets })
+}
+
+/// Loads the unbond tickets of the given address, ordered by ticket id
+fn load_unbond_tickets(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<usize>,
+) -> VaultResult<Vec<UnbondTicket>> {
     let address = deps.api.addr_validate(&address)?;
-    let unbond_handler = UNBOND_HANDLERS_ASSIGNED
-        .may_load(deps.storage, address)?
-        .ok_or(LunaVaultError::NoUnbondHandlerAssigned {})?;
+    let start = start_after.map(Bound::exclusive_int);
 
-    let state = STATE.load(deps.storage)?;
-    let bluna_hub_address =
-        query_contract_from_mem(deps, &state.memory_address, ANCHOR_BLUNA_HUB_ID)?;
+    let tickets = UNBOND_TICKETS
+        .prefix(address)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|item| Ok(item?.1))
+        .collect::<StdResult<Vec<UnbondTicket>>>()?;
 
-    // query unbond requests on anchor for the given unbond handler
-    Ok(white_whale::query::anchor::query_unbond_requests(
-        deps,
-        bluna_hub_address,
-        unbond_handler,
-    )?)
+    Ok(tickets)
 }
 
 /// Queries the unbond handler expiration time if set, returns the default value otherwise
@@ -141,3 +383,186 @@ pub fn query_unbond_handler_expiration_time(storage: &dyn Storage) -> VaultResul
         Ok(DEFAULT_UNBOND_EXPIRATION_TIME)
     }
 }
+
+/// Queries the vault unbonds reserved for the given address, split into pending and claimable
+pub fn query_reserved_unbonds(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> VaultResult<ReservedUnbondsResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let reservations = UNBOND_RESERVATIONS
+        .may_load(deps.storage, address)?
+        .unwrap_or_default();
+
+    let unbond_requests = query_vault_unbond_requests(deps, &env.contract.address)?;
+    let (released, pending) = split_released_reservations(reservations, &unbond_requests);
+
+    Ok(ReservedUnbondsResponse {
+        pending,
+        claimable: total_reserved_luna_amount(&released),
+    })
+}
+
+/// Queries the unbond handlers assigned to users, ordered by handler address
+pub fn query_assigned_unbond_handlers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> VaultResult<UnbondHandlersResponse> {
+    let start = match start_after {
+        Some(unbond_handler) => Some(Bound::exclusive(
+            deps.api.addr_validate(&unbond_handler)?.as_bytes(),
+        )),
+        None => None,
+    };
+
+    let unbond_handlers = UNBOND_HANDLERS_ASSIGNED
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(read_limit(limit))
+        .map(|unbond_handler| {
+            let unbond_handler =
+                Addr::unchecked(String::from_utf8(unbond_handler).map_err(StdError::from)?);
+            query_unbond_handler_info(deps, unbond_handler)
+        })
+        .collect::<VaultResult<Vec<UnbondHandlerInfo>>>()?;
+
+    Ok(UnbondHandlersResponse { unbond_handlers })
+}
+
+/// Queries the unbond handlers available to be assigned to users
+pub fn query_available_unbond_handlers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> VaultResult<UnbondHandlersResponse> {
+    let unbond_handlers_available = UNBOND_HANDLERS_AVAILABLE
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let start = match start_after {
+        Some(start_after) => {
+            let start_after = deps.api.addr_validate(&start_after)?;
+            unbond_handlers_available
+                .iter()
+                .position(|unbond_handler| *unbond_handler == start_after)
+                .map_or(unbond_handlers_available.len(), |position| position + 1)
+        }
+        None => 0,
+    };
+
+    let unbond_handlers = unbond_handlers_available
+        .into_iter()
+        .skip(start)
+        .take(read_limit(limit))
+        .map(|unbond_handler| query_unbond_handler_info(deps, unbond_handler))
+        .collect::<VaultResult<Vec<UnbondHandlerInfo>>>()?;
+
+    Ok(UnbondHandlersResponse { unbond_handlers })
+}
+
+/// Queries the unbond handlers expired as of the given timestamp, optionally only the ones assigned
+/// to the given owner, ordered by handler address. The limit bounds the handlers read before they
+/// are filtered, so a page can hold fewer handlers than the limit while there are more to read
+pub fn query_expired_unbond_handlers(
+    deps: Deps,
+    env: Env,
+    expired_at: Option<u64>,
+    owner: Option<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> VaultResult<ExpiredUnbondHandlersResponse> {
+    let expired_at = expired_at.unwrap_or_else(|| env.block.time.seconds());
+    let owner = owner
+        .map(|owner| deps.api.addr_validate(&owner))
+        .transpose()?;
+    let start = match start_after {
+        Some(unbond_handler) => Some(Bound::exclusive(
+            deps.api.addr_validate(&unbond_handler)?.as_bytes(),
+        )),
+        None => None,
+    };
+
+    let limit = read_limit(limit);
+    let expiration_times = UNBOND_HANDLER_EXPIRATION_TIMES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (unbond_handler, expiration_time) = item?;
+            let unbond_handler =
+                Addr::unchecked(String::from_utf8(unbond_handler).map_err(StdError::from)?);
+            Ok((unbond_handler, expiration_time))
+        })
+        .collect::<VaultResult<Vec<(Addr, u64)>>>()?;
+    let last_unbond_handler = match expiration_times.last() {
+        Some((unbond_handler, _)) if expiration_times.len() == limit => {
+            Some(unbond_handler.to_string())
+        }
+        _ => None,
+    };
+
+    let mut unbond_handlers = vec![];
+    for (unbond_handler, expiration_time) in expiration_times {
+        if expired_at <= expiration_time {
+            continue;
+        }
+        if let Some(owner) = &owner {
+            match UNBOND_HANDLERS_ASSIGNED.may_load(deps.storage, unbond_handler.clone())? {
+                Some((assigned_owner, _)) if assigned_owner == *owner => (),
+                _ => continue,
+            }
+        }
+        unbond_handlers.push(query_unbond_handler_info(deps, unbond_handler)?);
+    }
+
+    Ok(ExpiredUnbondHandlersResponse {
+        unbond_handlers,
+        last_unbond_handler,
+    })
+}
+
+/// Queries the owner, ticket, expiration time and the pending and withdrawable amounts of the
+/// given unbond handler
+fn query_unbond_handler_info(deps: Deps, unbond_handler: Addr) -> VaultResult<UnbondHandlerInfo> {
+    let (owner, ticket_id) =
+        match UNBOND_HANDLERS_ASSIGNED.may_load(deps.storage, unbond_handler.clone())? {
+            Some((owner, ticket_id)) => (Some(owner.into_string()), Some(ticket_id)),
+            None => (None, None),
+        };
+    let (pending_amount, withdrawable_amount) =
+        query_unbond_handler_amounts(deps, &unbond_handler)?;
+
+    Ok(UnbondHandlerInfo {
+        owner,
+        ticket_id,
+        expiration_time: UNBOND_HANDLER_EXPIRATION_TIMES
+            .may_load(deps.storage, unbond_handler.clone())?,
+        pending_amount,
+        withdrawable_amount,
+        address: unbond_handler.into_string(),
+    })
+}
+
+/// Queries the pending and withdrawable amounts of the given unbond handler
+fn query_unbond_handler_amounts(
+    deps: Deps,
+    unbond_handler: &Addr,
+) -> VaultResult<(Uint128, Uint128)> {
+    let unbond_requests = query_handler_unbond_requests(deps, unbond_handler)?;
+    let pending_bluna = unbond_requests
+        .anchor_requests
+        .iter()
+        .fold(Uint128::zero(), |acc, request| acc + request.1);
+    let pending_cluna = unbond_requests
+        .prism_requests
+        .iter()
+        .fold(Uint128::zero(), |acc, request| acc + request.1);
+    let withdrawable = query_handler_withdrawable_unbonded(deps, unbond_handler)?.withdrawable;
+
+    Ok((pending_bluna + pending_cluna, withdrawable))
+}
+
+fn read_limit(limit: Option<u32>) -> usize {
+    limit
+        .unwrap_or(UNBOND_HANDLERS_DEFAULT_READ_LIMIT)
+        .min(UNBOND_HANDLER_EXPIRATION_TIMES_READ_LIMIT) as usize
+}
@@ -1,15 +1,15 @@
-use cosmwasm_std::{attr, DepsMut, Event, Response, StdError};
+use cosmwasm_std::{attr, DepsMut, Env, Event, Response, StdError};
 
 use white_whale::luna_vault::luna_unbond_handler::{EXPIRATION_TIME_KEY, OWNER_KEY};
 
 use crate::contract::VaultResult;
 use crate::error::LunaVaultError;
-use crate::helpers::unbond_bluna_with_handler_msg;
 use crate::helpers::{event_contains_attr, get_attribute_value_from_event};
-use crate::response::MsgInstantiateContractResponse;
-use crate::state::{
-    POOL_INFO, UNBOND_CACHE, UNBOND_HANDLERS_ASSIGNED, UNBOND_HANDLER_EXPIRATION_TIMES,
+use crate::helpers::{
+    save_unbond_ticket, unbond_bluna_with_handler_msg, unbond_cluna_with_handler_msg,
 };
+use crate::response::MsgInstantiateContractResponse;
+use crate::state::{UnbondTicket, POOL_INFO, UNBOND_CACHE, UNBOND_HANDLER_EXPIRATION_TIMES};
 
 /// Executes after the token contract instantiation occurs successfully
 /// Stores the liquidity token address
@@ -31,6 +31,7 @@ pub fn after_token_instantiation(
 /// Stores the new unbond handler information into the respective state items
 pub fn after_unbond_handler_instantiation(
     deps: DepsMut,
+    env: Env,
     response: MsgInstantiateContractResponse,
     events: Vec<Event>,
 ) -> VaultResult<Response> {
@@ -44,7 +45,6 @@ pub fn after_unbond_handler_instantiation(
     // get owner from event
     let owner_string = get_attribute_value_from_event(event, OWNER_KEY)?;
     let owner = deps.api.addr_validate(&owner_string)?;
-    UNBOND_HANDLERS_ASSIGNED.save(deps.storage, owner.clone(), &unbond_handler_contract)?;
 
     // get expiration_time from event
     let expiration_time_string = get_attribute_value_from_event(event, EXPIRATION_TIME_KEY)?;
@@ -69,28 +69,57 @@ pub fn after_unbond_handler_instantiation(
         return Err(LunaVaultError::UnbondHandlerMismatchingDataCache {});
     }
 
+    // assign the new unbond handler to the user's ticket
+    save_unbond_ticket(
+        deps.storage,
+        &owner,
+        &UnbondTicket {
+            id: unbond_data_cache.ticket_id,
+            unbond_handler: unbond_handler_contract.clone(),
+            created_at: env.block.time.seconds(),
+            expected_release_time: unbond_data_cache.expected_release_time,
+        },
+    )?;
+
+    let mut messages = vec![];
+
     // get luna amount from cache and send to unbond handler
     let luna_asset = unbond_data_cache.luna_asset;
-    let send_luna_to_handler_msg =
-        luna_asset.into_msg(&deps.querier, unbond_handler_contract.clone())?;
+    if !luna_asset.amount.is_zero() {
+        messages.push(luna_asset.into_msg(&deps.querier, unbond_handler_contract.clone())?);
+    }
 
     // get bluna amount from cache and send to unbond handler
     let bluna_amount = unbond_data_cache.bluna_amount;
-    let unbond_msg =
-        unbond_bluna_with_handler_msg(deps.storage, bluna_amount, &unbond_handler_contract)?;
+    if !bluna_amount.is_zero() {
+        messages.push(unbond_bluna_with_handler_msg(
+            deps.storage,
+            bluna_amount,
+            &unbond_handler_contract,
+        )?);
+    }
+
+    // get cluna amount from cache and send to unbond handler
+    let cluna_amount = unbond_data_cache.cluna_amount;
+    if !cluna_amount.is_zero() {
+        messages.push(unbond_cluna_with_handler_msg(
+            deps.storage,
+            cluna_amount,
+            &unbond_handler_contract,
+        )?);
+    }
 
     // clear the unbond cache
     UNBOND_CACHE.remove(deps.storage);
 
-    Ok(Response::new()
-        .add_messages(vec![send_luna_to_handler_msg, unbond_msg])
-        .add_attributes(vec![
-            attr("action", "unbond_handler_instantiate"),
-            attr("owner", owner_string),
-            attr(
-                "unbond_handler_contract",
-                unbond_handler_contract.to_string(),
-            ),
-            attr("expiration_time", expiration_time_string),
-        ]))
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "unbond_handler_instantiate"),
+        attr("owner", owner_string),
+        attr("ticket_id", unbond_data_cache.ticket_id.to_string()),
+        attr(
+            "unbond_handler_contract",
+            unbond_handler_contract.to_string(),
+        ),
+        attr("expiration_time", expiration_time_string),
+    ]))
 }
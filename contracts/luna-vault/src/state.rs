@@ -1,11 +1,18 @@
 use cosmwasm_std::{Addr, Uint128};
 use cw_controllers::Admin;
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Item, Map, U64Key};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use terraswap::asset::Asset;
 use white_whale::deposit_info::DepositInfo;
-use white_whale::fee::VaultFee;
+use white_whale::fee::{Fee, VaultFee};
+
+use white_whale::luna_vault::msg::{
+    DepositCaps, HarvestConfig, HarvestRecord, StrategyInfo, UnbondReservation,
+};
+use white_whale::snapshot::VaultSnapshot;
+use white_whale::vault::flashloan::ProfitCheck;
 
 use crate::pool_info::PoolInfoRaw;
 
@@ -26,25 +33,56 @@ pub struct State {
     pub unbond_handler_code_id: u64,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct ProfitCheck {
-    pub last_balance: Uint128,
-    pub last_profit: Uint128,
-}
-
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct UnbondDataCache {
     pub owner: Addr,
+    pub ticket_id: u64,
+    pub expected_release_time: u64,
     pub bluna_amount: Uint128,
+    pub cluna_amount: Uint128,
     pub luna_asset: astroport::asset::Asset,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+// Unbond of a user, backed by its own unbond handler until the unbonded luna is claimed
+pub struct UnbondTicket {
+    pub id: u64,
+    pub unbond_handler: Addr,
+    pub created_at: u64,
+    pub expected_release_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+// Rewards harvested by the vault over all the harvests
+pub struct HarvestTotals {
+    pub harvests: u64,
+    pub rewards: Vec<Asset>,
+    pub luna_return: Uint128,
+}
+
 pub const PROFIT: Item<ProfitCheck> = Item::new("profit");
 pub const ADMIN: Admin = Admin::new("admin");
 pub const STATE: Item<State> = Item::new("state");
 pub const POOL_INFO: Item<PoolInfoRaw> = Item::new("pool");
 pub const DEPOSIT_INFO: Item<DepositInfo> = Item::new("deposit");
 pub const FEE: Item<VaultFee> = Item::new("fee");
+// Extra fee charged on instant withdrawals, kept by the vault. Zero if not set
+pub const INSTANT_WITHDRAW_FEE: Item<Fee> = Item::new("instant_withdraw_fee");
+// Passive strategies the vault deposits luna into, along with their target weights
+pub const STRATEGIES: Item<Vec<StrategyInfo>> = Item::new("strategies");
+// Limits on the luna the vault accepts, uncapped if not set
+pub const DEPOSIT_CAPS: Item<DepositCaps> = Item::new("deposit_caps");
+// How the passive strategy token rewards are swapped for luna, directly on the reward-luna pair if not set
+pub const HARVEST_CONFIG: Item<HarvestConfig> = Item::new("harvest_config");
+// Harvests made by the vault, by harvest id
+pub const HARVESTS: Map<U64Key, HarvestRecord> = Map::new("harvests");
+// Share price history of the vault, by snapshot id
+pub const SNAPSHOTS: Map<U64Key, VaultSnapshot> = Map::new("snapshots");
+// Id of the last snapshot recorded at each timestamp, to look the snapshots up by time
+pub const SNAPSHOT_IDS: Map<U64Key, u64> = Map::new("snapshot_ids");
+pub const LAST_SNAPSHOT_ID: Item<u64> = Item::new("last_snapshot_id");
+// Rewards harvested by the vault over all the harvests
+pub const HARVEST_TOTALS: Item<HarvestTotals> = Item::new("harvest_totals");
 
 // Unbond handler objects
 
@@ -56,9 +94,16 @@ pub const UNBOND_HANDLER_EXPIRATION_TIME: Item<u64> = Item::new("unbond_handler_
 
 // Unbond handler addresses that are available and ready to be used
 pub const UNBOND_HANDLERS_AVAILABLE: Item<Vec<Addr>> = Item::new("unbond_handlers_available");
-// Map of unbond handlers assigned to user addresses
-pub const UNBOND_HANDLERS_ASSIGNED: Map<UserAddr, UnbondHandlerAddr> =
+// Map of unbond handlers assigned to user addresses, replaced by the unbond tickets
+pub const LEGACY_UNBOND_HANDLERS_ASSIGNED: Map<UserAddr, UnbondHandlerAddr> =
     Map::new("unbond_handlers_assigned");
+// Unbond tickets of user addresses, by ticket id
+pub const UNBOND_TICKETS: Map<(UserAddr, U64Key), UnbondTicket> = Map::new("unbond_tickets");
+// Map of the user address and ticket id each assigned unbond handler is backing
+pub const UNBOND_HANDLERS_ASSIGNED: Map<UnbondHandlerAddr, (UserAddr, u64)> =
+    Map::new("unbond_handler_tickets");
+// Id of the last unbond ticket created
+pub const LAST_UNBOND_TICKET_ID: Item<u64> = Item::new("last_unbond_ticket_id");
 // Map of expiration times for a unbond handlers addresses
 pub const UNBOND_HANDLER_EXPIRATION_TIMES: Map<UnbondHandlerAddr, u64> =
     Map::new("unbond_handler_expiration_times");
@@ -66,6 +111,13 @@ pub const UNBOND_HANDLER_EXPIRATION_TIMES: Map<UnbondHandlerAddr, u64> =
 // needs to be created. This cache will be used by the reply handler.
 pub const UNBOND_CACHE: Item<UnbondDataCache> = Item::new("unbond_cache");
 
+// Vault unbonds reserved for user addresses whose share couldn't be covered by the passive strategy
+pub const UNBOND_RESERVATIONS: Map<UserAddr, Vec<UnbondReservation>> =
+    Map::new("unbond_reservations");
+// Unbond reservations of all users aggregated per hub batch, not part of the vault value anymore
+pub const RESERVED_UNBONDS: Item<Vec<UnbondReservation>> = Item::new("reserved_unbonds");
+
 // 40 days
 pub const DEFAULT_UNBOND_EXPIRATION_TIME: u64 = 3456000u64;
 pub const UNBOND_HANDLER_EXPIRATION_TIMES_READ_LIMIT: u32 = 30u32;
+pub const UNBOND_HANDLERS_DEFAULT_READ_LIMIT: u32 = 10u32;
@@ -0,0 +1,245 @@
+use cosmwasm_std::{attr, coins, to_binary, Addr, CosmosMsg, Decimal, Deps, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use terraswap::asset::{Asset, AssetInfo};
+
+use white_whale::denom::LUNA_DENOM;
+
+use crate::contract::VaultResult;
+use crate::error::LunaVaultError;
+use crate::helpers::{get_lp_token_address, get_share_amount, HubExchangeRates};
+use crate::strategies::{
+    query_lp_position, Harvest, PassiveStrategy, RewardsSwap, StrategyValue, Withdrawal,
+};
+
+/// Astroport bLuna-Luna stable pair LP, with ASTRO rewards from the generator
+pub struct AstroportBlunaLp {
+    pub pair_address: Addr,
+    pub factory_address: Addr,
+    pub bluna_address: Addr,
+    pub vault_address: Addr,
+}
+
+impl PassiveStrategy for AstroportBlunaLp {
+    // Deposits Luna into the luna-bluna LP
+    fn deposit(&self, deps: Deps, amount: Uint128) -> VaultResult<Vec<CosmosMsg>> {
+        // split luna into half so half goes to purchase bLuna, remaining half is used as liquidity
+        let luna_asset = astroport::asset::Asset {
+            amount: amount.checked_div(Uint128::from(2_u8))?,
+            info: astroport::asset::AssetInfo::NativeToken {
+                denom: LUNA_DENOM.to_string(),
+            },
+        };
+
+        // simulate the luna swap so we know the bluna return amount when we later provide liquidity
+        let bluna_return: astroport::pair::SimulationResponse = deps.querier.query_wasm_smart(
+            &self.pair_address,
+            &astroport::pair::QueryMsg::Simulation {
+                offer_asset: luna_asset.clone(),
+            },
+        )?;
+
+        let bluna_asset = astroport::asset::Asset {
+            amount: bluna_return.return_amount,
+            info: astroport::asset::AssetInfo::Token {
+                contract_addr: self.bluna_address.clone(),
+            },
+        };
+
+        let bluna_purchase_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.pair_address.to_string(),
+            msg: to_binary(&astroport::pair::ExecuteMsg::Swap {
+                offer_asset: luna_asset.clone(),
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            })?,
+            funds: coins(luna_asset.amount.u128(), LUNA_DENOM),
+        });
+
+        // allow the pair to take the purchased bluna when providing liquidity
+        let bluna_allowance_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.bluna_address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                spender: self.pair_address.to_string(),
+                amount: bluna_asset.amount,
+                expires: None,
+            })?,
+            funds: vec![],
+        });
+
+        let deposit_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.pair_address.to_string(),
+            msg: to_binary(&astroport::pair::ExecuteMsg::ProvideLiquidity {
+                assets: [luna_asset.clone(), bluna_asset],
+                slippage_tolerance: None,
+                auto_stake: None,
+                receiver: None,
+            })?,
+            funds: coins(luna_asset.amount.u128(), LUNA_DENOM),
+        });
+
+        Ok(vec![
+            bluna_purchase_msg,  // 1. purchase bluna
+            bluna_allowance_msg, // 2. allow the pair to take the bluna
+            deposit_msg,         // 3. deposit bLuna/Luna to the LP as liquidity
+        ])
+    }
+
+    // Withdraws Luna from the luna-bluna LP -> Luna + bLuna, providing the bLuna back as single-sided liquidity
+    fn withdraw(&self, deps: Deps, amount: Uint128) -> VaultResult<Withdrawal> {
+        let astro_lp_token_address = get_lp_token_address(&deps, self.pair_address.clone())?;
+
+        let pool_info: astroport::pair::PoolResponse = deps.querier.query_wasm_smart(
+            &self.pair_address,
+            &astroport::pair_stable_bluna::QueryMsg::Pool {},
+        )?;
+
+        let requested_asset = astroport::asset::Asset {
+            amount,
+            info: astroport::asset::AssetInfo::NativeToken {
+                denom: LUNA_DENOM.to_string(),
+            },
+        };
+
+        let share_to_withdraw =
+            get_share_amount(&deps, self.pair_address.clone(), requested_asset.clone())?;
+
+        // cw20 send message that transfers the LP tokens to the pair address and withdraws liquidity
+        let cw20_withdraw_liquidity_msg: CosmosMsg = WasmMsg::Execute {
+            contract_addr: astro_lp_token_address.into_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: self.pair_address.to_string(),
+                amount: share_to_withdraw,
+                msg: to_binary(&astroport::pair::Cw20HookMsg::WithdrawLiquidity {})?,
+            })?,
+            funds: vec![],
+        }
+        .into();
+
+        // provide liquidity with the remaining bLuna as single-sided LP provision
+        let pool_non_desired_asset = pool_info
+            .assets
+            .iter()
+            .find(|asset| asset.info != requested_asset.info)
+            .ok_or_else(|| {
+                LunaVaultError::generic_err(
+                    "Failed to get non-desired asset when providing single-sided liquidity",
+                )
+            })?;
+
+        // reference: https://github.com/astroport-fi/astroport-core/blob/c0a121798157ea3e5540a19b8061eb0196b15667/contracts/pair_stable_bluna/src/contract.rs#L729-L737
+        let unwanted_amount_returned =
+            Decimal::from_ratio(share_to_withdraw, pool_info.total_share)
+                * pool_non_desired_asset.amount;
+
+        let bluna_lp_msg = WasmMsg::Execute {
+            contract_addr: self.pair_address.to_string(),
+            msg: to_binary(
+                &astroport::pair_stable_bluna::ExecuteMsg::ProvideLiquidity {
+                    assets: [
+                        astroport::asset::Asset {
+                            amount: Uint128::zero(),
+                            info: requested_asset.info.clone(),
+                        },
+                        astroport::asset::Asset {
+                            amount: unwanted_amount_returned,
+                            info: pool_non_desired_asset.info.clone(),
+                        },
+                    ],
+                    slippage_tolerance: None,
+                    auto_stake: None,
+                    receiver: None,
+                },
+            )?,
+            funds: vec![],
+        }
+        .into();
+
+        // the shares are rounded up, so the LP returns at least the requested luna
+        Ok(Withdrawal {
+            messages: vec![
+                cw20_withdraw_liquidity_msg, // 1. withdraw bluna and Luna from LP.
+                bluna_lp_msg, // 2. provide single-sided LP provision to astroport pool
+            ],
+            luna_return: amount,
+        })
+    }
+
+    fn value(&self, deps: Deps, rates: &HubExchangeRates) -> VaultResult<StrategyValue> {
+        let (luna_amount, bluna_amount) =
+            query_lp_position(deps, &self.pair_address, &self.vault_address)?;
+
+        Ok(StrategyValue {
+            value_in_luna: luna_amount + rates.bluna * bluna_amount,
+            nominal_value_in_luna: luna_amount + bluna_amount,
+        })
+    }
+
+    // Claims the ASTRO rewards from the generator and swaps them for luna
+    fn harvest(&self, deps: Deps, swap: &RewardsSwap) -> VaultResult<Harvest> {
+        let passive_lp_token_address = get_lp_token_address(&deps, self.pair_address.clone())?;
+
+        // get generator address
+        let astro_factory_config: astroport::factory::ConfigResponse =
+            deps.querier.query_wasm_smart(
+                &self.factory_address,
+                &astroport::factory::QueryMsg::Config {},
+            )?;
+        let astro_generator_address = astro_factory_config.generator_address.ok_or_else(|| {
+            LunaVaultError::generic_err("Astroport generator was not set in factory config")
+        })?;
+
+        // get the ASTRO rewards pending for the vault
+        let pending_tokens: astroport::generator::PendingTokenResponse =
+            deps.querier.query_wasm_smart(
+                astro_generator_address.clone(),
+                &astroport::generator::QueryMsg::PendingToken {
+                    lp_token: passive_lp_token_address.clone().into_string(),
+                    user: self.vault_address.to_string(),
+                },
+            )?;
+        if pending_tokens.pending.is_zero() {
+            return Ok(Harvest::default());
+        }
+
+        // get ASTRO token address
+        let astro_generator_config: astroport::generator_proxy::ConfigResponse =
+            deps.querier.query_wasm_smart(
+                astro_generator_address.clone(),
+                &astroport::generator::QueryMsg::Config {},
+            )?;
+        let astro_token_address = deps
+            .api
+            .addr_validate(&*astro_generator_config.reward_token_addr)?;
+        let astro_pending = Asset {
+            amount: pending_tokens.pending,
+            info: AssetInfo::Token {
+                contract_addr: astro_token_address.into_string(),
+            },
+        };
+
+        // withdraw ASTRO rewards
+        let withdraw_rewards_msg: CosmosMsg = WasmMsg::Execute {
+            contract_addr: astro_generator_address.into_string(),
+            msg: to_binary(&astroport::generator::ExecuteMsg::ClaimRewards {
+                lp_tokens: vec![passive_lp_token_address.into_string()],
+            })?,
+            funds: vec![],
+        }
+        .into();
+
+        // swap ASTRO into Luna, either on the ASTRO-Luna pair or along the configured swap route
+        let (swap_luna_return, swap_astro_message) =
+            swap.swap_for_luna(deps, &self.factory_address, &astro_pending)?;
+
+        Ok(Harvest {
+            messages: vec![withdraw_rewards_msg, swap_astro_message],
+            attributes: vec![
+                attr("astro_swapped", astro_pending.amount),
+                attr("luna_return", swap_luna_return),
+            ],
+            rewards: vec![astro_pending],
+            luna_return: swap_luna_return,
+        })
+    }
+}
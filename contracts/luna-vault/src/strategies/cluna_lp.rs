@@ -0,0 +1,152 @@
+use astroport::querier::query_token_balance;
+use cosmwasm_std::{coins, to_binary, Addr, CosmosMsg, Deps, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+use white_whale::denom::LUNA_DENOM;
+
+use crate::contract::VaultResult;
+use crate::helpers::{get_lp_token_address, get_share_amount, HubExchangeRates};
+use crate::strategies::{
+    query_lp_position, Harvest, PassiveStrategy, RewardsSwap, StrategyValue, Withdrawal,
+};
+
+/// LP of a cLuna-Luna pair implementing the Astroport pair interface, i.e. the Prism cLuna-Luna pair
+pub struct ClunaLp {
+    pub pair_address: Addr,
+    pub cluna_address: Addr,
+    pub vault_address: Addr,
+}
+
+impl PassiveStrategy for ClunaLp {
+    // Deposits Luna into the luna-cluna LP
+    fn deposit(&self, deps: Deps, amount: Uint128) -> VaultResult<Vec<CosmosMsg>> {
+        // split luna into half so half goes to purchase cLuna, remaining half is used as liquidity
+        let luna_asset = astroport::asset::Asset {
+            amount: amount.checked_div(Uint128::from(2_u8))?,
+            info: astroport::asset::AssetInfo::NativeToken {
+                denom: LUNA_DENOM.to_string(),
+            },
+        };
+
+        // simulate the luna swap so we know the cluna return amount when we later provide liquidity
+        let cluna_return: astroport::pair::SimulationResponse = deps.querier.query_wasm_smart(
+            &self.pair_address,
+            &astroport::pair::QueryMsg::Simulation {
+                offer_asset: luna_asset.clone(),
+            },
+        )?;
+
+        let cluna_asset = astroport::asset::Asset {
+            amount: cluna_return.return_amount,
+            info: astroport::asset::AssetInfo::Token {
+                contract_addr: self.cluna_address.clone(),
+            },
+        };
+
+        let cluna_purchase_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.pair_address.to_string(),
+            msg: to_binary(&astroport::pair::ExecuteMsg::Swap {
+                offer_asset: luna_asset.clone(),
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            })?,
+            funds: coins(luna_asset.amount.u128(), LUNA_DENOM),
+        });
+
+        // allow the pair to take the purchased cluna when providing liquidity
+        let cluna_allowance_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.cluna_address.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                spender: self.pair_address.to_string(),
+                amount: cluna_asset.amount,
+                expires: None,
+            })?,
+            funds: vec![],
+        });
+
+        let deposit_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.pair_address.to_string(),
+            msg: to_binary(&astroport::pair::ExecuteMsg::ProvideLiquidity {
+                assets: [luna_asset.clone(), cluna_asset],
+                slippage_tolerance: None,
+                auto_stake: None,
+                receiver: None,
+            })?,
+            funds: coins(luna_asset.amount.u128(), LUNA_DENOM),
+        });
+
+        Ok(vec![
+            cluna_purchase_msg,  // 1. purchase cluna
+            cluna_allowance_msg, // 2. allow the pair to take the cluna
+            deposit_msg,         // 3. deposit cLuna/Luna to the LP as liquidity
+        ])
+    }
+
+    // Withdraws Luna from the luna-cluna LP -> Luna + cLuna. The cLuna stays in the vault, the luna
+    // returned falls short of the amount if the vault doesn't hold enough LP tokens
+    fn withdraw(&self, deps: Deps, amount: Uint128) -> VaultResult<Withdrawal> {
+        let lp_token_address = get_lp_token_address(&deps, self.pair_address.clone())?;
+
+        let requested_asset = astroport::asset::Asset {
+            amount,
+            info: astroport::asset::AssetInfo::NativeToken {
+                denom: LUNA_DENOM.to_string(),
+            },
+        };
+        let requested_asset_info = requested_asset.info.clone();
+        let share_to_withdraw =
+            get_share_amount(&deps, self.pair_address.clone(), requested_asset)?.min(
+                query_token_balance(
+                    &deps.querier,
+                    lp_token_address.clone(),
+                    self.vault_address.clone(),
+                )?,
+            );
+
+        if share_to_withdraw.is_zero() {
+            return Ok(Withdrawal::default());
+        }
+
+        let pool_info: astroport::pair::PoolResponse = deps
+            .querier
+            .query_wasm_smart(&self.pair_address, &astroport::pair::QueryMsg::Pool {})?;
+        let pool_luna_amount = pool_info
+            .assets
+            .iter()
+            .find(|asset| asset.info == requested_asset_info)
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+
+        // cw20 send message that transfers the LP tokens to the pair address and withdraws liquidity
+        Ok(Withdrawal {
+            messages: vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: lp_token_address.into_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: self.pair_address.to_string(),
+                    amount: share_to_withdraw,
+                    msg: to_binary(&astroport::pair::Cw20HookMsg::WithdrawLiquidity {})?,
+                })?,
+                funds: vec![],
+            })],
+            luna_return: share_to_withdraw
+                .multiply_ratio(pool_luna_amount, pool_info.total_share)
+                .min(amount),
+        })
+    }
+
+    fn value(&self, deps: Deps, rates: &HubExchangeRates) -> VaultResult<StrategyValue> {
+        let (luna_amount, cluna_amount) =
+            query_lp_position(deps, &self.pair_address, &self.vault_address)?;
+
+        Ok(StrategyValue {
+            value_in_luna: luna_amount + rates.cluna * cluna_amount,
+            nominal_value_in_luna: luna_amount + cluna_amount,
+        })
+    }
+
+    // The cLuna-Luna LP doesn't earn rewards on top of the swap fees
+    fn harvest(&self, _deps: Deps, _swap: &RewardsSwap) -> VaultResult<Harvest> {
+        Ok(Harvest::default())
+    }
+}
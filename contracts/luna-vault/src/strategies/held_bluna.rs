@@ -0,0 +1,124 @@
+use astroport::querier::query_token_balance;
+use cosmwasm_std::{coins, to_binary, Addr, CosmosMsg, Decimal, Deps, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+use white_whale::denom::LUNA_DENOM;
+
+use crate::contract::VaultResult;
+use crate::helpers::HubExchangeRates;
+use crate::strategies::{
+    withdraw_max_spread, Harvest, PassiveStrategy, RewardsSwap, StrategyValue, Withdrawal,
+};
+
+/// bLuna held by the vault, bought on the Astroport bLuna-Luna pair below the Anchor hub
+/// exchange rate so it can be burnt for a discount
+pub struct HeldBluna {
+    pub pair_address: Addr,
+    pub bluna_address: Addr,
+    pub vault_address: Addr,
+}
+
+impl PassiveStrategy for HeldBluna {
+    // Buys bLuna with the given amount of luna
+    fn deposit(&self, _deps: Deps, amount: Uint128) -> VaultResult<Vec<CosmosMsg>> {
+        let luna_asset = astroport::asset::Asset {
+            amount,
+            info: astroport::asset::AssetInfo::NativeToken {
+                denom: LUNA_DENOM.to_string(),
+            },
+        };
+
+        Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.pair_address.to_string(),
+            msg: to_binary(&astroport::pair::ExecuteMsg::Swap {
+                offer_asset: luna_asset,
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            })?,
+            funds: coins(amount.u128(), LUNA_DENOM),
+        })])
+    }
+
+    // Sells the bLuna needed to get the given amount of luna back, the luna returned being whatever
+    // the bLuna sells for at most at the max spread from the simulated return
+    fn withdraw(&self, deps: Deps, amount: Uint128) -> VaultResult<Withdrawal> {
+        let reverse_simulation: astroport::pair::ReverseSimulationResponse =
+            deps.querier.query_wasm_smart(
+                &self.pair_address,
+                &astroport::pair::QueryMsg::ReverseSimulation {
+                    ask_asset: astroport::asset::Asset {
+                        amount,
+                        info: astroport::asset::AssetInfo::NativeToken {
+                            denom: LUNA_DENOM.to_string(),
+                        },
+                    },
+                },
+            )?;
+
+        // can't sell more bLuna than what the vault holds
+        let bluna_balance = query_token_balance(
+            &deps.querier,
+            self.bluna_address.clone(),
+            self.vault_address.clone(),
+        )?;
+        let bluna_amount = reverse_simulation.offer_amount.min(bluna_balance);
+        if bluna_amount.is_zero() {
+            return Ok(Withdrawal::default());
+        }
+
+        let simulation: astroport::pair::SimulationResponse = deps.querier.query_wasm_smart(
+            &self.pair_address,
+            &astroport::pair::QueryMsg::Simulation {
+                offer_asset: astroport::asset::Asset {
+                    amount: bluna_amount,
+                    info: astroport::asset::AssetInfo::Token {
+                        contract_addr: self.bluna_address.clone(),
+                    },
+                },
+            },
+        )?;
+        if simulation.return_amount.is_zero() {
+            return Ok(Withdrawal::default());
+        }
+        let max_spread = withdraw_max_spread();
+
+        Ok(Withdrawal {
+            messages: vec![CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.bluna_address.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: self.pair_address.to_string(),
+                    amount: bluna_amount,
+                    msg: to_binary(&astroport::pair::Cw20HookMsg::Swap {
+                        belief_price: Some(Decimal::from_ratio(
+                            bluna_amount,
+                            simulation.return_amount,
+                        )),
+                        max_spread: Some(max_spread),
+                        to: None,
+                    })?,
+                })?,
+                funds: vec![],
+            })],
+            luna_return: (Decimal::one() - max_spread) * simulation.return_amount,
+        })
+    }
+
+    fn value(&self, deps: Deps, rates: &HubExchangeRates) -> VaultResult<StrategyValue> {
+        let bluna_amount = query_token_balance(
+            &deps.querier,
+            self.bluna_address.clone(),
+            self.vault_address.clone(),
+        )?;
+
+        Ok(StrategyValue {
+            value_in_luna: rates.bluna * bluna_amount,
+            nominal_value_in_luna: bluna_amount,
+        })
+    }
+
+    // Held bLuna doesn't earn rewards, the yield comes from burning it
+    fn harvest(&self, _deps: Deps, _swap: &RewardsSwap) -> VaultResult<Harvest> {
+        Ok(Harvest::default())
+    }
+}
@@ -0,0 +1,462 @@
+use astroport::querier::query_token_balance;
+use cosmwasm_std::{
+    coins, to_binary, Addr, Api, Attribute, CosmosMsg, Decimal, Deps, Env, StdResult, Storage,
+    Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::querier::query_balance;
+
+use white_whale::astroport_helper::{
+    AstroportCw20HookMsg, AstroportExecuteMsg, AstroportQueryMsg, SimulateSwapOperationsResponse,
+    SwapOperation,
+};
+use white_whale::denom::LUNA_DENOM;
+use white_whale::luna_vault::msg::{
+    HarvestConfig, PassiveStrategyKind, StrategyInfo, StrategyResponse,
+};
+
+use crate::contract::VaultResult;
+use crate::error::LunaVaultError;
+use crate::helpers::{
+    get_lp_token_address, query_hub_exchange_rates, query_released_reserved_unbonds,
+    HubExchangeRates,
+};
+use crate::state::{STATE, STRATEGIES};
+
+pub(crate) mod astroport_lp;
+pub(crate) mod cluna_lp;
+pub(crate) mod held_bluna;
+
+/// Passive strategy the vault deposits its idle luna into to earn a yield
+pub trait PassiveStrategy {
+    /// Builds the messages depositing the given amount of luna from the vault into the strategy
+    fn deposit(&self, deps: Deps, amount: Uint128) -> VaultResult<Vec<CosmosMsg>>;
+    /// Builds the messages withdrawing the given amount of luna from the strategy into the vault
+    fn withdraw(&self, deps: Deps, amount: Uint128) -> VaultResult<Withdrawal>;
+    /// Values the position of the vault in the strategy
+    fn value(&self, deps: Deps, rates: &HubExchangeRates) -> VaultResult<StrategyValue>;
+    /// Builds the messages claiming the strategy rewards and swapping them for luna
+    fn harvest(&self, deps: Deps, swap: &RewardsSwap) -> VaultResult<Harvest>;
+}
+
+/// Value of the position of the vault in a passive strategy
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StrategyValue {
+    /// value with bLuna and cLuna valued at the Anchor and Prism hub exchange rates
+    pub value_in_luna: Uint128,
+    /// value assuming 1 bLuna = 1 cLuna = 1 Luna
+    pub nominal_value_in_luna: Uint128,
+}
+
+/// Messages withdrawing luna from a passive strategy, along with the luna the vault is guaranteed
+/// to get back. It can fall short of the requested amount because of the swap slippage
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Withdrawal {
+    pub messages: Vec<CosmosMsg>,
+    pub luna_return: Uint128,
+}
+
+/// Max spread of the swaps selling bLuna or cLuna for luna when withdrawing from the strategies
+pub fn withdraw_max_spread() -> Decimal {
+    Decimal::permille(5)
+}
+
+/// Messages harvesting the rewards of a passive strategy, along with the luna they are expected to return
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Harvest {
+    pub messages: Vec<CosmosMsg>,
+    /// reward tokens swapped for luna
+    pub rewards: Vec<Asset>,
+    pub luna_return: Uint128,
+    pub attributes: Vec<Attribute>,
+}
+
+/// How the rewards of the passive strategies are swapped for luna when harvested
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RewardsSwap {
+    pub config: HarvestConfig,
+    pub max_spread: Option<Decimal>,
+}
+
+impl RewardsSwap {
+    /// Simulates the swap of the given reward for luna and builds the message swapping it, either
+    /// directly on the reward-luna pair of the Astroport factory or through the router along the
+    /// swap route
+    pub fn swap_for_luna(
+        &self,
+        deps: Deps,
+        factory_address: &Addr,
+        reward: &Asset,
+    ) -> VaultResult<(Uint128, CosmosMsg)> {
+        match &self.config.router_address {
+            Some(router_address) if !self.config.swap_route.is_empty() => {
+                self.swap_through_router(deps, router_address, reward)
+            }
+            _ => self.swap_on_pair(deps, factory_address, reward),
+        }
+    }
+
+    fn swap_on_pair(
+        &self,
+        deps: Deps,
+        factory_address: &Addr,
+        reward: &Asset,
+    ) -> VaultResult<(Uint128, CosmosMsg)> {
+        let luna_info = AssetInfo::NativeToken {
+            denom: LUNA_DENOM.to_string(),
+        };
+
+        let pair: astroport::asset::PairInfo = deps.querier.query_wasm_smart(
+            factory_address,
+            &astroport::factory::QueryMsg::Pair {
+                asset_infos: [
+                    to_astroport_asset_info(&reward.info),
+                    to_astroport_asset_info(&luna_info),
+                ],
+            },
+        )?;
+        let simulation: astroport::pair::SimulationResponse = deps.querier.query_wasm_smart(
+            &pair.contract_addr,
+            &astroport::pair::QueryMsg::Simulation {
+                offer_asset: astroport::asset::Asset {
+                    info: to_astroport_asset_info(&reward.info),
+                    amount: reward.amount,
+                },
+            },
+        )?;
+
+        let swap_msg = match &reward.info {
+            // cw20 rewards are sent to the pair with a swap hook
+            AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                contract_addr: contract_addr.clone(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: pair.contract_addr.into_string(),
+                    amount: reward.amount,
+                    msg: to_binary(&astroport::pair::Cw20HookMsg::Swap {
+                        belief_price: None,
+                        max_spread: self.max_spread,
+                        to: None,
+                    })?,
+                })?,
+                funds: vec![],
+            },
+            AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+                contract_addr: pair.contract_addr.into_string(),
+                msg: to_binary(&astroport::pair::ExecuteMsg::Swap {
+                    offer_asset: astroport::asset::Asset {
+                        info: to_astroport_asset_info(&reward.info),
+                        amount: reward.amount,
+                    },
+                    belief_price: None,
+                    max_spread: self.max_spread,
+                    to: None,
+                })?,
+                funds: coins(reward.amount.u128(), denom),
+            },
+        };
+
+        Ok((simulation.return_amount, swap_msg.into()))
+    }
+
+    fn swap_through_router(
+        &self,
+        deps: Deps,
+        router_address: &Addr,
+        reward: &Asset,
+    ) -> VaultResult<(Uint128, CosmosMsg)> {
+        // reward -> swap route -> luna
+        let route: Vec<AssetInfo> = std::iter::once(reward.info.clone())
+            .chain(self.config.swap_route.iter().cloned())
+            .chain(std::iter::once(AssetInfo::NativeToken {
+                denom: LUNA_DENOM.to_string(),
+            }))
+            .collect();
+        let operations: Vec<SwapOperation> = route
+            .windows(2)
+            .map(|hop| SwapOperation::AstroSwap {
+                offer_asset_info: hop[0].clone(),
+                ask_asset_info: hop[1].clone(),
+            })
+            .collect();
+
+        let simulation: SimulateSwapOperationsResponse = deps.querier.query_wasm_smart(
+            router_address,
+            &AstroportQueryMsg::SimulateSwapOperations {
+                offer_amount: reward.amount,
+                operations: operations.clone(),
+            },
+        )?;
+        // the router doesn't take a max spread, so it's enforced on the luna received at the end of the route
+        let minimum_receive = self
+            .max_spread
+            .map(|max_spread| (Decimal::one() - max_spread) * simulation.amount);
+
+        let swap_msg = match &reward.info {
+            AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                contract_addr: contract_addr.clone(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: router_address.to_string(),
+                    amount: reward.amount,
+                    msg: to_binary(&AstroportCw20HookMsg::ExecuteSwapOperations {
+                        operations,
+                        minimum_receive,
+                        to: None,
+                    })?,
+                })?,
+                funds: vec![],
+            },
+            AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+                contract_addr: router_address.to_string(),
+                msg: to_binary(&AstroportExecuteMsg::ExecuteSwapOperations {
+                    operations,
+                    minimum_receive,
+                    to: None,
+                })?,
+                funds: coins(reward.amount.u128(), denom),
+            },
+        };
+
+        Ok((simulation.amount, swap_msg.into()))
+    }
+}
+
+fn to_astroport_asset_info(info: &AssetInfo) -> astroport::asset::AssetInfo {
+    match info {
+        AssetInfo::Token { contract_addr } => astroport::asset::AssetInfo::Token {
+            contract_addr: Addr::unchecked(contract_addr),
+        },
+        AssetInfo::NativeToken { denom } => astroport::asset::AssetInfo::NativeToken {
+            denom: denom.clone(),
+        },
+    }
+}
+
+/// Strategies used by vaults instantiated before the passive strategies were configurable
+fn default_strategies() -> Vec<StrategyInfo> {
+    vec![StrategyInfo {
+        strategy: PassiveStrategyKind::AstroportBlunaLp,
+        target_weight: Decimal::one(),
+    }]
+}
+
+/// Loads the passive strategies of the vault
+pub fn load_strategies(storage: &dyn Storage) -> StdResult<Vec<StrategyInfo>> {
+    Ok(STRATEGIES
+        .may_load(storage)?
+        .unwrap_or_else(default_strategies))
+}
+
+/// Validates the given passive strategies, making sure they are not duplicated and that their
+/// target weights add up to 1
+pub fn validate_strategies(
+    api: &dyn Api,
+    strategies: Vec<StrategyInfo>,
+) -> VaultResult<Vec<StrategyInfo>> {
+    let total_weight = strategies.iter().fold(Decimal::zero(), |acc, strategy| {
+        acc + strategy.target_weight
+    });
+    if total_weight != Decimal::one() {
+        return Err(LunaVaultError::InvalidStrategyWeights {});
+    }
+
+    let mut validated_strategies: Vec<StrategyInfo> = vec![];
+    for mut strategy in strategies {
+        if let PassiveStrategyKind::ClunaLp { pair_address } = &strategy.strategy {
+            strategy.strategy = PassiveStrategyKind::ClunaLp {
+                pair_address: api.addr_validate(pair_address)?.into_string(),
+            };
+        }
+        if validated_strategies
+            .iter()
+            .any(|validated| validated.strategy == strategy.strategy)
+        {
+            return Err(LunaVaultError::DuplicatedStrategy {});
+        }
+        validated_strategies.push(strategy);
+    }
+
+    Ok(validated_strategies)
+}
+
+/// Builds the passive strategy implementation for the given strategy kind
+pub fn load_strategy(
+    deps: Deps,
+    env: &Env,
+    kind: &PassiveStrategyKind,
+) -> VaultResult<Box<dyn PassiveStrategy>> {
+    let state = STATE.load(deps.storage)?;
+    let vault_address = env.contract.address.clone();
+
+    Ok(match kind {
+        PassiveStrategyKind::AstroportBlunaLp => Box::new(astroport_lp::AstroportBlunaLp {
+            pair_address: state.astro_lp_address,
+            factory_address: state.astro_factory_address,
+            bluna_address: state.bluna_address,
+            vault_address,
+        }),
+        PassiveStrategyKind::HeldBluna => Box::new(held_bluna::HeldBluna {
+            pair_address: state.astro_lp_address,
+            bluna_address: state.bluna_address,
+            vault_address,
+        }),
+        // the pair address is validated when the strategies are set
+        PassiveStrategyKind::ClunaLp { pair_address } => Box::new(cluna_lp::ClunaLp {
+            pair_address: Addr::unchecked(pair_address),
+            cluna_address: state.cluna_address,
+            vault_address,
+        }),
+    })
+}
+
+/// Values the position of the vault in each of its passive strategies
+pub fn query_strategies_values(
+    deps: Deps,
+    env: &Env,
+    rates: &HubExchangeRates,
+) -> VaultResult<Vec<(StrategyInfo, StrategyValue)>> {
+    load_strategies(deps.storage)?
+        .into_iter()
+        .map(|strategy| {
+            let value = load_strategy(deps, env, &strategy.strategy)?.value(deps, rates)?;
+            Ok((strategy, value))
+        })
+        .collect()
+}
+
+/// Breaks the value held by the passive strategies down per strategy
+pub fn strategies_breakdown(
+    strategies: Vec<(StrategyInfo, StrategyValue)>,
+    strategies_value_in_luna: Uint128,
+) -> Vec<StrategyResponse> {
+    strategies
+        .into_iter()
+        .map(|(strategy, value)| StrategyResponse {
+            strategy: strategy.strategy,
+            target_weight: strategy.target_weight,
+            current_weight: if strategies_value_in_luna.is_zero() {
+                Decimal::zero()
+            } else {
+                Decimal::from_ratio(value.value_in_luna, strategies_value_in_luna)
+            },
+            value_in_luna: value.value_in_luna,
+        })
+        .collect()
+}
+
+/// Builds the messages depositing the given amount of luna into the passive strategies,
+/// split according to their target weights
+pub fn deposit_into_strategies(
+    deps: Deps,
+    env: &Env,
+    amount: Uint128,
+) -> VaultResult<Vec<CosmosMsg>> {
+    let strategies = load_strategies(deps.storage)?;
+    let mut messages = vec![];
+    let mut remaining_amount = amount;
+    for (i, strategy) in strategies.iter().enumerate() {
+        // the last strategy gets whatever is left after rounding
+        let deposit_amount = if i == strategies.len() - 1 {
+            remaining_amount
+        } else {
+            strategy.target_weight * amount
+        };
+        remaining_amount = remaining_amount.checked_sub(deposit_amount)?;
+
+        if !deposit_amount.is_zero() {
+            messages.extend(
+                load_strategy(deps, env, &strategy.strategy)?.deposit(deps, deposit_amount)?,
+            );
+        }
+    }
+
+    Ok(messages)
+}
+
+/// Builds the messages withdrawing the given value in luna from the passive strategies,
+/// in the order the strategies were set
+pub fn withdraw_from_strategies(
+    deps: Deps,
+    env: &Env,
+    rates: &HubExchangeRates,
+    amount: Uint128,
+) -> VaultResult<Withdrawal> {
+    let mut withdrawal = Withdrawal::default();
+    let mut remaining_amount = amount;
+    for strategy in load_strategies(deps.storage)? {
+        if remaining_amount.is_zero() {
+            break;
+        }
+
+        let strategy = load_strategy(deps, env, &strategy.strategy)?;
+        let withdraw_amount = strategy
+            .value(deps, rates)?
+            .value_in_luna
+            .min(remaining_amount);
+        if !withdraw_amount.is_zero() {
+            let strategy_withdrawal = strategy.withdraw(deps, withdraw_amount)?;
+            withdrawal.messages.extend(strategy_withdrawal.messages);
+            withdrawal.luna_return += strategy_withdrawal.luna_return;
+            remaining_amount = remaining_amount.checked_sub(withdraw_amount)?;
+        }
+    }
+
+    if !remaining_amount.is_zero() {
+        return Err(LunaVaultError::Broke {});
+    }
+
+    Ok(withdrawal)
+}
+
+/// Builds the messages withdrawing the given value in luna out of the vault, taking it from the
+/// vault's liquid luna first and then from the passive strategies. The luna reserved for users from
+/// released unbonds is left out
+pub fn withdraw_from_vault(deps: Deps, env: &Env, amount: Uint128) -> VaultResult<Withdrawal> {
+    let luna_balance = query_balance(
+        &deps.querier,
+        env.contract.address.clone(),
+        LUNA_DENOM.to_string(),
+    )?;
+    let liquid_luna = luna_balance.saturating_sub(query_released_reserved_unbonds(deps, env)?);
+    let strategies_withdraw_amount = amount.saturating_sub(liquid_luna);
+
+    let rates = query_hub_exchange_rates(deps)?;
+    let mut withdrawal = withdraw_from_strategies(deps, env, &rates, strategies_withdraw_amount)?;
+    withdrawal.luna_return += amount.checked_sub(strategies_withdraw_amount)?;
+    Ok(withdrawal)
+}
+
+/// Queries the luna and non-luna token amounts underlying the LP tokens of the given pair held by the vault
+pub(crate) fn query_lp_position(
+    deps: Deps,
+    pair_address: &Addr,
+    vault_address: &Addr,
+) -> VaultResult<(Uint128, Uint128)> {
+    let lp_token_address = get_lp_token_address(&deps, pair_address.clone())?;
+    let lp_amount = query_token_balance(&deps.querier, lp_token_address, vault_address.clone())?;
+    if lp_amount.is_zero() {
+        return Ok((Uint128::zero(), Uint128::zero()));
+    }
+
+    let lp_assets: [astroport::asset::Asset; 2] = deps.querier.query_wasm_smart(
+        pair_address,
+        &astroport::pair::QueryMsg::Share { amount: lp_amount },
+    )?;
+    let luna_asset_info = astroport::asset::AssetInfo::NativeToken {
+        denom: LUNA_DENOM.to_string(),
+    };
+    let (luna_amount, token_amount) = lp_assets.iter().fold(
+        (Uint128::zero(), Uint128::zero()),
+        |(luna, token), asset| {
+            if asset.info == luna_asset_info {
+                (luna + asset.amount, token)
+            } else {
+                (luna, token + asset.amount)
+            }
+        },
+    );
+
+    Ok((luna_amount, token_amount))
+}
@@ -1,11 +1,11 @@
-use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{from_binary, to_binary, MessageInfo, ReplyOn, SubMsg, WasmMsg};
-use cosmwasm_std::{Api, Decimal};
-
-use crate::contract::{execute, instantiate, query};
-use crate::state::{State, STATE};
-use cw20::MinterResponse;
-use terraswap::asset::AssetInfo;
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{coins, from_binary, to_binary, MessageInfo, ReplyOn, SubMsg, WasmMsg};
+use cosmwasm_std::{Addr, Api, CosmosMsg, Decimal, OwnedDeps, Response, Uint128};
+
+use crate::contract::{execute, instantiate, query, VaultResult};
+use crate::state::{State, POOL_INFO, STATE};
+use cw20::{Cw20ExecuteMsg, MinterResponse};
+use terraswap::asset::{Asset, AssetInfo};
 use white_whale::luna_vault::msg::InstantiateMsg as VaultInstantiateMsg;
 
 use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
@@ -16,7 +16,7 @@ use white_whale::luna_vault::msg::*;
 use crate::tests::common::{ARB_CONTRACT, TEST_CREATOR};
 
 use crate::tests::instantiate::mock_instantiate;
-use crate::tests::mock_querier::mock_dependencies;
+use crate::tests::mock_querier::{mock_dependencies, WasmMockQuerier};
 
 const INSTANTIATE_REPLY_ID: u8 = 1u8;
 use crate::error::LunaVaultError;
@@ -85,6 +85,9 @@ fn successful_update_fee() {
         commission_fee: Some(Fee {
             share: Decimal::percent(2),
         }),
+        instant_withdraw_fee: Some(Fee {
+            share: Decimal::percent(1),
+        }),
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -97,6 +100,7 @@ fn successful_update_fee() {
     assert_eq!(Decimal::percent(2), fees.treasury_fee.share);
     assert_eq!(Decimal::percent(2), fees.commission_fee.share);
     assert_eq!(Decimal::percent(2), fees.flash_loan_fee.share);
+    assert_eq!(Decimal::percent(1), fee_response.instant_withdraw_fee.share);
 }
 
 #[test]
@@ -116,6 +120,9 @@ fn sad_path_update_fee() {
         commission_fee: Some(Fee {
             share: Decimal::percent(200),
         }),
+        instant_withdraw_fee: Some(Fee {
+            share: Decimal::percent(200),
+        }),
     };
     // Also test with exactly 100. We cant set fees as 100 otherwise theres nothing but fees
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
@@ -133,6 +140,9 @@ fn sad_path_update_fee() {
         commission_fee: Some(Fee {
             share: Decimal::percent(100),
         }),
+        instant_withdraw_fee: Some(Fee {
+            share: Decimal::percent(100),
+        }),
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
@@ -223,3 +233,182 @@ fn test_init_with_non_default_vault_lp_token() {
         }]
     );
 }
+
+#[test]
+fn successful_set_deposit_caps() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // uncapped by default
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::DepositCaps {}).unwrap();
+    let deposit_caps: DepositCaps = from_binary(&res).unwrap();
+    assert_eq!(deposit_caps, DepositCaps::default());
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let msg = ExecuteMsg::SetDepositCaps {
+        tvl_cap: Some(Uint128::new(1_000_000u128)),
+        per_address_cap: Some(Uint128::new(10_000u128)),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::DepositCaps {}).unwrap();
+    let deposit_caps: DepositCaps = from_binary(&res).unwrap();
+    assert_eq!(
+        deposit_caps,
+        DepositCaps {
+            tvl_cap: Some(Uint128::new(1_000_000u128)),
+            per_address_cap: Some(Uint128::new(10_000u128)),
+        }
+    );
+}
+
+#[test]
+fn unsuccessful_set_deposit_caps_unauthorized() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let info = mock_info("unauthorized", &[]);
+    let msg = ExecuteMsg::SetDepositCaps {
+        tvl_cap: Some(Uint128::new(1_000_000u128)),
+        per_address_cap: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(LunaVaultError::Admin(_)) => (),
+        _ => panic!("Must return LunaVaultError::Admin"),
+    }
+}
+
+const DEPOSITOR: &str = "depositor";
+
+/// Vault holding 1000 luna, with 1000 vLuna of which the depositor holds the given amount,
+/// and a pending deposit of the given amount of luna
+fn mock_capped_vault(
+    deposit: u128,
+    depositor_share: u128,
+    deposit_caps: DepositCaps,
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&coins(1000 + deposit, "uluna"));
+    mock_instantiate(deps.as_mut());
+    POOL_INFO
+        .update(deps.as_mut().storage, |mut info| -> VaultResult<_> {
+            info.liquidity_token = Addr::unchecked("vluna");
+            Ok(info)
+        })
+        .unwrap();
+    deps.querier.with_token_balances(&[(
+        &"vluna".to_string(),
+        &[
+            (&DEPOSITOR.to_string(), &Uint128::new(depositor_share)),
+            (
+                &"other_depositor".to_string(),
+                &Uint128::new(1000 - depositor_share),
+            ),
+        ],
+    )]);
+
+    let msg = ExecuteMsg::SetDepositCaps {
+        tvl_cap: deposit_caps.tvl_cap,
+        per_address_cap: deposit_caps.per_address_cap,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+    deps
+}
+
+fn provide_liquidity(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    deposit: u128,
+) -> VaultResult<Response> {
+    let msg = ExecuteMsg::ProvideLiquidity {
+        asset: Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::new(deposit),
+        },
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(DEPOSITOR, &coins(deposit, "uluna")),
+        msg,
+    )
+}
+
+#[test]
+fn successful_provide_liquidity_up_to_tvl_cap() {
+    let mut deps = mock_capped_vault(
+        500,
+        0,
+        DepositCaps {
+            tvl_cap: Some(Uint128::new(1500)),
+            per_address_cap: None,
+        },
+    );
+
+    // the deposit brings the vault value exactly to the cap
+    let res = provide_liquidity(&mut deps, 500).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vluna".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: DEPOSITOR.to_string(),
+                amount: Uint128::new(500),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn unsuccessful_provide_liquidity_tvl_cap_exceeded() {
+    let mut deps = mock_capped_vault(
+        500,
+        0,
+        DepositCaps {
+            tvl_cap: Some(Uint128::new(1499)),
+            per_address_cap: None,
+        },
+    );
+
+    let res = provide_liquidity(&mut deps, 500);
+    match res {
+        Err(LunaVaultError::TvlCapExceeded {}) => (),
+        _ => panic!("Must return LunaVaultError::TvlCapExceeded"),
+    }
+}
+
+#[test]
+fn successful_provide_liquidity_up_to_address_cap() {
+    // the depositor already holds 200 luna worth of vLuna
+    let mut deps = mock_capped_vault(
+        300,
+        200,
+        DepositCaps {
+            tvl_cap: None,
+            per_address_cap: Some(Uint128::new(500)),
+        },
+    );
+
+    provide_liquidity(&mut deps, 300).unwrap();
+}
+
+#[test]
+fn unsuccessful_provide_liquidity_address_cap_exceeded() {
+    let mut deps = mock_capped_vault(
+        300,
+        200,
+        DepositCaps {
+            tvl_cap: None,
+            per_address_cap: Some(Uint128::new(499)),
+        },
+    );
+
+    let res = provide_liquidity(&mut deps, 300);
+    match res {
+        Err(LunaVaultError::AddressCapExceeded {}) => (),
+        _ => panic!("Must return LunaVaultError::AddressCapExceeded"),
+    }
+}
@@ -0,0 +1,159 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, Addr, Decimal, Uint128};
+use terraswap::asset::{Asset, AssetInfo};
+
+use white_whale::luna_vault::msg::VaultQueryMsg as QueryMsg;
+use white_whale::luna_vault::msg::*;
+
+use crate::contract::{execute, query};
+use crate::error::LunaVaultError;
+use crate::helpers::record_harvest;
+use crate::tests::common::TEST_CREATOR;
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::mock_dependencies;
+
+fn astro(amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::Token {
+            contract_addr: "astro_token".to_string(),
+        },
+        amount: Uint128::new(amount),
+    }
+}
+
+fn ust_route() -> Vec<AssetInfo> {
+    vec![AssetInfo::NativeToken {
+        denom: "uusd".to_string(),
+    }]
+}
+
+#[test]
+fn successful_set_harvest_config() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetHarvestConfig {
+        router_address: Some("astro_router".to_string()),
+        swap_route: ust_route(),
+        dust_threshold: Uint128::new(1_000_000u128),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::HarvestConfig {}).unwrap();
+    let harvest_config: HarvestConfig = from_binary(&res).unwrap();
+    assert_eq!(
+        harvest_config,
+        HarvestConfig {
+            router_address: Some(Addr::unchecked("astro_router")),
+            swap_route: ust_route(),
+            dust_threshold: Uint128::new(1_000_000u128),
+        }
+    );
+}
+
+#[test]
+fn unsuccessful_set_harvest_config_unauthorized() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetHarvestConfig {
+        router_address: None,
+        swap_route: vec![],
+        dust_threshold: Uint128::zero(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg,
+    );
+    match res {
+        Err(LunaVaultError::Admin(_)) => (),
+        _ => panic!("Must return LunaVaultError::Admin"),
+    }
+}
+
+#[test]
+fn unsuccessful_set_harvest_config_route_without_router() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetHarvestConfig {
+        router_address: None,
+        swap_route: ust_route(),
+        dust_threshold: Uint128::zero(),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(LunaVaultError::HarvestRouterNotSet {}) => (),
+        _ => panic!("Must return LunaVaultError::HarvestRouterNotSet"),
+    }
+}
+
+#[test]
+fn unsuccessful_swap_rewards_invalid_max_spread() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::AddToWhitelist {
+        contract_addr: "bot".to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::SwapRewards {
+        max_spread: Some(Decimal::percent(101)),
+        minimum_receive: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("bot", &[]), msg);
+    match res {
+        Err(LunaVaultError::InvalidMaxSpread {}) => (),
+        _ => panic!("Must return LunaVaultError::InvalidMaxSpread"),
+    }
+}
+
+#[test]
+fn test_query_harvest_history() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let env = mock_env();
+    record_harvest(
+        &mut deps.storage,
+        &env,
+        vec![astro(100)],
+        Uint128::new(40u128),
+    )
+    .unwrap();
+    record_harvest(
+        &mut deps.storage,
+        &env,
+        vec![astro(50)],
+        Uint128::new(25u128),
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::HarvestHistory {
+            start_after: Some(1u64),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let history: HarvestHistoryResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        history,
+        HarvestHistoryResponse {
+            total_harvests: 2u64,
+            total_rewards: vec![astro(150)],
+            total_luna_return: Uint128::new(65u128),
+            harvests: vec![HarvestRecord {
+                id: 2u64,
+                timestamp: env.block.time.seconds(),
+                rewards: vec![astro(50)],
+                luna_return: Uint128::new(25u128),
+            }],
+        }
+    );
+}
@@ -1,14 +1,17 @@
-use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{to_binary, ReplyOn, Response, SubMsg, Uint128};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{coins, from_binary, to_binary, Decimal, ReplyOn, Response, SubMsg, Uint128};
 use cw20::Cw20ReceiveMsg;
-use white_whale::luna_vault::msg::Cw20HookMsg;
+use white_whale::luna_vault::msg::{Cw20HookMsg, ValueResponse, VaultQueryMsg};
 
 use white_whale::ust_vault::msg::CallbackMsg;
+use white_whale::vault::flashloan::encapsulate_payload;
 
 use crate::commands::receive_cw20;
+use crate::contract::query;
 use crate::error::LunaVaultError;
-use crate::flashloan::encapsulate_payload;
-use crate::helpers::get_treasury_fee;
+use crate::flashloan::LunaVault;
+use crate::helpers::{compute_total_value, get_treasury_fee, query_hub_exchange_rates};
+use crate::state::POOL_INFO;
 use crate::tests::common::TEST_CREATOR;
 use crate::tests::instantiate::{mock_instantiate, TREASURY_FEE};
 use crate::tests::mock_querier::mock_dependencies;
@@ -21,7 +24,7 @@ fn test_encapsulate_payload() {
     let response = Response::new();
     let loan_fee = Uint128::new(1000);
 
-    let res = encapsulate_payload(deps.as_ref(), mock_env(), response, loan_fee).unwrap();
+    let res = encapsulate_payload(&LunaVault, &mock_env(), response, loan_fee).unwrap();
     assert_eq!(res.messages.len(), 1);
 
     let env = mock_env();
@@ -72,3 +75,58 @@ fn test_get_treasury_fee() {
         amount / Uint128::new(u128::from(TREASURY_FEE))
     );
 }
+
+#[test]
+fn test_query_hub_exchange_rates() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let rates = query_hub_exchange_rates(deps.as_ref()).unwrap();
+    assert_eq!(rates.bluna, Decimal::one());
+    assert_eq!(rates.cluna, Decimal::one());
+}
+
+#[test]
+fn test_compute_total_value_at_hub_exchange_rates() {
+    let mut deps = mock_dependencies(&coins(1000, "uluna"));
+    mock_instantiate(deps.as_mut());
+    deps.querier.with_token_balances(&[
+        (
+            &"bluna".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::new(1000))],
+        ),
+        (
+            &"cluna".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::new(1000))],
+        ),
+    ]);
+    deps.querier
+        .with_hub_exchange_rates(Decimal::percent(90), Decimal::percent(120));
+
+    let rates = query_hub_exchange_rates(deps.as_ref()).unwrap();
+    assert_eq!(rates.bluna, Decimal::percent(90));
+    assert_eq!(rates.cluna, Decimal::percent(120));
+
+    let info = POOL_INFO.load(deps.as_ref().storage).unwrap();
+    let total_value = compute_total_value(&mock_env(), deps.as_ref(), &info).unwrap();
+    assert_eq!(total_value.luna_amount, Uint128::new(1000));
+    assert_eq!(total_value.bluna_amount, Uint128::new(1000));
+    assert_eq!(total_value.bluna_value_in_luna, Uint128::new(900));
+    assert_eq!(total_value.cluna_amount, Uint128::new(1000));
+    assert_eq!(total_value.cluna_value_in_luna, Uint128::new(1200));
+    assert_eq!(total_value.total_value_in_luna, Uint128::new(3100));
+    assert_eq!(total_value.nominal_total_value_in_luna, Uint128::new(3000));
+
+    let res: ValueResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), VaultQueryMsg::VaultValue {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        res,
+        ValueResponse {
+            total_luna_value: Uint128::new(3100),
+            nominal_luna_value: Uint128::new(3000),
+            bluna_exchange_rate: Decimal::percent(90),
+            cluna_exchange_rate: Decimal::percent(120),
+        }
+    );
+}
@@ -0,0 +1,212 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coins, from_binary, to_binary, Addr, BankMsg, CosmosMsg, Decimal, OwnedDeps, Response,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use white_whale::fee::Fee;
+use white_whale::luna_vault::msg::{
+    Cw20HookMsg, ExecuteMsg, PassiveStrategyKind, SimulateWithdrawResponse, StrategyInfo,
+    VaultQueryMsg,
+};
+
+use crate::contract::{execute, query, VaultResult};
+use crate::error::LunaVaultError;
+use crate::state::{POOL_INFO, STRATEGIES};
+use crate::strategies::withdraw_max_spread;
+use crate::tests::common::TEST_CREATOR;
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+const WITHDRAWER: &str = "withdrawer";
+
+/// Vault holding 200 liquid luna and 1000 bLuna in the held bLuna strategy, with a 1% instant
+/// withdraw fee. The withdrawer holds half of the 1200 vLuna
+fn mock_vault() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&coins(200, "uluna"));
+    mock_instantiate(deps.as_mut());
+    POOL_INFO
+        .update(deps.as_mut().storage, |mut info| -> VaultResult<_> {
+            info.liquidity_token = Addr::unchecked("vluna");
+            Ok(info)
+        })
+        .unwrap();
+    STRATEGIES
+        .save(
+            deps.as_mut().storage,
+            &vec![StrategyInfo {
+                strategy: PassiveStrategyKind::HeldBluna,
+                target_weight: Decimal::one(),
+            }],
+        )
+        .unwrap();
+    deps.querier.with_token_balances(&[
+        (
+            &"vluna".to_string(),
+            &[
+                (&WITHDRAWER.to_string(), &Uint128::new(600)),
+                (&"other_depositor".to_string(), &Uint128::new(600)),
+            ],
+        ),
+        (
+            &"bluna".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::new(1000))],
+        ),
+    ]);
+    deps.querier
+        .with_bluna_swap_rate(Uint128::new(1), Uint128::new(1));
+
+    let msg = ExecuteMsg::SetFee {
+        treasury_fee: None,
+        flash_loan_fee: None,
+        commission_fee: None,
+        instant_withdraw_fee: Some(Fee {
+            share: Decimal::percent(1),
+        }),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+    deps
+}
+
+fn instant_withdraw(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    amount: u128,
+) -> VaultResult<Response> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: WITHDRAWER.to_string(),
+        amount: Uint128::new(amount),
+        msg: to_binary(&Cw20HookMsg::InstantWithdraw {}).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("vluna", &[]), msg)
+}
+
+fn simulate_withdraw(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    amount: u128,
+) -> SimulateWithdrawResponse {
+    let msg = VaultQueryMsg::SimulateWithdraw {
+        amount: Uint128::new(amount),
+    };
+    from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn successful_instant_withdraw_from_liquid_luna() {
+    let mut deps = mock_vault();
+
+    // 10 vLuna go to the treasury, the remaining 90 are worth 90 luna, below the liquid luna
+    let res = instant_withdraw(&mut deps, 100).unwrap();
+    assert_eq!(res.messages.len(), 3);
+    assert_eq!(
+        res.messages[2].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: WITHDRAWER.to_string(),
+            amount: coins(90, "uluna"),
+        })
+    );
+}
+
+#[test]
+fn successful_instant_withdraw_from_strategies() {
+    let mut deps = mock_vault();
+
+    // 60 vLuna go to the treasury, the remaining 540 are worth 540 luna, 5 of which are kept as
+    // the instant withdraw fee. The 335 luna missing from the liquid luna come from selling bLuna
+    let res = instant_withdraw(&mut deps, 600).unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|msg| msg.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vluna".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "warchest".to_string(),
+                    amount: Uint128::new(60),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vluna".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::new(540),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "bluna".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "astro".to_string(),
+                    amount: Uint128::new(335),
+                    msg: to_binary(&astroport::pair::Cw20HookMsg::Swap {
+                        belief_price: Some(Decimal::one()),
+                        max_spread: Some(withdraw_max_spread()),
+                        to: None,
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            // the withdrawer gets the 333 luna guaranteed by the bLuna sale on top of the liquid luna
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: WITHDRAWER.to_string(),
+                amount: coins(533, "uluna"),
+            }),
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "instant_withdraw"),
+            attr("from", WITHDRAWER),
+            attr("burnt_amount", "600"),
+            attr("treasury_fee", "60"),
+            attr("instant_withdraw_fee", "5"),
+            attr("luna_returned", "533"),
+        ]
+    );
+}
+
+#[test]
+fn test_simulate_withdraw_matches_instant_withdraw() {
+    let mut deps = mock_vault();
+
+    let simulation = simulate_withdraw(&deps, 600);
+    assert_eq!(
+        simulation,
+        SimulateWithdrawResponse {
+            unbond_amount: Uint128::new(540),
+            instant_amount: Uint128::new(533),
+            instant_withdraw_fee: Uint128::new(5),
+        }
+    );
+
+    let res = instant_withdraw(&mut deps, 600).unwrap();
+    assert_eq!(
+        res.messages.last().unwrap().msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: WITHDRAWER.to_string(),
+            amount: coins(simulation.instant_amount.u128(), "uluna"),
+        })
+    );
+}
+
+#[test]
+fn unsuccessful_instant_withdraw_unauthorized() {
+    let mut deps = mock_vault();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: WITHDRAWER.to_string(),
+        amount: Uint128::new(600),
+        msg: to_binary(&Cw20HookMsg::InstantWithdraw {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info("bluna", &[]), msg);
+    match res {
+        Err(LunaVaultError::Unauthorized {}) => (),
+        _ => panic!("Must return LunaVaultError::Unauthorized"),
+    }
+}
@@ -210,6 +210,7 @@ fn successful_update_fee() {
             share: Decimal::percent(2),
         }),
         commission_fee: None,
+        instant_withdraw_fee: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -238,6 +239,7 @@ fn unsuccessful_update_fee_unauthorized() {
             share: Decimal::percent(2),
         }),
         commission_fee: None,
+        instant_withdraw_fee: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg);
@@ -262,6 +264,7 @@ fn successful_update_fee_unchanged() {
         flash_loan_fee: None,
         treasury_fee: None,
         commission_fee: None,
+        instant_withdraw_fee: None,
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
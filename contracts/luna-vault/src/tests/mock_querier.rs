@@ -11,9 +11,10 @@ use cosmwasm_std::{
 use crate::tests::anchor_mock::mock_epoch_state;
 use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::factory::PairType;
-use astroport::pair::PoolResponse;
+use astroport::pair::{PoolResponse, QueryMsg as PairQueryMsg, ReverseSimulationResponse};
 use cosmwasm_storage::to_length_prefixed;
-use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
+use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use serde::Serialize;
 use std::collections::HashMap;
 use terra_cosmwasm::{
     SwapResponse, TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper, TerraRoute,
@@ -23,7 +24,17 @@ use thiserror::private::DisplayAsDisplay;
 use white_whale::astroport_helper::SimulationResponse;
 
 use crate::pool_info::PoolInfo as VaultPoolInfo;
-use white_whale::query::anchor::{EpochStateResponse, UnbondRequestsResponse};
+use white_whale::luna_vault::luna_unbond_handler::msg::UnbondRequestsResponse as HandlerUnbondRequestsResponse;
+use white_whale::query::anchor::{
+    EpochStateResponse, HubParametersResponse, UnbondRequestsResponse, WithdrawableUnbondedResponse,
+};
+
+/// Mocked state of the Anchor bLuna hub and Prism cLuna hub, which share the mocked memory address
+#[derive(Serialize)]
+struct MockHubStateResponse {
+    bluna_exchange_rate: Decimal,
+    exchange_rate: Decimal,
+}
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -46,6 +57,8 @@ pub struct WasmMockQuerier {
     token_querier: TokenQuerier,
     tax_querier: TaxQuerier,
     astroport_factory_querier: AstroportFactoryQuerier,
+    hub_exchange_rates: (Decimal, Decimal),
+    bluna_swap_rate: Option<(Uint128, Uint128)>,
 }
 
 #[derive(Clone, Default)]
@@ -239,9 +252,31 @@ impl WasmMockQuerier {
                         )));
                     }
 
+                    let balance = match from_binary(msg) {
+                        Ok(Cw20QueryMsg::Balance { address }) => {
+                            self.token_balance(contract_addr, &address)
+                        }
+                        _ => Uint128::zero(),
+                    };
                     return SystemResult::Ok(ContractResult::from(to_binary(
-                        &Cw20BalanceResponse {
-                            balance: Uint128::zero(),
+                        &Cw20BalanceResponse { balance },
+                    )));
+                }
+
+                // Handle the unbond handlers, any contract starting with "unbond_handler"
+                if contract_addr.starts_with("unbond_handler") {
+                    if msg == &Binary::from(r#"{"withdrawable_unbonded":{}}"#.as_bytes()) {
+                        return SystemResult::Ok(ContractResult::from(to_binary(
+                            &WithdrawableUnbondedResponse {
+                                withdrawable: Uint128::new(50u128),
+                            },
+                        )));
+                    }
+                    return SystemResult::Ok(ContractResult::from(to_binary(
+                        &HandlerUnbondRequestsResponse {
+                            address: contract_addr.clone(),
+                            anchor_requests: vec![(1u64, Uint128::new(100u128), Uint128::zero())],
+                            prism_requests: vec![(1u64, Uint128::new(20u128))],
                         },
                     )));
                 }
@@ -251,6 +286,26 @@ impl WasmMockQuerier {
                 // This is a crude v1 impl for mocking, eventually this should be changed to have multiple
                 // Makes it so that ANY functionality within a memory queryied contract that needs to be covered can and should be covered here
                 if contract_addr == &String::from("contract_from_memory") {
+                    // Handle Anchor bLuna hub and Prism cLuna hub state queries for the exchange rates
+                    if msg == &Binary::from(r#"{"state":{}}"#.as_bytes()) {
+                        return SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&MockHubStateResponse {
+                                bluna_exchange_rate: self.hub_exchange_rates.0,
+                                exchange_rate: self.hub_exchange_rates.1,
+                            })
+                            .unwrap(),
+                        ));
+                    }
+                    // Handle Anchor bLuna hub parameters queries for the unbonding periods
+                    if msg == &Binary::from(r#"{"parameters":{}}"#.as_bytes()) {
+                        return SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&HubParametersResponse {
+                                epoch_period: 21600,
+                                unbonding_period: 1814400,
+                            })
+                            .unwrap(),
+                        ));
+                    }
                     // if msg == &Binary::from(r#"{"unbond_requests":{}}"#.as_bytes()) {
                     return SystemResult::Ok(ContractResult::Ok(
                         to_binary(&UnbondRequestsResponse {
@@ -374,6 +429,44 @@ impl WasmMockQuerier {
                             return SystemResult::Ok(ContractResult::from(to_binary(&myvac)));
                         }
 
+                        // Swap simulations at the configured bLuna swap rate
+                        if let Some((luna, bluna)) = self.bluna_swap_rate {
+                            let bluna_info = AssetInfo::Token {
+                                contract_addr: Addr::unchecked("bluna"),
+                            };
+                            match from_binary(msg) {
+                                Ok(PairQueryMsg::Simulation { offer_asset }) => {
+                                    let return_amount = if offer_asset.info == bluna_info {
+                                        offer_asset.amount.multiply_ratio(luna, bluna)
+                                    } else {
+                                        offer_asset.amount.multiply_ratio(bluna, luna)
+                                    };
+                                    return SystemResult::Ok(ContractResult::from(to_binary(
+                                        &SimulationResponse {
+                                            return_amount,
+                                            spread_amount: Uint128::zero(),
+                                            commission_amount: Uint128::zero(),
+                                        },
+                                    )));
+                                }
+                                Ok(PairQueryMsg::ReverseSimulation { ask_asset }) => {
+                                    let offer_amount = if ask_asset.info == bluna_info {
+                                        ask_asset.amount.multiply_ratio(luna, bluna)
+                                    } else {
+                                        ask_asset.amount.multiply_ratio(bluna, luna)
+                                    };
+                                    return SystemResult::Ok(ContractResult::from(to_binary(
+                                        &ReverseSimulationResponse {
+                                            offer_amount,
+                                            spread_amount: Uint128::zero(),
+                                            commission_amount: Uint128::zero(),
+                                        },
+                                    )));
+                                }
+                                _ => {}
+                            }
+                        }
+
                         // Only Simulation or a query I have not encountered/mocked in this contract left
                         let msg: SimulationResponse = SimulationResponse {
                             return_amount: Default::default(),
@@ -393,7 +486,7 @@ impl WasmMockQuerier {
                             {
                                 return SystemResult::Ok(ContractResult::Ok(
                                     to_binary(&Cw20BalanceResponse {
-                                        balance: Uint128::zero(),
+                                        balance: self.token_balance(contract_addr, &address),
                                     })
                                     .unwrap(),
                                 ));
@@ -429,6 +522,24 @@ impl WasmMockQuerier {
                                 to_binary(&Cw20BalanceResponse { balance }).unwrap(),
                             ))
                         }
+                        // the supply of a token is the sum of its configured balances
+                        Cw20QueryMsg::TokenInfo {} => {
+                            let total_supply = self
+                                .token_querier
+                                .balances
+                                .get(contract_addr)
+                                .map(|balances| balances.values().copied().sum())
+                                .unwrap_or_default();
+                            SystemResult::Ok(ContractResult::Ok(
+                                to_binary(&TokenInfoResponse {
+                                    name: contract_addr.clone(),
+                                    symbol: "TOKEN".to_string(),
+                                    decimals: 6,
+                                    total_supply,
+                                })
+                                .unwrap(),
+                            ))
+                        }
                         _ => panic!("DO NOT ENTER HERE"),
                     }
                 }
@@ -490,9 +601,36 @@ impl WasmMockQuerier {
             token_querier: TokenQuerier::default(),
             tax_querier: TaxQuerier::default(),
             astroport_factory_querier: AstroportFactoryQuerier::default(),
+            hub_exchange_rates: (Decimal::one(), Decimal::one()),
+            bluna_swap_rate: None,
         }
     }
 
+    // configure the cw20 token balances
+    pub fn with_token_balances(&mut self, balances: &[(&String, &[(&String, &Uint128)])]) {
+        self.token_querier = TokenQuerier::new(balances);
+    }
+
+    // configure the bLuna and cLuna exchange rates of the Anchor and Prism hubs
+    pub fn with_hub_exchange_rates(&mut self, bluna: Decimal, cluna: Decimal) {
+        self.hub_exchange_rates = (bluna, cluna);
+    }
+
+    // configure the luna the astro pair swaps the given bLuna for, both ways. Swap simulations
+    // return nothing if not set
+    pub fn with_bluna_swap_rate(&mut self, luna: Uint128, bluna: Uint128) {
+        self.bluna_swap_rate = Some((luna, bluna));
+    }
+
+    fn token_balance(&self, contract_addr: &str, address: &str) -> Uint128 {
+        self.token_querier
+            .balances
+            .get(contract_addr)
+            .and_then(|balances| balances.get(address))
+            .copied()
+            .unwrap_or_default()
+    }
+
     // configure the terraswap pair
     pub fn with_terraswap_pairs(&mut self, pairs: &[(&String, &PairInfo)]) {
         self.terraswap_pair_querier = TerraswapPairQuerier::new(pairs);
@@ -10,8 +10,17 @@ mod mock_querier;
 
 mod anchor_mock;
 mod deposit;
+mod harvest;
 mod helpers;
+mod instant_withdraw;
+mod pause;
 mod query;
+mod snapshots;
 mod state;
+mod strategies;
 mod tswap_mock;
+mod unbond;
+mod unbond_handlers;
+mod unbond_reservations;
+mod unbond_tickets;
 mod whitelist;
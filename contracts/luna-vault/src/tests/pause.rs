@@ -0,0 +1,183 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{coins, from_binary, to_binary, Addr, OwnedDeps, Response, Uint128};
+use cw20::Cw20ReceiveMsg;
+use terraswap::asset::{Asset, AssetInfo};
+
+use white_whale::denom::LUNA_DENOM;
+use white_whale::luna_vault::msg::{
+    Cw20HookMsg, ExecuteMsg, FlashLoanPayload, VaultQueryMsg as QueryMsg,
+};
+use white_whale::vault::pause::{PausableOperation, PauseInfoResponse, PauseState};
+
+use crate::contract::{execute, query, VaultResult};
+use crate::error::LunaVaultError;
+use crate::state::POOL_INFO;
+use crate::tests::common::TEST_CREATOR;
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+const GUARDIAN: &str = "guardian";
+
+fn luna(amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::NativeToken {
+            denom: LUNA_DENOM.to_string(),
+        },
+        amount: Uint128::new(amount),
+    }
+}
+
+/// Vault with vLuna as liquidity token and the guardian set
+fn mock_vault() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    POOL_INFO
+        .update(deps.as_mut().storage, |mut info| -> VaultResult<_> {
+            info.liquidity_token = Addr::unchecked("vluna");
+            Ok(info)
+        })
+        .unwrap();
+
+    let msg = ExecuteMsg::SetGuardian {
+        guardian: Some(GUARDIAN.to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+    deps
+}
+
+fn pause(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    operation: PausableOperation,
+) {
+    let msg = ExecuteMsg::Pause {
+        operation,
+        reason: "exploit".to_string(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(GUARDIAN, &[]), msg).unwrap();
+}
+
+fn assert_paused(res: VaultResult<Response>, operation: PausableOperation) {
+    assert_eq!(
+        res,
+        Err(LunaVaultError::Paused {
+            operation,
+            reason: "exploit".to_string(),
+        })
+    );
+}
+
+#[test]
+fn unsuccessful_pause_unauthorized() {
+    let mut deps = mock_vault();
+
+    let msg = ExecuteMsg::Pause {
+        operation: PausableOperation::Deposits,
+        reason: "exploit".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg,
+    );
+    match res {
+        Err(LunaVaultError::Unauthorized {}) => (),
+        _ => panic!("Must return LunaVaultError::Unauthorized"),
+    }
+}
+
+#[test]
+fn unsuccessful_deposit_paused() {
+    let mut deps = mock_vault();
+    pause(&mut deps, PausableOperation::Deposits);
+
+    let msg = ExecuteMsg::ProvideLiquidity { asset: luna(1000) };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &coins(1000, LUNA_DENOM)),
+        msg,
+    );
+    assert_paused(res, PausableOperation::Deposits);
+}
+
+#[test]
+fn unsuccessful_withdrawals_paused() {
+    let mut deps = mock_vault();
+    pause(&mut deps, PausableOperation::Withdrawals);
+
+    for hook_msg in [Cw20HookMsg::Unbond {}, Cw20HookMsg::InstantWithdraw {}] {
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: TEST_CREATOR.to_string(),
+            amount: Uint128::new(100),
+            msg: to_binary(&hook_msg).unwrap(),
+        });
+        let res = execute(deps.as_mut(), mock_env(), mock_info("vluna", &[]), msg);
+        assert_paused(res, PausableOperation::Withdrawals);
+    }
+
+    // the luna already unbonded can't be claimed either
+    for msg in [
+        ExecuteMsg::WithdrawUnbonded {},
+        ExecuteMsg::ClaimUnbondTicket { ticket_id: 1 },
+        ExecuteMsg::ClaimReservedUnbonds {},
+        ExecuteMsg::LiquidateExpiredUnbondHandler {
+            liquidate_unbond_handler_addr: "unbond_handler".to_string(),
+        },
+    ] {
+        let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+        assert_paused(res, PausableOperation::Withdrawals);
+    }
+}
+
+#[test]
+fn unsuccessful_flashloan_paused() {
+    let mut deps = mock_vault();
+    pause(&mut deps, PausableOperation::FlashLoans);
+
+    let msg = ExecuteMsg::FlashLoan {
+        payload: FlashLoanPayload {
+            requested_asset: luna(1000),
+            callback: Default::default(),
+        },
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    assert_paused(res, PausableOperation::FlashLoans);
+}
+
+#[test]
+fn successful_unpause() {
+    let mut deps = mock_vault();
+    pause(&mut deps, PausableOperation::Deposits);
+    pause(&mut deps, PausableOperation::Withdrawals);
+
+    // Only the admin can unpause
+    let msg = ExecuteMsg::Unpause {
+        operation: PausableOperation::Withdrawals,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(GUARDIAN, &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(LunaVaultError::Admin(_)) => (),
+        _ => panic!("Must return LunaVaultError::Admin"),
+    }
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    let res: PauseInfoResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PauseInfo {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        PauseInfoResponse {
+            guardian: Some(GUARDIAN.to_string()),
+            paused: PauseState {
+                deposits: Some("exploit".to_string()),
+                withdrawals: None,
+                flash_loans: None,
+            },
+        }
+    );
+}
@@ -0,0 +1,201 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{from_binary, Decimal, Env, Timestamp, Uint128};
+use signed_integer::SignedInt;
+
+use white_whale::luna_vault::msg::VaultQueryMsg as QueryMsg;
+use white_whale::snapshot::{AprResponse, SharePriceResponse, SnapshotsResponse, VaultSnapshot};
+use white_whale::vault::flashloan::PnlResponse;
+
+use crate::contract::query;
+use crate::error::LunaVaultError;
+use crate::helpers::record_snapshot;
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::mock_dependencies;
+
+const DAY: u64 = 86_400u64;
+
+fn env_at(timestamp: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(timestamp);
+    env
+}
+
+fn snapshot(id: u64, timestamp: u64, total_value: u128, total_share: u128) -> VaultSnapshot {
+    VaultSnapshot {
+        id,
+        timestamp,
+        total_value: Uint128::new(total_value),
+        total_share: Uint128::new(total_share),
+        profit: Uint128::zero(),
+        pnl: SignedInt::zero(),
+    }
+}
+
+#[test]
+fn unsuccessful_query_share_price_no_snapshots() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SharePrice { timestamp: None },
+    );
+    match res {
+        Err(LunaVaultError::NoSnapshots {}) => (),
+        _ => panic!("Must return LunaVaultError::NoSnapshots"),
+    }
+}
+
+#[test]
+fn test_query_share_price_and_apr() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // share price goes from 1 to 1.01 over 73 days, i.e. 5% APR, with deposits of 1000
+    for (timestamp, total_value, total_share) in [
+        (DAY, 1000u128, 1000u128),
+        (10 * DAY, 2000u128, 2000u128),
+        (74 * DAY, 3030u128, 3000u128),
+    ] {
+        record_snapshot(
+            &mut deps.storage,
+            &env_at(timestamp),
+            Uint128::new(total_value - 1000),
+            Uint128::new(total_value),
+            Uint128::new(total_share),
+            Uint128::zero(),
+        )
+        .unwrap();
+    }
+
+    // latest snapshot at or before the timestamp
+    let res = query(
+        deps.as_ref(),
+        env_at(80 * DAY),
+        QueryMsg::SharePrice {
+            timestamp: Some(20 * DAY),
+        },
+    )
+    .unwrap();
+    let response: SharePriceResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        SharePriceResponse {
+            share_price: Decimal::one(),
+            snapshot: snapshot(2, 10 * DAY, 2000u128, 2000u128),
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env_at(80 * DAY),
+        QueryMsg::SharePrice { timestamp: None },
+    )
+    .unwrap();
+    let response: SharePriceResponse = from_binary(&res).unwrap();
+    assert_eq!(response.share_price, Decimal::percent(101));
+
+    // the window starts before the first snapshot, so the earliest one is used
+    let res = query(
+        deps.as_ref(),
+        env_at(74 * DAY),
+        QueryMsg::Apr { window: 365 * DAY },
+    )
+    .unwrap();
+    let response: AprResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        AprResponse {
+            apr: Decimal::percent(5),
+            start: snapshot(1, DAY, 1000u128, 1000u128),
+            end: VaultSnapshot {
+                pnl: SignedInt::from(Uint128::new(30)),
+                ..snapshot(3, 74 * DAY, 3030u128, 3000u128)
+            },
+        }
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Pnl {}).unwrap();
+    let response: PnlResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        PnlResponse {
+            last_pnl: SignedInt::from(Uint128::new(30)),
+            cumulative_pnl: SignedInt::from(Uint128::new(30)),
+            last_value: Uint128::new(3030),
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Snapshots {
+            start_after: Some(1u64),
+            limit: Some(1u32),
+        },
+    )
+    .unwrap();
+    let response: SnapshotsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.snapshots,
+        vec![snapshot(2, 10 * DAY, 2000u128, 2000u128)]
+    );
+}
+
+#[test]
+fn test_snapshots_of_the_same_block_are_kept() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // a deposit and a flashloan in the same block
+    record_snapshot(
+        &mut deps.storage,
+        &env_at(DAY),
+        Uint128::zero(),
+        Uint128::new(1000),
+        Uint128::new(1000),
+        Uint128::zero(),
+    )
+    .unwrap();
+    record_snapshot(
+        &mut deps.storage,
+        &env_at(DAY),
+        Uint128::new(1000),
+        Uint128::new(1010),
+        Uint128::new(1000),
+        Uint128::new(10),
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Snapshots {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let response: SnapshotsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.snapshots,
+        vec![
+            snapshot(1, DAY, 1000u128, 1000u128),
+            VaultSnapshot {
+                profit: Uint128::new(10),
+                ..snapshot(2, DAY, 1010u128, 1000u128)
+            },
+        ]
+    );
+
+    // the share price at the block is the one of its last snapshot
+    let res = query(
+        deps.as_ref(),
+        env_at(DAY),
+        QueryMsg::SharePrice { timestamp: None },
+    )
+    .unwrap();
+    let response: SharePriceResponse = from_binary(&res).unwrap();
+    assert_eq!(response.share_price, Decimal::permille(1010));
+}
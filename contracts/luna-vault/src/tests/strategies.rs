@@ -0,0 +1,352 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coins, from_binary, to_binary, CosmosMsg, Decimal, OwnedDeps, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use white_whale::luna_vault::msg::{
+    ExecuteMsg, PassiveStrategyKind, PoolResponse, StrategyInfo, StrategyResponse, VaultQueryMsg,
+};
+
+use crate::contract::{execute, query};
+use crate::error::LunaVaultError;
+use crate::helpers::query_hub_exchange_rates;
+use crate::state::STRATEGIES;
+use crate::strategies::{
+    deposit_into_strategies, load_strategies, withdraw_from_strategies, withdraw_max_spread,
+};
+use crate::tests::common::{ARB_CONTRACT, TEST_CREATOR};
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+fn strategies() -> Vec<StrategyInfo> {
+    vec![
+        StrategyInfo {
+            strategy: PassiveStrategyKind::AstroportBlunaLp,
+            target_weight: Decimal::percent(60),
+        },
+        StrategyInfo {
+            strategy: PassiveStrategyKind::HeldBluna,
+            target_weight: Decimal::percent(20),
+        },
+        StrategyInfo {
+            strategy: PassiveStrategyKind::ClunaLp {
+                pair_address: "cluna_pair".to_string(),
+            },
+            target_weight: Decimal::percent(20),
+        },
+    ]
+}
+
+#[test]
+fn defaults_to_astroport_bluna_lp() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    assert_eq!(
+        load_strategies(deps.as_ref().storage).unwrap(),
+        vec![StrategyInfo {
+            strategy: PassiveStrategyKind::AstroportBlunaLp,
+            target_weight: Decimal::one(),
+        }]
+    );
+}
+
+#[test]
+fn successful_set_strategies() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetStrategies {
+        strategies: strategies(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    assert_eq!(
+        STRATEGIES.load(deps.as_ref().storage).unwrap(),
+        strategies()
+    );
+}
+
+#[test]
+fn unsuccessful_set_strategies_unauthorized() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetStrategies {
+        strategies: strategies(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg,
+    );
+    match res {
+        Err(LunaVaultError::Admin(_)) => (),
+        _ => panic!("Must return LunaVaultError::Admin"),
+    }
+}
+
+#[test]
+fn unsuccessful_set_strategies_invalid_weights() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let mut strategies = strategies();
+    strategies[0].target_weight = Decimal::percent(50);
+
+    let msg = ExecuteMsg::SetStrategies { strategies };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(LunaVaultError::InvalidStrategyWeights {}) => (),
+        _ => panic!("Must return LunaVaultError::InvalidStrategyWeights"),
+    }
+}
+
+#[test]
+fn unsuccessful_set_strategies_duplicated() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetStrategies {
+        strategies: vec![
+            StrategyInfo {
+                strategy: PassiveStrategyKind::HeldBluna,
+                target_weight: Decimal::percent(50),
+            },
+            StrategyInfo {
+                strategy: PassiveStrategyKind::HeldBluna,
+                target_weight: Decimal::percent(50),
+            },
+        ],
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(LunaVaultError::DuplicatedStrategy {}) => (),
+        _ => panic!("Must return LunaVaultError::DuplicatedStrategy"),
+    }
+}
+
+/// Vault split between the bLuna-Luna LP and held bLuna, holding 1000 bLuna and no LP tokens
+fn mock_held_bluna_vault() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    STRATEGIES
+        .save(
+            deps.as_mut().storage,
+            &vec![
+                StrategyInfo {
+                    strategy: PassiveStrategyKind::AstroportBlunaLp,
+                    target_weight: Decimal::percent(50),
+                },
+                StrategyInfo {
+                    strategy: PassiveStrategyKind::HeldBluna,
+                    target_weight: Decimal::percent(50),
+                },
+            ],
+        )
+        .unwrap();
+    deps.querier.with_token_balances(&[(
+        &"bluna".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::new(1000))],
+    )]);
+    deps
+}
+
+/// Message selling the given amount of bLuna on the astro pair at a 1:1 belief price
+fn sell_bluna_msg(amount: u128) -> CosmosMsg {
+    CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "bluna".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: "astro".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&astroport::pair::Cw20HookMsg::Swap {
+                belief_price: Some(Decimal::one()),
+                max_spread: Some(withdraw_max_spread()),
+                to: None,
+            })
+            .unwrap(),
+        })
+        .unwrap(),
+        funds: vec![],
+    })
+}
+
+#[test]
+fn test_deposit_into_strategies_by_target_weight() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    STRATEGIES
+        .save(
+            deps.as_mut().storage,
+            &vec![
+                StrategyInfo {
+                    strategy: PassiveStrategyKind::HeldBluna,
+                    target_weight: Decimal::percent(30),
+                },
+                StrategyInfo {
+                    strategy: PassiveStrategyKind::AstroportBlunaLp,
+                    target_weight: Decimal::percent(70),
+                },
+            ],
+        )
+        .unwrap();
+
+    let msgs = deposit_into_strategies(deps.as_ref(), &mock_env(), Uint128::new(1001)).unwrap();
+    // held bLuna buys bLuna, the LP buys bLuna, allows the pair to take it and provides liquidity
+    assert_eq!(msgs.len(), 4);
+    assert_eq!(
+        msgs[0],
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "astro".to_string(),
+            msg: to_binary(&astroport::pair::ExecuteMsg::Swap {
+                offer_asset: astroport::asset::Asset {
+                    amount: Uint128::new(300),
+                    info: astroport::asset::AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                },
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            })
+            .unwrap(),
+            funds: coins(300, "uluna"),
+        })
+    );
+    // the last strategy gets the remaining 701 luna, half of it buying bLuna
+    match &msgs[1] {
+        CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) => {
+            assert_eq!(funds, &coins(350, "uluna"))
+        }
+        _ => panic!("Must purchase bLuna for the LP"),
+    }
+}
+
+#[test]
+fn test_withdraw_from_strategies_in_order() {
+    let mut deps = mock_held_bluna_vault();
+    deps.querier
+        .with_bluna_swap_rate(Uint128::new(1), Uint128::new(1));
+    let rates = query_hub_exchange_rates(deps.as_ref()).unwrap();
+
+    // the LP holds nothing, so all of it comes from the held bLuna
+    let withdrawal =
+        withdraw_from_strategies(deps.as_ref(), &mock_env(), &rates, Uint128::new(600)).unwrap();
+    assert_eq!(withdrawal.messages, vec![sell_bluna_msg(600)]);
+    // the luna returned is guaranteed at the max spread
+    assert_eq!(withdrawal.luna_return, Uint128::new(597));
+
+    let res = withdraw_from_strategies(deps.as_ref(), &mock_env(), &rates, Uint128::new(1001));
+    match res {
+        Err(LunaVaultError::Broke {}) => (),
+        _ => panic!("Must return LunaVaultError::Broke"),
+    }
+}
+
+#[test]
+fn test_withdraw_from_strategies_below_hub_exchange_rate() {
+    let mut deps = mock_held_bluna_vault();
+    // bLuna trades at 0.95 luna while the hub values it at 1 luna
+    deps.querier
+        .with_bluna_swap_rate(Uint128::new(95), Uint128::new(100));
+    let rates = query_hub_exchange_rates(deps.as_ref()).unwrap();
+
+    let withdrawal =
+        withdraw_from_strategies(deps.as_ref(), &mock_env(), &rates, Uint128::new(500)).unwrap();
+    // 526 bLuna sell for 499 luna, 496 of which are guaranteed at the max spread
+    assert_eq!(withdrawal.luna_return, Uint128::new(496));
+    match &withdrawal.messages[..] {
+        [CosmosMsg::Wasm(WasmMsg::Execute { msg, .. })] => match from_binary(msg).unwrap() {
+            Cw20ExecuteMsg::Send { amount, .. } => assert_eq!(amount, Uint128::new(526)),
+            _ => panic!("Must send the bLuna to the pair"),
+        },
+        _ => panic!("Must sell the held bLuna"),
+    }
+}
+
+#[test]
+fn successful_rebalance() {
+    let mut deps = mock_held_bluna_vault();
+    deps.querier
+        .with_bluna_swap_rate(Uint128::new(1), Uint128::new(1));
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::AddToWhitelist {
+            contract_addr: ARB_CONTRACT.to_string(),
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ARB_CONTRACT, &[]),
+        ExecuteMsg::Rebalance {},
+    )
+    .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "rebalance"),
+            attr("strategies_value_in_luna", "1000"),
+        ]
+    );
+
+    // the held bLuna above its target is sold, and the 497 luna returned go into the LP
+    assert_eq!(res.messages.len(), 4);
+    assert_eq!(res.messages[0].msg, sell_bluna_msg(500));
+    match &res.messages[1].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) => {
+            assert_eq!(funds, &coins(248, "uluna"))
+        }
+        _ => panic!("Must purchase bLuna for the LP"),
+    }
+}
+
+#[test]
+fn unsuccessful_rebalance_not_whitelisted() {
+    let mut deps = mock_held_bluna_vault();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        ExecuteMsg::Rebalance {},
+    );
+    match res {
+        Err(LunaVaultError::NotWhitelisted {}) => (),
+        _ => panic!("Must return LunaVaultError::NotWhitelisted"),
+    }
+}
+
+#[test]
+fn test_query_pool_state_strategies_breakdown() {
+    let mut deps = mock_held_bluna_vault();
+    deps.querier
+        .with_hub_exchange_rates(Decimal::percent(90), Decimal::one());
+
+    let res: PoolResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), VaultQueryMsg::PoolState {}).unwrap())
+            .unwrap();
+    assert_eq!(res.total_value_in_luna, Uint128::new(900));
+    assert_eq!(
+        res.strategies,
+        vec![
+            StrategyResponse {
+                strategy: PassiveStrategyKind::AstroportBlunaLp,
+                target_weight: Decimal::percent(50),
+                current_weight: Decimal::zero(),
+                value_in_luna: Uint128::zero(),
+            },
+            StrategyResponse {
+                strategy: PassiveStrategyKind::HeldBluna,
+                target_weight: Decimal::percent(50),
+                current_weight: Decimal::one(),
+                value_in_luna: Uint128::new(900),
+            },
+        ]
+    );
+}
@@ -0,0 +1,128 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coins, to_binary, Addr, BankMsg, CosmosMsg, Decimal, OwnedDeps, Response, Uint128,
+    WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use white_whale::luna_vault::msg::{Cw20HookMsg, ExecuteMsg, PassiveStrategyKind, StrategyInfo};
+
+use crate::contract::{execute, VaultResult};
+use crate::state::{POOL_INFO, STRATEGIES, UNBOND_HANDLERS_AVAILABLE};
+use crate::strategies::withdraw_max_spread;
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::{mock_dependencies, WasmMockQuerier};
+
+const UNBONDER: &str = "unbonder";
+const UNBOND_HANDLER: &str = "unbond_handler_a";
+
+/// Vault holding 200 liquid luna and 1000 bLuna in the held bLuna strategy, with an unbond handler
+/// available. The unbonder holds half of the 1200 vLuna
+fn mock_vault() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&coins(200, "uluna"));
+    mock_instantiate(deps.as_mut());
+    POOL_INFO
+        .update(deps.as_mut().storage, |mut info| -> VaultResult<_> {
+            info.liquidity_token = Addr::unchecked("vluna");
+            Ok(info)
+        })
+        .unwrap();
+    STRATEGIES
+        .save(
+            deps.as_mut().storage,
+            &vec![StrategyInfo {
+                strategy: PassiveStrategyKind::HeldBluna,
+                target_weight: Decimal::one(),
+            }],
+        )
+        .unwrap();
+    UNBOND_HANDLERS_AVAILABLE
+        .save(
+            deps.as_mut().storage,
+            &vec![Addr::unchecked(UNBOND_HANDLER)],
+        )
+        .unwrap();
+    deps.querier.with_token_balances(&[
+        (
+            &"vluna".to_string(),
+            &[
+                (&UNBONDER.to_string(), &Uint128::new(600)),
+                (&"other_depositor".to_string(), &Uint128::new(600)),
+            ],
+        ),
+        (
+            &"bluna".to_string(),
+            &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::new(1000))],
+        ),
+    ]);
+    deps.querier
+        .with_bluna_swap_rate(Uint128::new(1), Uint128::new(1));
+    deps
+}
+
+fn unbond(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    amount: u128,
+) -> VaultResult<Response> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: UNBONDER.to_string(),
+        amount: Uint128::new(amount),
+        msg: to_binary(&Cw20HookMsg::Unbond {}).unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("vluna", &[]), msg)
+}
+
+#[test]
+fn successful_unbond_withdraws_the_strategies_and_the_liquid_luna() {
+    let mut deps = mock_vault();
+
+    // 60 vLuna go to the treasury, the remaining 540 are a 45% share of the vault. The unbonder
+    // gets 45% of the 200 liquid luna, and 45% of the 1000 bLuna sold for the 447 luna guaranteed
+    // by the sale
+    let res = unbond(&mut deps, 600).unwrap();
+    let messages: Vec<CosmosMsg> = res.messages.into_iter().map(|msg| msg.msg).collect();
+    assert_eq!(messages.len(), 5);
+    assert_eq!(
+        messages[0],
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "bluna".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "astro".to_string(),
+                amount: Uint128::new(450),
+                msg: to_binary(&astroport::pair::Cw20HookMsg::Swap {
+                    belief_price: Some(Decimal::one()),
+                    max_spread: Some(withdraw_max_spread()),
+                    to: None,
+                })
+                .unwrap(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert_eq!(
+        messages[2],
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: UNBOND_HANDLER.to_string(),
+            amount: coins(537, "uluna"),
+        })
+    );
+    assert_eq!(
+        messages[3],
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "vluna".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn {
+                amount: Uint128::new(540),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert!(res.attributes.contains(&attr("bluna_amount", "0")));
+    assert!(res.attributes.contains(&attr("luna_amount", "537")));
+
+    // the handler is assigned to the unbonder
+    assert_eq!(
+        UNBOND_HANDLERS_AVAILABLE.load(&deps.storage).unwrap(),
+        Vec::<Addr>::new()
+    );
+}
@@ -0,0 +1,289 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{from_binary, Addr, Uint128};
+
+use white_whale::luna_vault::msg::VaultQueryMsg as QueryMsg;
+use white_whale::luna_vault::msg::*;
+
+use crate::contract::query;
+use crate::state::{
+    UNBOND_HANDLERS_ASSIGNED, UNBOND_HANDLERS_AVAILABLE, UNBOND_HANDLER_EXPIRATION_TIMES,
+};
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::mock_dependencies;
+
+fn unbond_handler_info(
+    address: &str,
+    ticket: Option<(&str, u64)>,
+    expiration_time: Option<u64>,
+) -> UnbondHandlerInfo {
+    UnbondHandlerInfo {
+        address: address.to_string(),
+        owner: ticket.map(|(owner, _)| owner.to_string()),
+        ticket_id: ticket.map(|(_, ticket_id)| ticket_id),
+        expiration_time,
+        pending_amount: Uint128::new(120u128),
+        withdrawable_amount: Uint128::new(50u128),
+    }
+}
+
+#[test]
+fn test_query_assigned_unbond_handlers() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    for (owner, ticket_id, unbond_handler, expiration_time) in [
+        ("user_a", 1u64, "unbond_handler_a", 100u64),
+        ("user_b", 2u64, "unbond_handler_b", 200u64),
+        ("user_a", 3u64, "unbond_handler_c", 300u64),
+    ] {
+        UNBOND_HANDLERS_ASSIGNED
+            .save(
+                &mut deps.storage,
+                Addr::unchecked(unbond_handler),
+                &(Addr::unchecked(owner), ticket_id),
+            )
+            .unwrap();
+        UNBOND_HANDLER_EXPIRATION_TIMES
+            .save(
+                &mut deps.storage,
+                Addr::unchecked(unbond_handler),
+                &expiration_time,
+            )
+            .unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssignedUnbondHandlers {
+            start_after: None,
+            limit: Some(2u32),
+        },
+    )
+    .unwrap();
+    let response: UnbondHandlersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.unbond_handlers,
+        vec![
+            unbond_handler_info("unbond_handler_a", Some(("user_a", 1u64)), Some(100u64)),
+            unbond_handler_info("unbond_handler_b", Some(("user_b", 2u64)), Some(200u64)),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AssignedUnbondHandlers {
+            start_after: Some("unbond_handler_b".to_string()),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let response: UnbondHandlersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.unbond_handlers,
+        vec![unbond_handler_info(
+            "unbond_handler_c",
+            Some(("user_a", 3u64)),
+            Some(300u64)
+        )]
+    );
+}
+
+#[test]
+fn test_query_available_unbond_handlers() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    UNBOND_HANDLERS_AVAILABLE
+        .save(
+            &mut deps.storage,
+            &vec![
+                Addr::unchecked("unbond_handler_a"),
+                Addr::unchecked("unbond_handler_b"),
+            ],
+        )
+        .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AvailableUnbondHandlers {
+            start_after: Some("unbond_handler_a".to_string()),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let response: UnbondHandlersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.unbond_handlers,
+        vec![unbond_handler_info("unbond_handler_b", None, None)]
+    );
+}
+
+#[test]
+fn test_query_expired_unbond_handlers() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    for (unbond_handler, expiration_time) in [
+        ("unbond_handler_a", 100u64),
+        ("unbond_handler_b", 300u64),
+        ("unbond_handler_c", 150u64),
+    ] {
+        UNBOND_HANDLER_EXPIRATION_TIMES
+            .save(
+                &mut deps.storage,
+                Addr::unchecked(unbond_handler),
+                &expiration_time,
+            )
+            .unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ExpiredUnbondHandlers {
+            expired_at: Some(200u64),
+            owner: None,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let response: ExpiredUnbondHandlersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        ExpiredUnbondHandlersResponse {
+            unbond_handlers: vec![
+                unbond_handler_info("unbond_handler_a", None, Some(100u64)),
+                unbond_handler_info("unbond_handler_c", None, Some(150u64)),
+            ],
+            last_unbond_handler: None,
+        }
+    );
+
+    // none of the handlers had expired yet at that time
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ExpiredUnbondHandlers {
+            expired_at: Some(50u64),
+            owner: None,
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let response: ExpiredUnbondHandlersResponse = from_binary(&res).unwrap();
+    assert!(response.unbond_handlers.is_empty());
+}
+
+#[test]
+fn test_query_expired_unbond_handlers_paginated() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    for (unbond_handler, expiration_time) in [
+        ("unbond_handler_a", 100u64),
+        ("unbond_handler_b", 300u64),
+        ("unbond_handler_c", 150u64),
+    ] {
+        UNBOND_HANDLER_EXPIRATION_TIMES
+            .save(
+                &mut deps.storage,
+                Addr::unchecked(unbond_handler),
+                &expiration_time,
+            )
+            .unwrap();
+    }
+
+    // the page reads handlers a and b, only a has expired
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ExpiredUnbondHandlers {
+            expired_at: Some(200u64),
+            owner: None,
+            start_after: None,
+            limit: Some(2u32),
+        },
+    )
+    .unwrap();
+    let response: ExpiredUnbondHandlersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        ExpiredUnbondHandlersResponse {
+            unbond_handlers: vec![unbond_handler_info("unbond_handler_a", None, Some(100u64))],
+            last_unbond_handler: Some("unbond_handler_b".to_string()),
+        }
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ExpiredUnbondHandlers {
+            expired_at: Some(200u64),
+            owner: None,
+            start_after: response.last_unbond_handler,
+            limit: Some(2u32),
+        },
+    )
+    .unwrap();
+    let response: ExpiredUnbondHandlersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response,
+        ExpiredUnbondHandlersResponse {
+            unbond_handlers: vec![unbond_handler_info("unbond_handler_c", None, Some(150u64))],
+            last_unbond_handler: None,
+        }
+    );
+}
+
+#[test]
+fn test_query_expired_unbond_handlers_by_owner() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    for (owner, ticket_id, unbond_handler, expiration_time) in [
+        ("user_a", 1u64, "unbond_handler_a", 100u64),
+        ("user_b", 2u64, "unbond_handler_b", 120u64),
+        ("user_a", 3u64, "unbond_handler_c", 300u64),
+    ] {
+        UNBOND_HANDLERS_ASSIGNED
+            .save(
+                &mut deps.storage,
+                Addr::unchecked(unbond_handler),
+                &(Addr::unchecked(owner), ticket_id),
+            )
+            .unwrap();
+        UNBOND_HANDLER_EXPIRATION_TIMES
+            .save(
+                &mut deps.storage,
+                Addr::unchecked(unbond_handler),
+                &expiration_time,
+            )
+            .unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ExpiredUnbondHandlers {
+            expired_at: Some(200u64),
+            owner: Some("user_a".to_string()),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap();
+    let response: ExpiredUnbondHandlersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.unbond_handlers,
+        vec![unbond_handler_info(
+            "unbond_handler_a",
+            Some(("user_a", 1u64)),
+            Some(100u64)
+        )]
+    );
+}
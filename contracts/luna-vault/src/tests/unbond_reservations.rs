@@ -0,0 +1,234 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{coins, from_binary, Addr, Decimal, Uint128};
+
+use white_whale::luna_vault::msg::VaultQueryMsg as QueryMsg;
+use white_whale::luna_vault::msg::*;
+
+use crate::contract::{execute, query};
+use crate::error::LunaVaultError;
+use crate::helpers::{
+    add_reservations, remove_reservations, reserved_unbonds_value_in_luna,
+    split_released_reservations, total_reserved_amount, total_reserved_luna_amount,
+    HubExchangeRates,
+};
+use crate::state::{RESERVED_UNBONDS, UNBOND_RESERVATIONS};
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::mock_dependencies;
+
+const TEST_USER: &str = "user";
+
+fn reservation(hub: UnbondHub, batch_id: u64, amount: u128) -> UnbondReservation {
+    reservation_at_rate(hub, batch_id, amount, Decimal::one())
+}
+
+fn reservation_at_rate(
+    hub: UnbondHub,
+    batch_id: u64,
+    amount: u128,
+    rate: Decimal,
+) -> UnbondReservation {
+    UnbondReservation {
+        hub,
+        batch_id,
+        amount: Uint128::new(amount),
+        luna_amount: Uint128::new(amount) * rate,
+    }
+}
+
+#[test]
+fn test_add_and_remove_reservations() {
+    let mut aggregate = vec![reservation(UnbondHub::Anchor, 1, 100)];
+
+    add_reservations(
+        &mut aggregate,
+        &[
+            reservation(UnbondHub::Anchor, 1, 50),
+            reservation(UnbondHub::Prism, 1, 20),
+        ],
+    );
+    assert_eq!(
+        aggregate,
+        vec![
+            reservation(UnbondHub::Anchor, 1, 150),
+            reservation(UnbondHub::Prism, 1, 20),
+        ]
+    );
+    assert_eq!(total_reserved_amount(&aggregate), Uint128::new(170));
+
+    remove_reservations(&mut aggregate, &[reservation(UnbondHub::Prism, 1, 20)]).unwrap();
+    assert_eq!(aggregate, vec![reservation(UnbondHub::Anchor, 1, 150)]);
+}
+
+#[test]
+fn test_add_and_remove_reservations_at_different_rates() {
+    let mut aggregate = vec![reservation_at_rate(
+        UnbondHub::Anchor,
+        1,
+        100,
+        Decimal::percent(110),
+    )];
+
+    // the same batch reserved later at another rate keeps the luna owed of each reservation
+    add_reservations(
+        &mut aggregate,
+        &[reservation_at_rate(
+            UnbondHub::Anchor,
+            1,
+            100,
+            Decimal::percent(90),
+        )],
+    );
+    assert_eq!(total_reserved_amount(&aggregate), Uint128::new(200));
+    assert_eq!(total_reserved_luna_amount(&aggregate), Uint128::new(200));
+
+    remove_reservations(
+        &mut aggregate,
+        &[reservation_at_rate(
+            UnbondHub::Anchor,
+            1,
+            100,
+            Decimal::percent(110),
+        )],
+    )
+    .unwrap();
+    assert_eq!(
+        aggregate,
+        vec![reservation_at_rate(
+            UnbondHub::Anchor,
+            1,
+            100,
+            Decimal::percent(90)
+        )]
+    );
+}
+
+#[test]
+fn test_split_released_reservations() {
+    let reservations = vec![
+        reservation(UnbondHub::Anchor, 1, 100),
+        reservation(UnbondHub::Anchor, 2, 100),
+        reservation(UnbondHub::Prism, 2, 100),
+    ];
+    // only the anchor batch 2 is still pending on the hub
+    let unbond_requests = vec![reservation(UnbondHub::Anchor, 2, 1000)];
+
+    let (released, pending) = split_released_reservations(reservations, &unbond_requests);
+    assert_eq!(
+        released,
+        vec![
+            reservation(UnbondHub::Anchor, 1, 100),
+            reservation(UnbondHub::Prism, 2, 100),
+        ]
+    );
+    assert_eq!(pending, vec![reservation(UnbondHub::Anchor, 2, 100)]);
+}
+
+#[test]
+fn test_reserved_unbonds_value_after_a_hub_rate_drop() {
+    // reserved at a hub rate of 1, the hub rates then dropped
+    let reservations = vec![
+        reservation(UnbondHub::Anchor, 1, 100),
+        reservation(UnbondHub::Anchor, 2, 100),
+        reservation(UnbondHub::Prism, 2, 100),
+    ];
+    let rates = HubExchangeRates {
+        bluna: Decimal::percent(90),
+        cluna: Decimal::percent(80),
+    };
+    // the anchor batch 1 was released, the others are still burning and valued at the
+    // current rates like the bLuna and cLuna backing them
+    let unbond_requests = vec![
+        reservation_at_rate(UnbondHub::Anchor, 2, 100, rates.bluna),
+        reservation_at_rate(UnbondHub::Prism, 2, 100, rates.cluna),
+    ];
+    assert_eq!(
+        reserved_unbonds_value_in_luna(&reservations, &unbond_requests, &rates),
+        Uint128::new(100 + 90 + 80)
+    );
+
+    // a rate above the reservation rate doesn't increase what is owed
+    let rates = HubExchangeRates {
+        bluna: Decimal::percent(110),
+        cluna: Decimal::percent(120),
+    };
+    assert_eq!(
+        reserved_unbonds_value_in_luna(&reservations, &unbond_requests, &rates),
+        Uint128::new(300)
+    );
+}
+
+#[test]
+fn unsuccessful_claim_reserved_unbonds_no_reservations() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_USER, &[]),
+        ExecuteMsg::ClaimReservedUnbonds {},
+    );
+    match res {
+        Err(LunaVaultError::NoUnbondReservations {}) => (),
+        _ => panic!("Must return LunaVaultError::NoUnbondReservations"),
+    }
+}
+
+#[test]
+fn successful_claim_reserved_unbonds() {
+    let mut deps = mock_dependencies(&coins(1000, "uluna"));
+    mock_instantiate(deps.as_mut());
+
+    // reserved at hub rates below 1, the luna paid out is what the bLuna and cLuna were worth
+    let reservations = vec![
+        reservation_at_rate(UnbondHub::Anchor, 1, 300, Decimal::percent(90)),
+        reservation_at_rate(UnbondHub::Prism, 1, 200, Decimal::percent(80)),
+    ];
+    UNBOND_RESERVATIONS
+        .save(
+            deps.as_mut().storage,
+            Addr::unchecked(TEST_USER),
+            &reservations,
+        )
+        .unwrap();
+    RESERVED_UNBONDS
+        .save(deps.as_mut().storage, &reservations)
+        .unwrap();
+
+    // the mocked hubs have no pending requests, so all reservations were released
+    let res: ReservedUnbondsResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ReservedUnbonds {
+                address: TEST_USER.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.claimable, Uint128::new(430));
+    assert!(res.pending.is_empty());
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_USER, &[]),
+        ExecuteMsg::ClaimReservedUnbonds {},
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "claimed_amount" && attr.value == "430"));
+
+    assert!(UNBOND_RESERVATIONS
+        .may_load(deps.as_ref().storage, Addr::unchecked(TEST_USER))
+        .unwrap()
+        .is_none());
+    assert!(RESERVED_UNBONDS
+        .load(deps.as_ref().storage)
+        .unwrap()
+        .is_empty());
+}
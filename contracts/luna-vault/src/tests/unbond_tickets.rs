@@ -0,0 +1,165 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, Addr, Uint128};
+use cw_storage_plus::U64Key;
+
+use white_whale::luna_vault::msg::VaultQueryMsg as QueryMsg;
+use white_whale::luna_vault::msg::*;
+use white_whale::query::anchor::WithdrawableUnbondedResponse;
+
+use crate::contract::{execute, query};
+use crate::error::LunaVaultError;
+use crate::helpers::save_unbond_ticket;
+use crate::state::{
+    UnbondTicket, UNBOND_HANDLERS_ASSIGNED, UNBOND_HANDLERS_AVAILABLE, UNBOND_TICKETS,
+};
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::mock_dependencies;
+
+fn ticket(id: u64, unbond_handler: &str) -> UnbondTicket {
+    UnbondTicket {
+        id,
+        unbond_handler: Addr::unchecked(unbond_handler),
+        created_at: 1000u64,
+        expected_release_time: 1000u64 + 21600u64 + 1814400u64,
+    }
+}
+
+#[test]
+fn test_query_unbond_tickets() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let owner = Addr::unchecked("user");
+    for (id, unbond_handler) in [
+        (1u64, "unbond_handler_a"),
+        (3u64, "unbond_handler_b"),
+        (4u64, "unbond_handler_c"),
+    ] {
+        save_unbond_ticket(&mut deps.storage, &owner, &ticket(id, unbond_handler)).unwrap();
+    }
+    save_unbond_ticket(
+        &mut deps.storage,
+        &Addr::unchecked("other_user"),
+        &ticket(2u64, "unbond_handler_d"),
+    )
+    .unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UnbondTickets {
+            address: "user".to_string(),
+            start_after: Some(1u64),
+            limit: Some(1u32),
+        },
+    )
+    .unwrap();
+    let response: UnbondTicketsResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.tickets,
+        vec![UnbondTicketResponse {
+            id: 3u64,
+            unbond_handler: "unbond_handler_b".to_string(),
+            created_at: 1000u64,
+            expected_release_time: 1000u64 + 21600u64 + 1814400u64,
+            status: UnbondTicketStatus::Claimable,
+            pending_amount: Uint128::new(120u128),
+            withdrawable_amount: Uint128::new(50u128),
+        }]
+    );
+
+    // the withdrawable amounts of all the tickets of the user are added up
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::WithdrawableUnbonded {
+            address: "user".to_string(),
+        },
+    )
+    .unwrap();
+    let response: WithdrawableUnbondedResponse = from_binary(&res).unwrap();
+    assert_eq!(response.withdrawable, Uint128::new(150u128));
+}
+
+#[test]
+fn unsuccessful_claim_unbond_ticket_not_found() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // the ticket belongs to another user
+    save_unbond_ticket(
+        &mut deps.storage,
+        &Addr::unchecked("other_user"),
+        &ticket(1u64, "unbond_handler_a"),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[]),
+        ExecuteMsg::ClaimUnbondTicket { ticket_id: 1u64 },
+    );
+    match res {
+        Err(LunaVaultError::UnbondTicketNotFound {}) => (),
+        _ => panic!("Must return LunaVaultError::UnbondTicketNotFound"),
+    }
+}
+
+#[test]
+fn successful_claim_unbond_ticket() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    save_unbond_ticket(
+        &mut deps.storage,
+        &Addr::unchecked("user"),
+        &ticket(1u64, "unbond_handler_a"),
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user", &[]),
+        ExecuteMsg::ClaimUnbondTicket { ticket_id: 1u64 },
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
+
+#[test]
+fn successful_release_unbond_handler_removes_ticket() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let owner = Addr::unchecked("user");
+    save_unbond_ticket(&mut deps.storage, &owner, &ticket(1u64, "unbond_handler_a")).unwrap();
+    UNBOND_HANDLERS_AVAILABLE
+        .save(&mut deps.storage, &vec![])
+        .unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unbond_handler_a", &[]),
+        ExecuteMsg::UnbondHandler(UnbondHandlerMsg::AfterUnbondHandlerReleased {
+            unbond_handler_addr: "unbond_handler_a".to_string(),
+            previous_owner: "user".to_string(),
+        }),
+    )
+    .unwrap();
+
+    assert!(UNBOND_TICKETS
+        .may_load(&deps.storage, (owner, U64Key::new(1u64)))
+        .unwrap()
+        .is_none());
+    assert!(UNBOND_HANDLERS_ASSIGNED
+        .may_load(&deps.storage, Addr::unchecked("unbond_handler_a"))
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        UNBOND_HANDLERS_AVAILABLE.load(&deps.storage).unwrap(),
+        vec![Addr::unchecked("unbond_handler_a")]
+    );
+}
@@ -1,6 +1,6 @@
 use cosmwasm_std::{
-    entry_point, to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Order, Response, StdResult, WasmMsg,
+    entry_point, to_binary, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg,
 };
 
 use terra_cosmwasm::{create_swap_msg, TerraMsgWrapper};
@@ -9,28 +9,33 @@ use terraswap::asset::{Asset, AssetInfo};
 use terraswap::querier::query_balance;
 
 use white_whale::denom::{LUNA_DENOM, UST_DENOM};
-
-use white_whale::ust_vault::terraswap::create_terraswap_msg;
+use white_whale::query::terraswap::query_asset_balance;
 
 use white_whale::deposit_info::ArbBaseAsset;
 use white_whale::luna_vault::msg::{ExecuteMsg as LunaVaultMsg, FlashLoanPayload};
-use white_whale::query::terraswap::simulate_swap as simulate_terraswap_swap;
 use white_whale::tax::deduct_tax;
-use white_whale::ust_vault::msg::ExecuteMsg as VaultMsg;
-
-use white_whale::flashloan::flashloan::FlashLoanPayload;
+use white_whale::ust_vault::msg::{
+    ExecuteMsg as VaultMsg, FeeResponse, FlashLoanPayload as VaultFlashLoanPayload, VaultQueryMsg,
+};
+use white_whale::vault::fees::LoanFee;
 
 use cw2::{get_contract_version, set_contract_version};
 use semver::Version;
+use signed_integer::SignedInt;
 
 use crate::error::StableArbError;
 use white_whale::peg_arb::msg::{
-    ArbDetails, CallbackMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ArbDetails, ArbRoute, CallbackMsg, DexKind, ExecuteMsg, Hop, InstantiateMsg,
+    KeeperShareResponse, MigrateMsg, QueryMsg,
 };
 
+use crate::dex::{asset_swap_msg, pool_swap_msg, query_pool_assets, simulate_swap};
 use crate::querier::query_market_price;
+use crate::simulation::{arb_profit, keeper_reward, query_vault_fee, simulate_arb};
 
-use crate::state::{State, ADMIN, ARB_BASE_ASSET, POOLS, STATE};
+use crate::state::{
+    ArbPool, State, ADMIN, ARB_BASE_ASSET, KEEPER_SHARE, LEGACY_POOLS, POOLS, STATE,
+};
 use white_whale::memory::LIST_SIZE_LIMIT;
 type VaultResult = Result<Response<TerraMsgWrapper>, StableArbError>;
 
@@ -74,6 +79,8 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> V
         }
         ExecuteMsg::BelowPegCallback { details } => try_arb_below_peg(deps, env, info, details),
         ExecuteMsg::AbovePegCallback { details } => try_arb_above_peg(deps, env, info, details),
+        ExecuteMsg::AmmArbCallback { details } => try_arb_across_pools(deps, env, info, details),
+        ExecuteMsg::RouteArbCallback { details } => try_arb_route(deps, env, info, details),
         ExecuteMsg::SetAdmin { admin } => {
             let admin_addr = deps.api.addr_validate(&admin)?;
             let previous_admin = ADMIN.get(deps.as_ref())?.unwrap();
@@ -83,7 +90,10 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> V
                 .add_attribute("admin", admin))
         }
         ExecuteMsg::SetVault { vault } => set_vault_addr(deps, info, vault),
-        ExecuteMsg::UpdatePools { to_add, to_remove } => update_pools(deps, to_add, to_remove),
+        ExecuteMsg::SetKeeperShare { share } => set_keeper_share(deps, info, share),
+        ExecuteMsg::UpdatePools { to_add, to_remove } => {
+            update_pools(deps, info, to_add, to_remove)
+        }
         ExecuteMsg::Callback(msg) => _handle_callback(deps, env, info, msg),
     }
 }
@@ -103,6 +113,23 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> VaultResult {
         // If state structure changed in any contract version in the way migration is needed, it
         // should occur here
     }
+
+    // Pools registered before the DEX kind was stored are Terraswap pools
+    let legacy_pools = LEGACY_POOLS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (name, address) in legacy_pools {
+        let name = String::from_utf8(name).map_err(StdError::from)?;
+        POOLS.save(
+            deps.storage,
+            &name,
+            &ArbPool {
+                address,
+                dex: DexKind::Terraswap,
+            },
+        )?;
+        LEGACY_POOLS.remove(deps.storage, &name);
+    }
     Ok(Response::default())
 }
 
@@ -117,6 +144,23 @@ fn _handle_callback(deps: DepsMut, env: Env, info: MessageInfo, msg: CallbackMsg
     }
     match msg {
         CallbackMsg::AfterSuccessfulTradeCallback {} => after_successful_trade_callback(deps, env),
+        CallbackMsg::SwapHop {
+            offer_asset,
+            hop,
+            max_spread,
+        } => swap_hop(deps, env, offer_asset, hop, max_spread),
+        CallbackMsg::AssertMinimumProfit {
+            borrowed,
+            min_profit,
+        } => assert_minimum_profit(deps, env, borrowed, min_profit),
+        CallbackMsg::PayKeeperReward { keeper, borrowed } => {
+            pay_keeper_reward(deps, env, keeper, borrowed)
+        }
+        CallbackMsg::AssertMinimumReceive {
+            balance_before,
+            minimum_receive,
+        } => assert_minimum_receive(deps, env, balance_before, minimum_receive),
+        CallbackMsg::SettleProfitShare { keeper } => settle_profit_share(deps, env, keeper),
         // Possibility to add more callbacks in future.
     }
 }
@@ -126,9 +170,9 @@ fn _handle_callback(deps: DepsMut, env: Env, info: MessageInfo, msg: CallbackMsg
 
 fn call_flashloan(
     deps: DepsMut,
-    _env: Env,
-    _msg_info: MessageInfo,
-    details: ArbDetails,
+    env: Env,
+    msg_info: MessageInfo,
+    mut details: ArbDetails,
     above_peg: bool,
 ) -> VaultResult {
     let state = STATE.load(deps.storage)?;
@@ -137,15 +181,48 @@ fn call_flashloan(
     // Check if requested asset is same as strategy base asset
     deposit_info.assert(&details.asset.info)?;
 
+    // The keeper share goes to the caller unless told otherwise
+    let reward_address = match &details.reward_address {
+        Some(address) => deps.api.addr_validate(address)?,
+        None => msg_info.sender,
+    };
+    details.reward_address = Some(reward_address.to_string());
+
+    // Don't borrow if the arb isn't expected to make the minimum profit
+    if let Some(min_profit) = details.min_profit {
+        let simulation = simulate_arb(deps.as_ref(), env.clone(), details.clone(), above_peg)?;
+        if simulation.net_profit < SignedInt::from(min_profit) {
+            return Err(StableArbError::ExpectedProfitTooLow {
+                expected: simulation.net_profit,
+                min_profit,
+            });
+        }
+    }
+
     // Construct callback msg
-    let callback_msg = if above_peg {
-        ExecuteMsg::AbovePegCallback {
+    let callback_msg = match details.route {
+        ArbRoute::AmmToAmm { .. } => ExecuteMsg::AmmArbCallback {
             details: details.clone(),
-        }
-    } else {
-        ExecuteMsg::BelowPegCallback {
+        },
+        ArbRoute::Hops { .. } => ExecuteMsg::RouteArbCallback {
             details: details.clone(),
+        },
+        ArbRoute::MarketModule {} if above_peg => ExecuteMsg::AbovePegCallback {
+            details: details.clone(),
+        },
+        ArbRoute::MarketModule {} => ExecuteMsg::BelowPegCallback {
+            details: details.clone(),
+        },
+    };
+
+    // Profit share loans refund the borrower's share of the profit once the vault settled the
+    // trade, the keeper is paid out of it after the flashloan
+    let settle_msgs = match query_vault_fee(deps.as_ref(), &env, details.asset.amount)?.fee {
+        LoanFee::ProfitShare { .. } => vec![CallbackMsg::SettleProfitShare {
+            keeper: details.reward_address.clone(),
         }
+        .to_cosmos_msg(&env.contract.address)?],
+        LoanFee::Flat { .. } => vec![],
     };
 
     // Construct payload
@@ -156,7 +233,7 @@ fn call_flashloan(
 
     let supported_assets = vec![LUNA_DENOM.to_string(), UST_DENOM.to_string()];
 
-    match &deposit_info.asset_info {
+    let flashloan_msg = match &deposit_info.asset_info {
         // Eventually Can be expanded for CW20s, just remove the Err and replace with LOGIC
         AssetInfo::Token { .. } => return Err(StableArbError::Unauthorized {}),
         AssetInfo::NativeToken { denom } => {
@@ -164,30 +241,31 @@ fn call_flashloan(
                 return Err(StableArbError::UnsupportedAsset {});
             }
             match denom {
-                LUNA_DENOM => {
-                    return Ok(
-                        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                            contract_addr: state.vault_address.to_string(),
-                            msg: to_binary(&LunaVaultMsg::FlashLoan {
-                                payload: payload.clone(),
-                            })?,
-                            funds: vec![],
-                        })),
-                    );
-                }
-                UST_DENOM => {
-                    return Ok(
-                        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-                            contract_addr: state.vault_address.to_string(),
-                            msg: to_binary(&VaultMsg::FlashLoan { payload })?,
-                            funds: vec![],
-                        })),
-                    )
-                }
+                LUNA_DENOM => CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: state.vault_address.to_string(),
+                    msg: to_binary(&LunaVaultMsg::FlashLoan {
+                        payload: payload.clone(),
+                    })?,
+                    funds: vec![],
+                }),
+                UST_DENOM => CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: state.vault_address.to_string(),
+                    msg: to_binary(&VaultMsg::FlashLoan {
+                        payload: VaultFlashLoanPayload {
+                            requested_asset: payload.requested_asset,
+                            callback: payload.callback,
+                        },
+                    })?,
+                    funds: vec![],
+                }),
                 _ => return Err(StableArbError::UnsupportedAsset {}),
-            };
+            }
         }
-    }
+    };
+
+    Ok(Response::new()
+        .add_message(flashloan_msg)
+        .add_messages(settle_msgs))
 }
 
 // Attempt to perform an arbitrage operation with the assumption that
@@ -209,7 +287,7 @@ pub fn try_arb_below_peg(
     }
 
     // Set vars
-    let pool_address = POOLS.load(deps.storage, &details.pool_name)?;
+    let pool = POOLS.load(deps.storage, &details.pool_name)?;
     let denom = deposit_info.get_denom()?;
     let lent_coin = deduct_tax(
         deps.as_ref(),
@@ -229,7 +307,7 @@ pub fn try_arb_below_peg(
     let expected_luna_received =
         query_market_price(deps.as_ref(), lent_coin.clone(), ask_denom.clone())?;
 
-    // Construct offer for Terraswap
+    // Construct offer for the pool
     let offer_coin = Coin {
         denom: ask_denom.clone(),
         amount: expected_luna_received,
@@ -238,16 +316,13 @@ pub fn try_arb_below_peg(
     // Market swap msg, swap STABLE -> LUNA
     let swap_msg = create_swap_msg(lent_coin.clone(), ask_denom);
 
-    // Terraswap msg, swap LUNA -> STABLE
-    let terraswap_msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: pool_address.to_string(),
-        funds: vec![offer_coin.clone()],
-        msg: to_binary(&create_terraswap_msg(
-            offer_coin,
-            details.belief_price,
-            Some(details.slippage),
-        ))?,
-    });
+    // Pool swap msg, swap LUNA -> STABLE
+    let pool_msg = pool_swap_msg(
+        &pool,
+        offer_coin,
+        details.belief_price,
+        Some(details.slippage),
+    )?;
 
     let logs = vec![
         ("action", String::from("arb below peg")),
@@ -256,14 +331,13 @@ pub fn try_arb_below_peg(
     ];
 
     // Create callback, this will send the funds back to the vault.
-    let callback_msg =
-        CallbackMsg::AfterSuccessfulTradeCallback {}.to_cosmos_msg(&env.contract.address)?;
+    let callback_msgs = repay_msgs(deps.as_ref(), &env, &details)?;
 
     Ok(response
         .add_attributes(logs)
         .add_message(swap_msg)
-        .add_message(terraswap_msg)
-        .add_message(callback_msg))
+        .add_message(pool_msg)
+        .add_messages(callback_msgs))
 }
 
 // Attempt to perform an arbitrage operation with the assumption that
@@ -284,7 +358,7 @@ pub fn try_arb_above_peg(
     }
 
     // Set vars
-    let pool_address = POOLS.load(deps.storage, &details.pool_name)?;
+    let pool = POOLS.load(deps.storage, &details.pool_name)?;
     let denom = deposit_info.get_denom()?;
     let lent_coin = deduct_tax(
         deps.as_ref(),
@@ -298,9 +372,8 @@ pub fn try_arb_above_peg(
     if balance < details.asset.amount {
         return Err(StableArbError::Broke {});
     }
-    // Simulate first tx with Terraswap
-    let expected_luna_received =
-        simulate_terraswap_swap(deps.as_ref(), pool_address.clone(), lent_coin.clone())?;
+    // Simulate first tx with the pool
+    let expected_luna_received = simulate_swap(deps.as_ref(), &pool, lent_coin.clone())?;
 
     // Construct offer for Market Swap
     let offer_coin = Coin {
@@ -308,16 +381,13 @@ pub fn try_arb_above_peg(
         amount: expected_luna_received,
     };
 
-    // Terraswap msg, swap STABLE -> LUNA
-    let terraswap_msg: CosmosMsg<TerraMsgWrapper> = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: pool_address.to_string(),
-        funds: vec![lent_coin.clone()],
-        msg: to_binary(&create_terraswap_msg(
-            lent_coin.clone(),
-            details.belief_price,
-            Some(details.slippage),
-        ))?,
-    });
+    // Pool swap msg, swap STABLE -> LUNA
+    let pool_msg = pool_swap_msg(
+        &pool,
+        lent_coin.clone(),
+        details.belief_price,
+        Some(details.slippage),
+    )?;
 
     // Market swap msg, swap LUNA -> STABLE
     let swap_msg = create_swap_msg(offer_coin, lent_coin.denom);
@@ -329,20 +399,375 @@ pub fn try_arb_above_peg(
     ];
 
     // Create callback, this will send the funds back to the vault.
-    let callback_msg =
-        CallbackMsg::AfterSuccessfulTradeCallback {}.to_cosmos_msg(&env.contract.address)?;
+    let callback_msgs = repay_msgs(deps.as_ref(), &env, &details)?;
 
     Ok(response
         .add_attributes(logs)
-        .add_message(terraswap_msg)
+        .add_message(pool_msg)
         .add_message(swap_msg)
-        .add_message(callback_msg))
+        .add_messages(callback_msgs))
+}
+
+// Attempt to arbitrage the same pair across two pools, swapping on the first pool and back
+// on the second one. Both legs are simulated before anything is sent.
+pub fn try_arb_across_pools(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    details: ArbDetails,
+) -> VaultResult {
+    let state = STATE.load(deps.storage)?;
+    let deposit_info = ARB_BASE_ASSET.load(deps.storage)?;
+
+    // Ensure the caller is the vault
+    if msg_info.sender != state.vault_address {
+        return Err(StableArbError::Unauthorized {});
+    }
+
+    let (second_pool_name, ask_denom) = match &details.route {
+        ArbRoute::AmmToAmm {
+            second_pool_name,
+            ask_denom,
+        } => (second_pool_name, ask_denom),
+        _ => return Err(StableArbError::InvalidRoute {}),
+    };
+
+    // Set vars
+    let first_pool = POOLS.load(deps.storage, &details.pool_name)?;
+    let second_pool = POOLS.load(deps.storage, second_pool_name)?;
+    let denom = deposit_info.get_denom()?;
+    let lent_coin = deduct_tax(
+        deps.as_ref(),
+        Coin::new(details.asset.amount.u128(), denom.clone()),
+    )?;
+    let response: Response<TerraMsgWrapper> = Response::new();
+
+    // Check if we have enough funds
+    let balance = query_balance(&deps.querier, env.contract.address.clone(), denom.clone())?;
+    if balance < details.asset.amount {
+        return Err(StableArbError::Broke {});
+    }
+
+    // The first pool has to trade the borrowed asset for the ask denom, and the second one back
+    let ask_asset = AssetInfo::NativeToken {
+        denom: ask_denom.clone(),
+    };
+    assert_pool_pair(
+        deps.as_ref(),
+        &first_pool,
+        &deposit_info.asset_info,
+        &ask_asset,
+    )?;
+    assert_pool_pair(
+        deps.as_ref(),
+        &second_pool,
+        &ask_asset,
+        &deposit_info.asset_info,
+    )?;
+
+    // Simulate first tx with the first pool
+    let expected_intermediate = simulate_swap(deps.as_ref(), &first_pool, lent_coin.clone())?;
+    // Stables are taxed when the pool sends them and again when offered to the second pool
+    let received_coin = deduct_tax(
+        deps.as_ref(),
+        Coin::new(expected_intermediate.u128(), ask_denom.clone()),
+    )?;
+    let offer_coin = deduct_tax(deps.as_ref(), received_coin)?;
+
+    // Simulate second tx with the second pool
+    let expected_return = simulate_swap(deps.as_ref(), &second_pool, offer_coin.clone())?;
+    if expected_return.is_zero() {
+        return Err(StableArbError::Std(StdError::generic_err(
+            "The second pool returns nothing for the offer",
+        )));
+    }
+    // The second pool deducts the transfer tax from what it sends
+    let received_return =
+        deduct_tax(deps.as_ref(), Coin::new(expected_return.u128(), denom))?.amount;
+    let minimum_receive = received_return * (Decimal::one() - details.slippage);
+
+    // Pool swap msg, swap borrowed asset -> ask asset
+    let first_pool_msg = pool_swap_msg(
+        &first_pool,
+        lent_coin.clone(),
+        details.belief_price,
+        Some(details.slippage),
+    )?;
+
+    // Pool swap msg, swap ask asset -> borrowed asset. The belief price of the simulation and
+    // the slippage make the swap fail if it returns less than the minimum
+    let second_pool_msg = pool_swap_msg(
+        &second_pool,
+        offer_coin.clone(),
+        Decimal::from_ratio(offer_coin.amount, expected_return),
+        Some(details.slippage),
+    )?;
+
+    // Fails the arb if the second pool returns less than the minimum receive. The first swap
+    // spends the whole borrowed amount, tax included
+    let assert_receive_msg = CallbackMsg::AssertMinimumReceive {
+        balance_before: balance - details.asset.amount,
+        minimum_receive,
+    }
+    .to_cosmos_msg(&env.contract.address)?;
+
+    let logs = vec![
+        ("action", String::from("arb across pools")),
+        ("offer_amount", lent_coin.amount.to_string()),
+        ("expected_intermediate", offer_coin.amount.to_string()),
+        ("expected_return", expected_return.to_string()),
+        ("minimum_receive", minimum_receive.to_string()),
+    ];
+
+    // Create callback, this will send the funds back to the vault.
+    let callback_msgs = repay_msgs(deps.as_ref(), &env, &details)?;
+
+    Ok(response
+        .add_attributes(logs)
+        .add_message(first_pool_msg)
+        .add_message(second_pool_msg)
+        .add_message(assert_receive_msg)
+        .add_messages(callback_msgs))
+}
+
+// Attempt to arbitrage along a multi-hop route. Each hop is a self callback swapping what the
+// previous hop returned, the funds then go back to the vault.
+pub fn try_arb_route(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    details: ArbDetails,
+) -> VaultResult {
+    let state = STATE.load(deps.storage)?;
+    let deposit_info = ARB_BASE_ASSET.load(deps.storage)?;
+
+    // Ensure the caller is the vault
+    if msg_info.sender != state.vault_address {
+        return Err(StableArbError::Unauthorized {});
+    }
+
+    let hops = match &details.route {
+        ArbRoute::Hops { hops } => hops.clone(),
+        _ => return Err(StableArbError::InvalidRoute {}),
+    };
+
+    // Check if we have enough funds
+    let denom = deposit_info.get_denom()?;
+    let balance = query_balance(&deps.querier, env.contract.address.clone(), denom)?;
+    if balance < details.asset.amount {
+        return Err(StableArbError::Broke {});
+    }
+
+    // Each hop offers what the previous one asked for, starting with the borrowed asset
+    let mut offer_asset = deposit_info.asset_info.clone();
+    let mut hop_msgs = vec![];
+    for hop in hops.iter() {
+        let ask_asset = match hop {
+            Hop::Pool {
+                pool_name,
+                ask_asset,
+            } => {
+                let pool = POOLS.load(deps.storage, pool_name)?;
+                // The hop has to ask for what the pool trades the offer asset for
+                assert_pool_pair(deps.as_ref(), &pool, &offer_asset, ask_asset)?;
+                ask_asset.clone()
+            }
+            Hop::MarketModule { ask_denom } => {
+                if let AssetInfo::Token { .. } = offer_asset {
+                    return Err(StableArbError::InvalidRoute {});
+                }
+                AssetInfo::NativeToken {
+                    denom: ask_denom.clone(),
+                }
+            }
+        };
+        hop_msgs.push(
+            CallbackMsg::SwapHop {
+                offer_asset,
+                hop: hop.clone(),
+                max_spread: details.slippage,
+            }
+            .to_cosmos_msg(&env.contract.address)?,
+        );
+        offer_asset = ask_asset;
+    }
+    // The route has to end in the borrowed asset
+    if hops.is_empty() || offer_asset != deposit_info.asset_info {
+        return Err(StableArbError::InvalidRoute {});
+    }
+
+    let logs = vec![
+        ("action", String::from("arb along route")),
+        ("offer_amount", details.asset.amount.to_string()),
+        ("hops", hops.len().to_string()),
+    ];
+
+    // Create callback, this will send the funds back to the vault.
+    let callback_msgs = repay_msgs(deps.as_ref(), &env, &details)?;
+
+    Ok(Response::new()
+        .add_attributes(logs)
+        .add_messages(hop_msgs)
+        .add_messages(callback_msgs))
 }
 
 //----------------------------------------------------------------------------------------
 //  CALLBACK FUNCTION HANDLERS
 //----------------------------------------------------------------------------------------
 
+// Swaps the whole balance of the offer asset on the hop, native balances are reduced by the
+// transfer tax.
+fn swap_hop(
+    deps: DepsMut,
+    env: Env,
+    offer_asset: AssetInfo,
+    hop: Hop,
+    max_spread: Decimal,
+) -> VaultResult {
+    let balance = query_asset_balance(deps.as_ref(), &offer_asset, env.contract.address)?;
+    let amount = match &offer_asset {
+        AssetInfo::NativeToken { denom } => {
+            deduct_tax(deps.as_ref(), Coin::new(balance.u128(), denom))?.amount
+        }
+        AssetInfo::Token { .. } => balance,
+    };
+    let offer = Asset {
+        info: offer_asset,
+        amount,
+    };
+
+    let swap_msg = match hop {
+        Hop::Pool { pool_name, .. } => {
+            let pool = POOLS.load(deps.storage, &pool_name)?;
+            asset_swap_msg(&pool, offer.clone(), max_spread)?
+        }
+        Hop::MarketModule { ask_denom } => match &offer.info {
+            AssetInfo::NativeToken { denom } => {
+                create_swap_msg(Coin::new(offer.amount.u128(), denom), ask_denom)
+            }
+            AssetInfo::Token { .. } => return Err(StableArbError::InvalidRoute {}),
+        },
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "arb hop")
+        .add_attribute("offer_amount", offer.amount.to_string())
+        .add_message(swap_msg))
+}
+
+// Fails the arb if its profit after the vault fee is below the minimum profit.
+fn assert_minimum_profit(
+    deps: DepsMut,
+    env: Env,
+    borrowed: Uint128,
+    min_profit: Uint128,
+) -> VaultResult {
+    let denom = ARB_BASE_ASSET.load(deps.storage)?.get_denom()?;
+    let balance = query_balance(&deps.querier, env.contract.address.clone(), denom.clone())?;
+    let (_, net_profit) = arb_profit(deps.as_ref(), &env, &denom, balance, borrowed)?;
+    if net_profit < SignedInt::from(min_profit) {
+        return Err(StableArbError::MinimumProfitNotMet {
+            profit: net_profit,
+            min_profit,
+        });
+    }
+    Ok(Response::new().add_attribute("profit", net_profit.to_string()))
+}
+
+// Fails the arb if less than the minimum receive of the borrowed asset came back since its
+// balance was the given one.
+fn assert_minimum_receive(
+    deps: DepsMut,
+    env: Env,
+    balance_before: Uint128,
+    minimum_receive: Uint128,
+) -> VaultResult {
+    let denom = ARB_BASE_ASSET.load(deps.storage)?.get_denom()?;
+    let balance = query_balance(&deps.querier, env.contract.address, denom)?;
+    let received = balance.saturating_sub(balance_before);
+    if received < minimum_receive {
+        return Err(StableArbError::MinimumReceiveNotMet {
+            received,
+            minimum_receive,
+        });
+    }
+    Ok(Response::new().add_attribute("received", received.to_string()))
+}
+
+// Pays the keeper share of the profit, what's left of the balance goes back to the vault.
+fn pay_keeper_reward(deps: DepsMut, env: Env, keeper: String, borrowed: Uint128) -> VaultResult {
+    let denom = ARB_BASE_ASSET.load(deps.storage)?.get_denom()?;
+    let balance = query_balance(&deps.querier, env.contract.address.clone(), denom.clone())?;
+    let (_, net_profit) = arb_profit(deps.as_ref(), &env, &denom, balance, borrowed)?;
+    let reward = keeper_reward(deps.storage, net_profit)?;
+    if reward.is_zero() {
+        return Ok(Response::new());
+    }
+
+    let reward_asset = Asset {
+        info: AssetInfo::NativeToken { denom },
+        amount: reward,
+    };
+    Ok(Response::new()
+        .add_attribute("keeper_reward", reward.to_string())
+        .add_message(CosmosMsg::Bank(BankMsg::Send {
+            to_address: keeper,
+            amount: vec![reward_asset.deduct_tax(&deps.querier)?],
+        })))
+}
+
+// Splits the refund of a profit share loan, which the vault sends once the trade is settled,
+// between the keeper and the vault treasury. The arb sent the rest of its balance back to the
+// vault, so the whole balance is the refund.
+fn settle_profit_share(deps: DepsMut, env: Env, keeper: Option<String>) -> VaultResult {
+    let state = STATE.load(deps.storage)?;
+    let denom = ARB_BASE_ASSET.load(deps.storage)?.get_denom()?;
+    let refund = query_balance(&deps.querier, env.contract.address, denom.clone())?;
+    let mut response = Response::new().add_attribute("refund", refund.to_string());
+    if refund.is_zero() {
+        return Ok(response);
+    }
+
+    // The refund is the profit left after the vault fee
+    let reward = if keeper.is_some() {
+        keeper_reward(deps.storage, SignedInt::from(refund))?
+    } else {
+        Uint128::zero()
+    };
+    if let Some(keeper) = keeper.filter(|_| !reward.is_zero()) {
+        let reward_asset = Asset {
+            info: AssetInfo::NativeToken {
+                denom: denom.clone(),
+            },
+            amount: reward,
+        };
+        response = response
+            .add_attribute("keeper_reward", reward.to_string())
+            .add_message(CosmosMsg::Bank(BankMsg::Send {
+                to_address: keeper,
+                amount: vec![reward_asset.deduct_tax(&deps.querier)?],
+            }));
+    }
+
+    let treasury_amount = refund - reward;
+    if !treasury_amount.is_zero() {
+        let fees: FeeResponse = deps
+            .querier
+            .query_wasm_smart(state.vault_address, &VaultQueryMsg::Fees {})?;
+        let treasury_asset = Asset {
+            info: AssetInfo::NativeToken { denom },
+            amount: treasury_amount,
+        };
+        response = response
+            .add_attribute("treasury_amount", treasury_amount.to_string())
+            .add_message(CosmosMsg::Bank(BankMsg::Send {
+                to_address: fees.fees.treasury_addr.to_string(),
+                amount: vec![treasury_asset.deduct_tax(&deps.querier)?],
+            }));
+    }
+    Ok(response)
+}
+
 // After the arb this function returns the funds to the vault.
 fn after_successful_trade_callback(deps: DepsMut, env: Env) -> VaultResult {
     let state = STATE.load(deps.storage)?;
@@ -364,21 +789,76 @@ fn after_successful_trade_callback(deps: DepsMut, env: Env) -> VaultResult {
     })))
 }
 
+// Checks the arb made the minimum profit and pays the keeper if it gets a share of it, then
+// returns the funds to the vault. On profit share loans the keeper is paid out of the refund
+// instead, once the vault took its fee.
+fn repay_msgs(
+    deps: Deps,
+    env: &Env,
+    details: &ArbDetails,
+) -> StdResult<Vec<CosmosMsg<TerraMsgWrapper>>> {
+    let mut msgs = vec![CallbackMsg::AssertMinimumProfit {
+        borrowed: details.asset.amount,
+        min_profit: details.min_profit.unwrap_or_default(),
+    }
+    .to_cosmos_msg(&env.contract.address)?];
+    let keeper_share = KEEPER_SHARE.may_load(deps.storage)?.unwrap_or_default();
+    let flat_fee = matches!(
+        query_vault_fee(deps, env, details.asset.amount)?.fee,
+        LoanFee::Flat { .. }
+    );
+    if let Some(keeper) = details
+        .reward_address
+        .clone()
+        .filter(|_| flat_fee && !keeper_share.is_zero())
+    {
+        msgs.push(
+            CallbackMsg::PayKeeperReward {
+                keeper,
+                borrowed: details.asset.amount,
+            }
+            .to_cosmos_msg(&env.contract.address)?,
+        );
+    }
+    msgs.push(CallbackMsg::AfterSuccessfulTradeCallback {}.to_cosmos_msg(&env.contract.address)?);
+    Ok(msgs)
+}
+
+// Checks the pool trades the offer asset for the ask asset.
+fn assert_pool_pair(
+    deps: Deps,
+    pool: &ArbPool,
+    offer_asset: &AssetInfo,
+    ask_asset: &AssetInfo,
+) -> Result<(), StableArbError> {
+    let pool_assets = query_pool_assets(deps, pool)?;
+    if offer_asset == ask_asset
+        || !pool_assets.contains(offer_asset)
+        || !pool_assets.contains(ask_asset)
+    {
+        return Err(StableArbError::InvalidRoute {});
+    }
+    Ok(())
+}
+
 pub fn update_pools(
     deps: DepsMut,
-    to_add: Option<Vec<(String, String)>>,
+    msg_info: MessageInfo,
+    to_add: Option<Vec<(String, String, DexKind)>>,
     to_remove: Option<Vec<String>>,
 ) -> VaultResult {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
     if let Some(pools_to_add) = to_add {
-        if POOLS
+        let pool_count = POOLS
             .keys(deps.storage, None, None, Order::Ascending)
-            .count()
-            >= LIST_SIZE_LIMIT
-        {
+            .count();
+        if pool_count + pools_to_add.len() > LIST_SIZE_LIMIT {
             return Err(StableArbError::PoolLimitReached {});
         }
 
-        for (name, new_address) in pools_to_add.into_iter() {
+        for (name, new_address, dex) in pools_to_add.into_iter() {
             if name.is_empty() {
                 return Err(StableArbError::EmptyPoolName {});
             };
@@ -386,7 +866,10 @@ pub fn update_pools(
             POOLS.save(
                 deps.storage,
                 name.as_str(),
-                &deps.api.addr_validate(&new_address)?,
+                &ArbPool {
+                    address: deps.api.addr_validate(&new_address)?,
+                    dex,
+                },
             )?;
         }
     }
@@ -420,14 +903,31 @@ pub fn set_vault_addr(deps: DepsMut, msg_info: MessageInfo, vault_address: Strin
         .add_attribute("previous vault", previous_vault))
 }
 
+pub fn set_keeper_share(deps: DepsMut, msg_info: MessageInfo, share: Decimal) -> VaultResult {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    if share > Decimal::one() {
+        return Err(StableArbError::InvalidKeeperShare {});
+    }
+    KEEPER_SHARE.save(deps.storage, &share)?;
+    Ok(Response::new().add_attribute("keeper share", share.to_string()))
+}
+
 //----------------------------------------------------------------------------------------
 //  QUERY HANDLERS
 //----------------------------------------------------------------------------------------
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&try_query_config(deps)?),
+        QueryMsg::SimulateArb { details, above_peg } => {
+            to_binary(&simulate_arb(deps, env, details, above_peg)?)
+        }
+        QueryMsg::KeeperShare {} => to_binary(&KeeperShareResponse {
+            share: KEEPER_SHARE.may_load(deps.storage)?.unwrap_or_default(),
+        }),
     }
 }
 
@@ -0,0 +1,118 @@
+use cosmwasm_std::{
+    to_binary, Coin, CosmosMsg, Decimal, Deps, QueryRequest, StdResult, Uint128, WasmMsg, WasmQuery,
+};
+use cw20::Cw20ExecuteMsg;
+use terra_cosmwasm::TerraMsgWrapper;
+use terraswap::asset::PairInfo;
+use terraswap::asset::{Asset, AssetInfo};
+use terraswap::pair::{
+    Cw20HookMsg, ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg, SimulationResponse,
+};
+
+use white_whale::astroport_helper::{create_astroport_msg, ExecuteMsg as AstroportPairMsg};
+use white_whale::peg_arb::msg::DexKind;
+use white_whale::query::astroport::simulate_swap as simulate_astroport_swap;
+use white_whale::query::terraswap::simulate_swap as simulate_terraswap_swap;
+use white_whale::ust_vault::terraswap::create_terraswap_msg;
+
+use crate::state::ArbPool;
+
+/// Simulates swapping the offer coin on the pool, returns the amount received
+pub fn simulate_swap(deps: Deps, pool: &ArbPool, offer_coin: Coin) -> StdResult<Uint128> {
+    match pool.dex {
+        DexKind::Terraswap => simulate_terraswap_swap(deps, pool.address.clone(), offer_coin),
+        DexKind::Astroport => simulate_astroport_swap(deps, pool.address.clone(), offer_coin),
+    }
+}
+
+/// Builds the msg swapping the offer coin on the pool
+pub fn pool_swap_msg(
+    pool: &ArbPool,
+    offer_coin: Coin,
+    belief_price: Decimal,
+    max_spread: Option<Decimal>,
+) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+    let msg = match pool.dex {
+        DexKind::Terraswap => to_binary(&create_terraswap_msg(
+            offer_coin.clone(),
+            belief_price,
+            max_spread,
+        ))?,
+        DexKind::Astroport => to_binary(&create_astroport_msg(
+            offer_coin.clone(),
+            belief_price,
+            max_spread,
+        ))?,
+    };
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: pool.address.to_string(),
+        funds: vec![offer_coin],
+        msg,
+    }))
+}
+
+/// Simulates swapping the offer asset, native or cw20, on the pool. Terraswap and Astroport pairs
+/// share the simulation query
+pub fn simulate_asset_swap(deps: Deps, pool: &ArbPool, offer_asset: Asset) -> StdResult<Uint128> {
+    let response: SimulationResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: pool.address.to_string(),
+            msg: to_binary(&PairQueryMsg::Simulation { offer_asset })?,
+        }))?;
+    Ok(response.return_amount)
+}
+
+/// Queries the assets traded on the pool. Terraswap and Astroport pairs share the pair query
+pub fn query_pool_assets(deps: Deps, pool: &ArbPool) -> StdResult<[AssetInfo; 2]> {
+    let pair: PairInfo = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: pool.address.to_string(),
+        msg: to_binary(&PairQueryMsg::Pair {})?,
+    }))?;
+    Ok(pair.asset_infos)
+}
+
+/// Builds the msg swapping the offer asset on the pool, with no belief price. Cw20 tokens are
+/// sent to the pool with the swap hook, which is the same on both DEXes
+pub fn asset_swap_msg(
+    pool: &ArbPool,
+    offer_asset: Asset,
+    max_spread: Decimal,
+) -> StdResult<CosmosMsg<TerraMsgWrapper>> {
+    match offer_asset.info.clone() {
+        AssetInfo::NativeToken { denom } => {
+            let funds = vec![Coin::new(offer_asset.amount.u128(), denom)];
+            let msg = match pool.dex {
+                DexKind::Terraswap => to_binary(&PairExecuteMsg::Swap {
+                    offer_asset,
+                    belief_price: None,
+                    max_spread: Some(max_spread),
+                    to: None,
+                })?,
+                DexKind::Astroport => to_binary(&AstroportPairMsg::Swap {
+                    offer_asset,
+                    belief_price: None,
+                    max_spread: Some(max_spread),
+                    to: None,
+                })?,
+            };
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: pool.address.to_string(),
+                funds,
+                msg,
+            }))
+        }
+        AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: pool.address.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&Cw20HookMsg::Swap {
+                    belief_price: None,
+                    max_spread: Some(max_spread),
+                    to: None,
+                })?,
+            })?,
+        })),
+    }
+}
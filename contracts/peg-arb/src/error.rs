@@ -1,7 +1,8 @@
 use thiserror::Error;
 
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use cw_controllers::AdminError;
+use signed_integer::SignedInt;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum StableArbError {
@@ -31,6 +32,34 @@ pub enum StableArbError {
 
     #[error("The pool list has reached its limit, can't store more contracts.")]
     PoolLimitReached {},
+
+    #[error("The route of the arb doesn't match the callback")]
+    InvalidRoute {},
+
+    #[error(
+        "The arb made {profit} after the vault fee, less than the minimum profit of {min_profit}"
+    )]
+    MinimumProfitNotMet {
+        profit: SignedInt,
+        min_profit: Uint128,
+    },
+
+    #[error("The arb received {received}, less than the minimum receive of {minimum_receive}")]
+    MinimumReceiveNotMet {
+        received: Uint128,
+        minimum_receive: Uint128,
+    },
+
+    #[error(
+        "The arb is expected to make {expected}, less than the minimum profit of {min_profit}"
+    )]
+    ExpectedProfitTooLow {
+        expected: SignedInt,
+        min_profit: Uint128,
+    },
+
+    #[error("The keeper share can't be more than 100%")]
+    InvalidKeeperShare {},
 }
 
 impl From<semver::Error> for StableArbError {
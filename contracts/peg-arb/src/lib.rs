@@ -1,7 +1,9 @@
 pub mod contract;
+pub mod dex;
 pub mod error;
 pub mod querier;
 pub mod response;
+pub mod simulation;
 pub mod state;
 
 #[cfg(test)]
@@ -0,0 +1,213 @@
+use cosmwasm_std::{Coin, Deps, Env, StdError, StdResult, Storage, Uint128};
+use signed_integer::SignedInt;
+use terraswap::asset::{Asset, AssetInfo};
+
+use white_whale::denom::LUNA_DENOM;
+use white_whale::peg_arb::msg::{ArbDetails, ArbRoute, Hop, SimulateArbResponse};
+use white_whale::tax::deduct_tax;
+use white_whale::ust_vault::msg::VaultQueryMsg;
+use white_whale::vault::fees::{EffectiveFeeResponse, LoanFee};
+
+use crate::dex::{simulate_asset_swap, simulate_swap};
+use crate::querier::query_market_price;
+use crate::state::{ARB_BASE_ASSET, KEEPER_SHARE, POOLS, STATE};
+
+/// Simulates the arb the way the callbacks execute it, then what the vault gets back and
+/// charges for the loan
+pub fn simulate_arb(
+    deps: Deps,
+    env: Env,
+    details: ArbDetails,
+    above_peg: bool,
+) -> StdResult<SimulateArbResponse> {
+    let deposit_info = ARB_BASE_ASSET.load(deps.storage)?;
+    deposit_info.assert(&details.asset.info)?;
+    let denom = deposit_info.clone().get_denom()?;
+    let lent_coin = deduct_tax(deps, Coin::new(details.asset.amount.u128(), denom.clone()))?;
+
+    let intermediate_amounts = match &details.route {
+        ArbRoute::MarketModule {} if above_peg => {
+            let pool = POOLS.load(deps.storage, &details.pool_name)?;
+            let expected_luna_received = simulate_swap(deps, &pool, lent_coin)?;
+            let expected_stables = query_market_price(
+                deps,
+                Coin::new(expected_luna_received.u128(), LUNA_DENOM),
+                denom.clone(),
+            )?;
+            vec![
+                native_asset(LUNA_DENOM, expected_luna_received),
+                native_asset(&denom, expected_stables),
+            ]
+        }
+        ArbRoute::MarketModule {} => {
+            let pool = POOLS.load(deps.storage, &details.pool_name)?;
+            let expected_luna_received =
+                query_market_price(deps, lent_coin, LUNA_DENOM.to_string())?;
+            let expected_stables = simulate_swap(
+                deps,
+                &pool,
+                Coin::new(expected_luna_received.u128(), LUNA_DENOM),
+            )?;
+            vec![
+                native_asset(LUNA_DENOM, expected_luna_received),
+                received_from_pool(deps, native_asset(&denom, expected_stables))?,
+            ]
+        }
+        ArbRoute::AmmToAmm {
+            second_pool_name,
+            ask_denom,
+        } => {
+            let first_pool = POOLS.load(deps.storage, &details.pool_name)?;
+            let second_pool = POOLS.load(deps.storage, second_pool_name)?;
+            let expected_intermediate = simulate_swap(deps, &first_pool, lent_coin)?;
+            let received_coin = deduct_tax(
+                deps,
+                Coin::new(expected_intermediate.u128(), ask_denom.clone()),
+            )?;
+            let offer_coin = deduct_tax(deps, received_coin.clone())?;
+            let expected_return = simulate_swap(deps, &second_pool, offer_coin)?;
+            vec![
+                native_asset(&received_coin.denom, received_coin.amount),
+                received_from_pool(deps, native_asset(&denom, expected_return))?,
+            ]
+        }
+        ArbRoute::Hops { hops, .. } => simulate_hops(deps, &details, hops)?,
+    };
+
+    let final_asset = intermediate_amounts
+        .last()
+        .cloned()
+        .ok_or_else(|| StdError::generic_err("The route has no swaps"))?;
+    if final_asset.info != deposit_info.asset_info {
+        return Err(StdError::generic_err(
+            "The route has to end in the borrowed asset",
+        ));
+    }
+    let final_balance = final_asset.amount;
+    let (vault_fee, net_profit) =
+        arb_profit(deps, &env, &denom, final_balance, details.asset.amount)?;
+
+    Ok(SimulateArbResponse {
+        intermediate_amounts,
+        final_balance,
+        vault_fee,
+        net_profit,
+        keeper_reward: keeper_reward(deps.storage, net_profit)?,
+    })
+}
+
+/// Vault fee of the loan and profit of the arb after it, the whole balance of the borrowed
+/// asset going back to the vault less the transfer tax
+pub fn arb_profit(
+    deps: Deps,
+    env: &Env,
+    denom: &str,
+    balance: Uint128,
+    borrowed: Uint128,
+) -> StdResult<(Uint128, SignedInt)> {
+    let repaid = deduct_tax(deps, Coin::new(balance.u128(), denom))?.amount;
+    let fee = query_vault_fee(deps, env, borrowed)?;
+    let profit = SignedInt::from_subtraction(repaid, borrowed);
+    let vault_fee = match fee.fee {
+        LoanFee::ProfitShare { share } if !profit.is_negative() => share.compute(profit.abs()),
+        LoanFee::ProfitShare { .. } => Uint128::zero(),
+        LoanFee::Flat { .. } => fee.loan_fee,
+    };
+    Ok((vault_fee, profit.checked_sub(SignedInt::from(vault_fee))?))
+}
+
+/// Flashloan fee the vault charges the arb contract for borrowing the given amount
+pub fn query_vault_fee(
+    deps: Deps,
+    env: &Env,
+    borrowed: Uint128,
+) -> StdResult<EffectiveFeeResponse> {
+    let state = STATE.load(deps.storage)?;
+    deps.querier.query_wasm_smart(
+        state.vault_address,
+        &VaultQueryMsg::EffectiveFee {
+            borrower: env.contract.address.to_string(),
+            amount: borrowed,
+        },
+    )
+}
+
+/// Keeper share of the profit after the vault fee, nothing if the arb loses money
+pub fn keeper_reward(storage: &dyn Storage, net_profit: SignedInt) -> StdResult<Uint128> {
+    if net_profit.is_negative() {
+        return Ok(Uint128::zero());
+    }
+    Ok(net_profit.abs() * KEEPER_SHARE.may_load(storage)?.unwrap_or_default())
+}
+
+/// Assets received from each hop, each hop offering the whole balance of what the previous one
+/// returned less the transfer tax
+fn simulate_hops(deps: Deps, details: &ArbDetails, hops: &[Hop]) -> StdResult<Vec<Asset>> {
+    let mut received = details.asset.clone();
+    let mut amounts = vec![];
+    for hop in hops.iter() {
+        let offer_amount = match &received.info {
+            AssetInfo::NativeToken { denom } => {
+                deduct_tax(deps, Coin::new(received.amount.u128(), denom))?.amount
+            }
+            AssetInfo::Token { .. } => received.amount,
+        };
+        received = match hop {
+            Hop::Pool {
+                pool_name,
+                ask_asset,
+            } => {
+                let pool = POOLS.load(deps.storage, pool_name)?;
+                let offer_asset = Asset {
+                    info: received.info,
+                    amount: offer_amount,
+                };
+                let return_amount = simulate_asset_swap(deps, &pool, offer_asset)?;
+                received_from_pool(
+                    deps,
+                    Asset {
+                        info: ask_asset.clone(),
+                        amount: return_amount,
+                    },
+                )?
+            }
+            Hop::MarketModule { ask_denom } => match &received.info {
+                AssetInfo::NativeToken { denom } => native_asset(
+                    ask_denom,
+                    query_market_price(
+                        deps,
+                        Coin::new(offer_amount.u128(), denom),
+                        ask_denom.clone(),
+                    )?,
+                ),
+                AssetInfo::Token { .. } => {
+                    return Err(StdError::generic_err(
+                        "Only native assets can be swapped on the market module",
+                    ))
+                }
+            },
+        };
+        amounts.push(received.clone());
+    }
+    Ok(amounts)
+}
+
+/// Pools deduct the transfer tax from the native assets they send
+fn received_from_pool(deps: Deps, asset: Asset) -> StdResult<Asset> {
+    match &asset.info {
+        AssetInfo::NativeToken { denom } => Ok(native_asset(
+            denom,
+            deduct_tax(deps, Coin::new(asset.amount.u128(), denom))?.amount,
+        )),
+        AssetInfo::Token { .. } => Ok(asset),
+    }
+}
+
+fn native_asset(denom: &str, amount: Uint128) -> Asset {
+    Asset {
+        info: AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        },
+        amount,
+    }
+}
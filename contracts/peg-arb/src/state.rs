@@ -1,11 +1,12 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Decimal};
 use cw_controllers::Admin;
 use cw_storage_plus::{Item, Map};
 
 use white_whale::deposit_info::ArbBaseAsset;
+use white_whale::peg_arb::msg::DexKind;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 // The Arb State contains configuration options for the vault including
@@ -15,7 +16,18 @@ pub struct State {
     pub seignorage_address: Addr,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+// Pool to arb on, the DEX sets how the swaps are simulated and executed
+pub struct ArbPool {
+    pub address: Addr,
+    pub dex: DexKind,
+}
+
 pub const ADMIN: Admin = Admin::new("admin");
 pub const STATE: Item<State> = Item::new("\u{0}{5}state");
 pub const ARB_BASE_ASSET: Item<ArbBaseAsset> = Item::new("\u{0}{7}deposit");
-pub const POOLS: Map<&str, Addr> = Map::new("pools");
+pub const POOLS: Map<&str, ArbPool> = Map::new("arb_pools");
+/// Share of the arb profits, after the vault fee, paid to the keepers
+pub const KEEPER_SHARE: Item<Decimal> = Item::new("keeper_share");
+/// Terraswap pools registered before the DEX kind was stored, moved to POOLS on migration
+pub const LEGACY_POOLS: Map<&str, Addr> = Map::new("pools");
@@ -2,10 +2,12 @@ use crate::tests::common::{POOL_NAME, TEST_CREATOR, VAULT_CONTRACT};
 use crate::tests::instantiate::mock_instantiate;
 use crate::tests::mock_querier::mock_dependencies;
 use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{coins, CosmosMsg, Decimal, Uint128};
-use terra_cosmwasm::TerraRoute;
+use cosmwasm_std::{attr, coins, from_binary, CosmosMsg, Decimal, Uint128, WasmMsg};
+use signed_integer::SignedInt;
+use terra_cosmwasm::{TerraMsgWrapper, TerraRoute};
 use terraswap::asset::{Asset, AssetInfo};
 use white_whale::peg_arb::msg::*;
+use white_whale::ust_vault::msg::ExecuteMsg as VaultMsg;
 
 use crate::contract::{execute, instantiate};
 use crate::error::StableArbError;
@@ -30,6 +32,9 @@ fn when_given_a_below_peg_msg_then_handle_returns_first_a_mint_then_a_terraswap_
         pool_name: POOL_NAME.to_string(),
         slippage: Decimal::percent(1),
         belief_price: Decimal::percent(420),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
     };
 
     // Prepare a BelowPegCallback msg
@@ -41,8 +46,8 @@ fn when_given_a_below_peg_msg_then_handle_returns_first_a_mint_then_a_terraswap_
     let info = mock_info(VAULT_CONTRACT, &[]);
 
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
-    // We should have gotten 3 messages back in this case
-    assert_eq!(3, res.messages.len());
+    // We should have gotten 4 messages back in this case
+    assert_eq!(4, res.messages.len());
     // Verify the operations happened in the order we expect.
     // For below peg, we expect first a mint tx, followed by a swap
     let first_msg = res.messages[0].msg.clone();
@@ -79,6 +84,9 @@ fn when_given_an_above_peg_msg_then_handle_returns_first_a_terraswap_then_a_mint
         pool_name: POOL_NAME.to_string(),
         slippage: Decimal::percent(1),
         belief_price: Decimal::percent(420),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
     };
 
     // Prepare an AbovePegCallback msg
@@ -90,8 +98,8 @@ fn when_given_an_above_peg_msg_then_handle_returns_first_a_terraswap_then_a_mint
     let info = mock_info(VAULT_CONTRACT, &[]);
 
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
-    // We should have gotten 3 messages back in this case
-    assert_eq!(3, res.messages.len());
+    // We should have gotten 4 messages back in this case
+    assert_eq!(4, res.messages.len());
     // Verify the operations happened in the order we expect.
     // For above peg, we expect first terraswap swap tx, followed by a mint
     let first_msg = res.messages[0].msg.clone();
@@ -111,7 +119,6 @@ fn when_given_an_above_peg_msg_then_handle_returns_first_a_terraswap_then_a_mint
     }
 }
 
-
 #[test]
 fn when_given_a_wrong_arb_complains() {
     let mut deps = mock_dependencies(&coins(100000000, "uusd"));
@@ -130,12 +137,15 @@ fn when_given_a_wrong_arb_complains() {
         pool_name: POOL_NAME.to_string(),
         slippage: Decimal::percent(1),
         belief_price: Decimal::percent(420),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
     };
 
     // Prepare an AbovePegCallback msg
     let msg = ExecuteMsg::ExecuteArb {
         details: arb_detail,
-        above_peg: true
+        above_peg: true,
     };
 
     // Ensure the 'caller' is the VAULT_CONTRACT to avoid unauthorized issues
@@ -165,7 +175,11 @@ fn peg_arb_can_support_luna_arb_with_differing_msgs() {
         .expect("contract successfully handles InstantiateMsg");
 
     let add_pool_msg = ExecuteMsg::UpdatePools {
-        to_add: Some(vec![(POOL_NAME.to_string(), "terraswap_pool".to_string())]),
+        to_add: Some(vec![(
+            POOL_NAME.to_string(),
+            "terraswap_pool".to_string(),
+            DexKind::Terraswap,
+        )]),
         to_remove: None,
     };
 
@@ -184,12 +198,15 @@ fn peg_arb_can_support_luna_arb_with_differing_msgs() {
         pool_name: POOL_NAME.to_string(),
         slippage: Decimal::percent(1),
         belief_price: Decimal::percent(420),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
     };
 
     // Prepare an AbovePegCallback msg
     let msg = ExecuteMsg::ExecuteArb {
         details: arb_detail,
-        above_peg: true
+        above_peg: true,
     };
 
     // Ensure the 'caller' is the VAULT_CONTRACT to avoid unauthorized issues
@@ -198,10 +215,8 @@ fn peg_arb_can_support_luna_arb_with_differing_msgs() {
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
     // We should have gotten 1 messages back in this case
     assert_eq!(1, res.messages.len());
-
 }
 
-
 #[test]
 fn peg_arb_can_support_below_or_above_peg_with_luna() {
     let mut deps = mock_dependencies(&coins(100000000, "uluna"));
@@ -219,7 +234,11 @@ fn peg_arb_can_support_below_or_above_peg_with_luna() {
         .expect("contract successfully handles InstantiateMsg");
 
     let add_pool_msg = ExecuteMsg::UpdatePools {
-        to_add: Some(vec![(POOL_NAME.to_string(), "terraswap_pool".to_string())]),
+        to_add: Some(vec![(
+            POOL_NAME.to_string(),
+            "terraswap_pool".to_string(),
+            DexKind::Terraswap,
+        )]),
         to_remove: None,
     };
 
@@ -238,6 +257,9 @@ fn peg_arb_can_support_below_or_above_peg_with_luna() {
         pool_name: POOL_NAME.to_string(),
         slippage: Decimal::percent(1),
         belief_price: Decimal::percent(420),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
     };
 
     // Prepare an AbovePegCallback msg
@@ -249,8 +271,8 @@ fn peg_arb_can_support_below_or_above_peg_with_luna() {
     let info = mock_info(VAULT_CONTRACT, &[]);
 
     let res = execute(deps.as_mut(), env, info, msg).unwrap();
-    // We should have gotten 3 messages back in this case
-    assert_eq!(3, res.messages.len());
+    // We should have gotten 4 messages back in this case
+    assert_eq!(4, res.messages.len());
     // Verify the operations happened in the order we expect.
     // For above peg, we expect first terraswap swap tx, followed by a mint
     let first_msg = res.messages[0].msg.clone();
@@ -268,5 +290,455 @@ fn peg_arb_can_support_below_or_above_peg_with_luna() {
         CosmosMsg::Wasm(_wasm_msg) => panic!("unexpected"),
         _ => panic!("unexpected"),
     }
+}
+
+#[test]
+fn when_the_pool_is_on_astroport_then_the_swap_goes_to_the_astroport_pool() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+
+    let add_pool_msg = ExecuteMsg::UpdatePools {
+        to_add: Some(vec![(
+            "astroport_luna_ust".to_string(),
+            "astroport_pool".to_string(),
+            DexKind::Astroport,
+        )]),
+        to_remove: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        add_pool_msg,
+    )
+    .unwrap();
 
+    let arb_detail: ArbDetails = ArbDetails {
+        asset: Asset {
+            amount: Uint128::from(OFFER_AMOUNT),
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+        pool_name: "astroport_luna_ust".to_string(),
+        slippage: Decimal::percent(1),
+        belief_price: Decimal::percent(420),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
+    };
+    let msg = ExecuteMsg::AbovePegCallback {
+        details: arb_detail,
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(VAULT_CONTRACT, &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(4, res.messages.len());
+    match res.messages[0].msg.clone() {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+            assert_eq!(contract_addr, "astroport_pool")
+        }
+        _ => panic!("unexpected"),
+    }
+    // luna received from the astroport simulation is market swapped back
+    assert!(res.attributes.contains(&attr("expected_luna", "1000000")));
+}
+
+#[test]
+fn when_given_an_amm_to_amm_route_then_handle_swaps_on_both_pools_with_a_minimum_out() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+
+    let add_pool_msg = ExecuteMsg::UpdatePools {
+        to_add: Some(vec![(
+            "astroport_luna_ust".to_string(),
+            "astroport_pool".to_string(),
+            DexKind::Astroport,
+        )]),
+        to_remove: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        add_pool_msg,
+    )
+    .unwrap();
+
+    let arb_detail: ArbDetails = ArbDetails {
+        asset: Asset {
+            amount: Uint128::from(OFFER_AMOUNT),
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+        pool_name: POOL_NAME.to_string(),
+        slippage: Decimal::percent(1),
+        belief_price: Decimal::percent(420),
+        route: ArbRoute::AmmToAmm {
+            second_pool_name: "astroport_luna_ust".to_string(),
+            ask_denom: "uluna".to_string(),
+        },
+        min_profit: None,
+        reward_address: None,
+    };
+
+    // the route picks the callback, whatever the peg direction
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::ExecuteArb {
+            details: arb_detail.clone(),
+            above_peg: true,
+        },
+    )
+    .unwrap();
+    match res.messages[0].msg.clone() {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => match from_binary(&msg).unwrap() {
+            VaultMsg::FlashLoan { payload } => assert_eq!(
+                from_binary::<ExecuteMsg>(&payload.callback).unwrap(),
+                ExecuteMsg::AmmArbCallback {
+                    details: arb_detail.clone()
+                }
+            ),
+            _ => panic!("unexpected"),
+        },
+        _ => panic!("unexpected"),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(VAULT_CONTRACT, &[]),
+        ExecuteMsg::AmmArbCallback {
+            details: arb_detail,
+        },
+    )
+    .unwrap();
+    assert_eq!(5, res.messages.len());
+    match res.messages[0].msg.clone() {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+            assert_eq!(contract_addr, "terraswap_pool")
+        }
+        _ => panic!("unexpected"),
+    }
+    match res.messages[1].msg.clone() {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds,
+            ..
+        }) => {
+            assert_eq!(contract_addr, "astroport_pool");
+            assert_eq!(funds, coins(1000000, "uluna"));
+        }
+        _ => panic!("unexpected"),
+    }
+    // the second pool has to return the minimum out of what was borrowed, then the profit is
+    // checked before the funds go back to the vault
+    let env = mock_env();
+    assert_eq!(
+        res.messages[2].msg,
+        CallbackMsg::AssertMinimumReceive {
+            balance_before: Uint128::from(100000000u64 - OFFER_AMOUNT),
+            minimum_receive: Uint128::from(990000u64),
+        }
+        .to_cosmos_msg::<TerraMsgWrapper>(&env.contract.address)
+        .unwrap()
+    );
+    assert_eq!(
+        res.messages[3].msg,
+        CallbackMsg::AssertMinimumProfit {
+            borrowed: Uint128::from(OFFER_AMOUNT),
+            min_profit: Uint128::zero(),
+        }
+        .to_cosmos_msg::<TerraMsgWrapper>(&env.contract.address)
+        .unwrap()
+    );
+    assert_eq!(
+        res.messages[4].msg,
+        CallbackMsg::AfterSuccessfulTradeCallback {}
+            .to_cosmos_msg::<TerraMsgWrapper>(&env.contract.address)
+            .unwrap()
+    );
+    assert!(res.attributes.contains(&attr("expected_return", "1000000")));
+    assert!(res.attributes.contains(&attr("minimum_receive", "990000")));
+}
+
+#[test]
+fn when_the_first_pool_does_not_return_the_ask_denom_then_handle_fails() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+
+    let arb_detail: ArbDetails = ArbDetails {
+        asset: Asset {
+            amount: Uint128::from(OFFER_AMOUNT),
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+        pool_name: POOL_NAME.to_string(),
+        slippage: Decimal::percent(1),
+        belief_price: Decimal::percent(420),
+        route: ArbRoute::AmmToAmm {
+            second_pool_name: POOL_NAME.to_string(),
+            ask_denom: "ukrw".to_string(),
+        },
+        min_profit: None,
+        reward_address: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(VAULT_CONTRACT, &[]),
+        ExecuteMsg::AmmArbCallback {
+            details: arb_detail,
+        },
+    );
+    assert_eq!(res, Err(StableArbError::InvalidRoute {}));
+}
+
+#[test]
+fn when_the_second_pool_returns_less_than_the_minimum_receive_then_the_arb_fails() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+    let env = mock_env();
+    let info = mock_info(env.contract.address.as_str(), &[]);
+
+    let msg = ExecuteMsg::Callback(CallbackMsg::AssertMinimumReceive {
+        balance_before: Uint128::from(99000000u64),
+        minimum_receive: Uint128::from(1000000u64),
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(res.attributes, vec![attr("received", "1000000")]);
+
+    let msg = ExecuteMsg::Callback(CallbackMsg::AssertMinimumReceive {
+        balance_before: Uint128::from(99000001u64),
+        minimum_receive: Uint128::from(1000000u64),
+    });
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert_eq!(
+        res,
+        Err(StableArbError::MinimumReceiveNotMet {
+            received: Uint128::from(999999u64),
+            minimum_receive: Uint128::from(1000000u64),
+        })
+    );
+}
+
+#[test]
+fn when_the_route_does_not_match_the_callback_then_handle_fails() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+
+    let arb_detail: ArbDetails = ArbDetails {
+        asset: Asset {
+            amount: Uint128::from(OFFER_AMOUNT),
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+        pool_name: POOL_NAME.to_string(),
+        slippage: Decimal::percent(1),
+        belief_price: Decimal::percent(420),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(VAULT_CONTRACT, &[]),
+        ExecuteMsg::AmmArbCallback {
+            details: arb_detail,
+        },
+    );
+    assert_eq!(res, Err(StableArbError::InvalidRoute {}));
+}
+
+fn route_arb_details(hops: Vec<Hop>, min_profit: u64) -> ArbDetails {
+    ArbDetails {
+        asset: Asset {
+            amount: Uint128::from(OFFER_AMOUNT),
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+        pool_name: POOL_NAME.to_string(),
+        slippage: Decimal::percent(1),
+        belief_price: Decimal::percent(420),
+        route: ArbRoute::Hops { hops },
+        min_profit: Some(Uint128::from(min_profit)),
+        reward_address: None,
+    }
+}
+
+#[test]
+fn when_given_a_route_then_handle_runs_the_hops_then_checks_the_profit() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+
+    let hops = vec![
+        Hop::MarketModule {
+            ask_denom: "uluna".to_string(),
+        },
+        Hop::Pool {
+            pool_name: POOL_NAME.to_string(),
+            ask_asset: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+    ];
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(VAULT_CONTRACT, &[]),
+        ExecuteMsg::RouteArbCallback {
+            details: route_arb_details(hops.clone(), 10),
+        },
+    )
+    .unwrap();
+
+    let env = mock_env();
+    let expected_msgs = [
+        CallbackMsg::SwapHop {
+            offer_asset: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            hop: hops[0].clone(),
+            max_spread: Decimal::percent(1),
+        },
+        CallbackMsg::SwapHop {
+            offer_asset: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            hop: hops[1].clone(),
+            max_spread: Decimal::percent(1),
+        },
+        CallbackMsg::AssertMinimumProfit {
+            borrowed: Uint128::from(OFFER_AMOUNT),
+            min_profit: Uint128::from(10u64),
+        },
+        CallbackMsg::AfterSuccessfulTradeCallback {},
+    ];
+    assert_eq!(res.messages.len(), expected_msgs.len());
+    for (sub_msg, expected) in res.messages.iter().zip(expected_msgs) {
+        assert_eq!(
+            sub_msg.msg,
+            expected
+                .to_cosmos_msg::<TerraMsgWrapper>(&env.contract.address)
+                .unwrap()
+        );
+    }
+
+    // the pool hop swaps the whole balance of the offer asset
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(env.contract.address.as_str(), &[]),
+        ExecuteMsg::Callback(CallbackMsg::SwapHop {
+            offer_asset: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            hop: Hop::Pool {
+                pool_name: POOL_NAME.to_string(),
+                ask_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            },
+            max_spread: Decimal::percent(1),
+        }),
+    )
+    .unwrap();
+    match res.messages[0].msg.clone() {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds,
+            ..
+        }) => {
+            assert_eq!(contract_addr, "terraswap_pool");
+            assert_eq!(funds, coins(100000000, "uusd"));
+        }
+        _ => panic!("unexpected"),
+    }
+}
+
+#[test]
+fn when_the_route_does_not_end_in_the_borrowed_asset_then_handle_fails() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+
+    let hops = vec![Hop::MarketModule {
+        ask_denom: "uluna".to_string(),
+    }];
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(VAULT_CONTRACT, &[]),
+        ExecuteMsg::RouteArbCallback {
+            details: route_arb_details(hops, 0),
+        },
+    );
+    assert_eq!(res, Err(StableArbError::InvalidRoute {}));
+}
+
+#[test]
+fn when_a_hop_asks_for_an_asset_the_pool_does_not_trade_then_handle_fails() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+
+    // the pool trades ust for luna, not for krw
+    let hops = vec![
+        Hop::Pool {
+            pool_name: POOL_NAME.to_string(),
+            ask_asset: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+        },
+        Hop::MarketModule {
+            ask_denom: "uusd".to_string(),
+        },
+    ];
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(VAULT_CONTRACT, &[]),
+        ExecuteMsg::RouteArbCallback {
+            details: route_arb_details(hops, 0),
+        },
+    );
+    assert_eq!(res, Err(StableArbError::InvalidRoute {}));
+}
+
+#[test]
+fn when_the_route_misses_the_minimum_profit_then_the_arb_fails() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+    let env = mock_env();
+    let info = mock_info(env.contract.address.as_str(), &[]);
+
+    // 99999000 of profit, less the vault fee of 10
+    let msg = ExecuteMsg::Callback(CallbackMsg::AssertMinimumProfit {
+        borrowed: Uint128::from(OFFER_AMOUNT),
+        min_profit: Uint128::from(99998990u64),
+    });
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::Callback(CallbackMsg::AssertMinimumProfit {
+        borrowed: Uint128::from(OFFER_AMOUNT),
+        min_profit: Uint128::from(99998991u64),
+    });
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert_eq!(
+        res,
+        Err(StableArbError::MinimumProfitNotMet {
+            profit: SignedInt::from(99998990i128),
+            min_profit: Uint128::from(99998991u64),
+        })
+    );
 }
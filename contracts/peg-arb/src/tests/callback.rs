@@ -50,6 +50,9 @@ fn unsuccessful_vault_callback() {
         pool_name: POOL_NAME.to_string(),
         slippage: Decimal::percent(1),
         belief_price: Decimal::from_str("420").unwrap(),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
     };
 
     let msg = ExecuteMsg::AbovePegCallback {
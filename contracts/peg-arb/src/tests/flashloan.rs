@@ -31,6 +31,9 @@ fn successfull_flashloan_call() {
         pool_name: POOL_NAME.to_string(),
         slippage: Decimal::percent(1),
         belief_price: Decimal::from_str("420").unwrap(),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
     };
 
     let msg = ExecuteMsg::ExecuteArb {
@@ -59,6 +62,9 @@ fn unsuccessful_flashloan_call_wrong_denom() {
         pool_name: POOL_NAME.to_string(),
         slippage: Decimal::percent(1),
         belief_price: Decimal::from_str("420").unwrap(),
+        route: ArbRoute::MarketModule {},
+        min_profit: None,
+        reward_address: None,
     };
 
     let msg = ExecuteMsg::ExecuteArb {
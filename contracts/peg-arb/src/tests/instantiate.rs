@@ -3,11 +3,13 @@ use cosmwasm_std::Api;
 use cosmwasm_std::DepsMut;
 
 use crate::contract::{execute, instantiate};
+use crate::error::StableArbError;
 use crate::state::{State, ARB_BASE_ASSET, STATE};
 
 use terraswap::asset::AssetInfo;
 
 use white_whale::deposit_info::ArbBaseAsset;
+use white_whale::memory::LIST_SIZE_LIMIT;
 
 use crate::tests::common::{TEST_CREATOR, VAULT_CONTRACT};
 use crate::tests::mock_querier::mock_dependencies;
@@ -42,7 +44,11 @@ pub fn mock_instantiate(mut deps: DepsMut) {
         .expect("contract successfully handles InstantiateMsg");
 
     let add_pool_msg = ExecuteMsg::UpdatePools {
-        to_add: Some(vec![(POOL_NAME.to_string(), "terraswap_pool".to_string())]),
+        to_add: Some(vec![(
+            POOL_NAME.to_string(),
+            "terraswap_pool".to_string(),
+            DexKind::Terraswap,
+        )]),
         to_remove: None,
     };
 
@@ -126,3 +132,54 @@ fn unsuccessful_set_vault() {
         Err(_) => (),
     }
 }
+
+#[test]
+fn unsuccessful_update_pools_unauthorized() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::UpdatePools {
+        to_add: Some(vec![(
+            POOL_NAME.to_string(),
+            "malicious_pool".to_string(),
+            DexKind::Astroport,
+        )]),
+        to_remove: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("someone", &[]), msg);
+    match res {
+        Err(StableArbError::Admin(_)) => (),
+        _ => panic!("Must return StableArbError::Admin"),
+    }
+}
+
+#[test]
+fn unsuccessful_update_pools_limit_reached() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // one pool is already registered
+    let pools = |count: usize| {
+        (0..count)
+            .map(|i| {
+                (
+                    format!("pool{}", i),
+                    format!("pool_addr{}", i),
+                    DexKind::Terraswap,
+                )
+            })
+            .collect::<Vec<(String, String, DexKind)>>()
+    };
+    let msg = ExecuteMsg::UpdatePools {
+        to_add: Some(pools(LIST_SIZE_LIMIT)),
+        to_remove: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    assert_eq!(res, Err(StableArbError::PoolLimitReached {}));
+
+    let msg = ExecuteMsg::UpdatePools {
+        to_add: Some(pools(LIST_SIZE_LIMIT - 1)),
+        to_remove: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+}
@@ -0,0 +1,235 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{coins, from_binary, BankMsg, CosmosMsg, Decimal, Uint128};
+use signed_integer::SignedInt;
+use terra_cosmwasm::TerraMsgWrapper;
+use terraswap::asset::{Asset, AssetInfo};
+use white_whale::fee::Fee;
+use white_whale::peg_arb::msg::*;
+
+use crate::contract::{execute, query};
+use crate::error::StableArbError;
+use crate::tests::common::{POOL_NAME, TEST_CREATOR, VAULT_CONTRACT};
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::mock_dependencies;
+
+const OFFER_AMOUNT: u64 = 1000u64;
+
+fn arb_details(min_profit: Option<u64>, reward_address: Option<&str>) -> ArbDetails {
+    ArbDetails {
+        asset: Asset {
+            amount: Uint128::from(OFFER_AMOUNT),
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+        pool_name: POOL_NAME.to_string(),
+        slippage: Decimal::percent(1),
+        belief_price: Decimal::percent(420),
+        route: ArbRoute::MarketModule {},
+        min_profit: min_profit.map(Uint128::from),
+        reward_address: reward_address.map(String::from),
+    }
+}
+
+#[test]
+fn when_the_expected_profit_is_below_the_minimum_then_the_arb_is_not_started() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+    let info = mock_info(TEST_CREATOR, &[]);
+
+    // 1000 borrowed, 1000000 returned and a vault fee of 10
+    let msg = ExecuteMsg::ExecuteArb {
+        details: arb_details(Some(998991), None),
+        above_peg: false,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_eq!(
+        res,
+        Err(StableArbError::ExpectedProfitTooLow {
+            expected: SignedInt::from(998990i128),
+            min_profit: Uint128::from(998991u64),
+        })
+    );
+
+    let msg = ExecuteMsg::ExecuteArb {
+        details: arb_details(Some(998990), None),
+        above_peg: false,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(1, res.messages.len());
+}
+
+#[test]
+fn set_keeper_share() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetKeeperShare {
+        share: Decimal::percent(10),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("someone", &[]),
+        msg.clone(),
+    );
+    assert!(matches!(res, Err(StableArbError::Admin(_))));
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::SetKeeperShare {
+            share: Decimal::percent(101),
+        },
+    );
+    assert_eq!(res, Err(StableArbError::InvalidKeeperShare {}));
+
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+    let res: KeeperShareResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::KeeperShare {}).unwrap()).unwrap();
+    assert_eq!(res.share, Decimal::percent(10));
+}
+
+#[test]
+fn when_a_keeper_share_is_set_then_the_keeper_gets_its_share_of_the_profit() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+    let env = mock_env();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::SetKeeperShare {
+            share: Decimal::percent(10),
+        },
+    )
+    .unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VAULT_CONTRACT, &[]),
+        ExecuteMsg::BelowPegCallback {
+            details: arb_details(None, Some("keeper")),
+        },
+    )
+    .unwrap();
+    assert_eq!(5, res.messages.len());
+    assert_eq!(
+        res.messages[3].msg,
+        CallbackMsg::PayKeeperReward {
+            keeper: "keeper".to_string(),
+            borrowed: Uint128::from(OFFER_AMOUNT),
+        }
+        .to_cosmos_msg::<TerraMsgWrapper>(&env.contract.address)
+        .unwrap()
+    );
+
+    // 99999000 of profit, less the vault fee of 10
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(env.contract.address.as_str(), &[]),
+        ExecuteMsg::Callback(CallbackMsg::PayKeeperReward {
+            keeper: "keeper".to_string(),
+            borrowed: Uint128::from(OFFER_AMOUNT),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "keeper".to_string(),
+            amount: coins(9999899, "uusd"),
+        })
+    );
+}
+
+#[test]
+fn when_the_vault_takes_a_profit_share_then_the_keeper_is_paid_out_of_the_refund() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+    deps.querier.with_profit_share(Fee {
+        share: Decimal::percent(20),
+    });
+    let env = mock_env();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::SetKeeperShare {
+            share: Decimal::percent(10),
+        },
+    )
+    .unwrap();
+
+    // the refund is settled once the flashloan is over
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(TEST_CREATOR, &[]),
+        ExecuteMsg::ExecuteArb {
+            details: arb_details(None, Some("keeper")),
+            above_peg: false,
+        },
+    )
+    .unwrap();
+    assert_eq!(2, res.messages.len());
+    assert_eq!(
+        res.messages[1].msg,
+        CallbackMsg::SettleProfitShare {
+            keeper: Some("keeper".to_string()),
+        }
+        .to_cosmos_msg::<TerraMsgWrapper>(&env.contract.address)
+        .unwrap()
+    );
+
+    // the keeper isn't paid during the arb, before the vault took its share of the profit
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(VAULT_CONTRACT, &[]),
+        ExecuteMsg::BelowPegCallback {
+            details: arb_details(None, Some("keeper")),
+        },
+    )
+    .unwrap();
+    assert_eq!(4, res.messages.len());
+    assert_eq!(
+        res.messages[3].msg,
+        CallbackMsg::AfterSuccessfulTradeCallback {}
+            .to_cosmos_msg::<TerraMsgWrapper>(&env.contract.address)
+            .unwrap()
+    );
+
+    // the whole balance is the refund, the profit left after the vault fee. The keeper gets 10%
+    // of it and the rest goes to the treasury
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(env.contract.address.as_str(), &[]),
+        ExecuteMsg::Callback(CallbackMsg::SettleProfitShare {
+            keeper: Some("keeper".to_string()),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|msg| msg.msg)
+            .collect::<Vec<CosmosMsg<TerraMsgWrapper>>>(),
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "keeper".to_string(),
+                amount: coins(10000000, "uusd"),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(90000000, "uusd"),
+            }),
+        ]
+    );
+}
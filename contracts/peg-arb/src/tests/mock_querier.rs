@@ -1,8 +1,8 @@
 #![allow(dead_code)]
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Api, Binary, Coin, ContractResult, Decimal, OwnedDeps,
-    Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    from_binary, from_slice, to_binary, Addr, Api, Binary, Coin, ContractResult, Decimal,
+    OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use cosmwasm_storage::to_length_prefixed;
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
@@ -12,6 +12,11 @@ use terra_cosmwasm::{
 };
 use terraswap::asset::{Asset, AssetInfo, AssetInfoRaw, PairInfo, PairInfoRaw};
 use terraswap::pair::PoolResponse;
+use white_whale::fee::{Fee, VaultFee};
+use white_whale::ust_vault::msg::{FeeResponse, VaultQueryMsg};
+use white_whale::vault::fees::{EffectiveFeeResponse, LoanFee};
+
+use crate::tests::common::VAULT_CONTRACT;
 use terraswap::pair::SimulationResponse;
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
@@ -34,6 +39,7 @@ pub struct WasmMockQuerier {
     terraswap_pair_querier: TerraswapPairQuerier,
     token_querier: TokenQuerier,
     tax_querier: TaxQuerier,
+    profit_share: Option<Fee>,
 }
 
 #[derive(Clone, Default)]
@@ -216,7 +222,63 @@ impl WasmMockQuerier {
 
                     return SystemResult::Ok(ContractResult::from(to_binary(&msg_balance)));
                 }
-                if contract_addr == &String::from("terraswap_pool") {
+                if contract_addr == VAULT_CONTRACT {
+                    match from_binary(msg) {
+                        Ok(VaultQueryMsg::EffectiveFee { amount, .. }) => {
+                            let res = match self.profit_share.clone() {
+                                Some(share) => EffectiveFeeResponse {
+                                    loan_fee: Uint128::zero(),
+                                    fee: LoanFee::ProfitShare { share },
+                                },
+                                None => {
+                                    let fee = Fee {
+                                        share: Decimal::percent(1),
+                                    };
+                                    EffectiveFeeResponse {
+                                        loan_fee: fee.compute(amount),
+                                        fee: LoanFee::Flat { fee },
+                                    }
+                                }
+                            };
+                            return SystemResult::Ok(ContractResult::from(to_binary(&res)));
+                        }
+                        Ok(VaultQueryMsg::Fees {}) => {
+                            let fee = Fee {
+                                share: Decimal::percent(1),
+                            };
+                            let res = FeeResponse {
+                                fees: VaultFee {
+                                    flash_loan_fee: fee.clone(),
+                                    treasury_fee: fee.clone(),
+                                    commission_fee: fee,
+                                    treasury_addr: Addr::unchecked("treasury"),
+                                },
+                            };
+                            return SystemResult::Ok(ContractResult::from(to_binary(&res)));
+                        }
+                        _ => {}
+                    }
+                }
+                if contract_addr == &String::from("terraswap_pool")
+                    || contract_addr == &String::from("astroport_pool")
+                {
+                    // Both pools trade luna for ust
+                    if msg == &Binary::from(r#"{"pair":{}}"#.as_bytes()) {
+                        let pair_info = PairInfo {
+                            asset_infos: [
+                                AssetInfo::NativeToken {
+                                    denom: "uluna".to_string(),
+                                },
+                                AssetInfo::NativeToken {
+                                    denom: "uusd".to_string(),
+                                },
+                            ],
+                            contract_addr: contract_addr.to_string(),
+                            liquidity_token: "liquidity0000".to_string(),
+                        };
+                        return SystemResult::Ok(ContractResult::from(to_binary(&pair_info)));
+                    }
+
                     let binary_response = to_binary(&SimulationResponse {
                         return_amount: Uint128::from(1000000u64),
                         spread_amount: Uint128::zero(),
@@ -313,9 +375,15 @@ impl WasmMockQuerier {
             terraswap_pair_querier: TerraswapPairQuerier::default(),
             token_querier: TokenQuerier::default(),
             tax_querier: TaxQuerier::default(),
+            profit_share: None,
         }
     }
 
+    // configure the vault to take a share of the arb profit instead of a flat fee
+    pub fn with_profit_share(&mut self, share: Fee) {
+        self.profit_share = Some(share);
+    }
+
     // configure the terraswap pair
     pub fn with_terraswap_pairs(&mut self, pairs: &[(&String, &PairInfo)]) {
         self.terraswap_pair_querier = TerraswapPairQuerier::new(pairs);
@@ -3,4 +3,6 @@ mod callback;
 mod common;
 mod flashloan;
 mod instantiate;
+mod keeper;
 mod mock_querier;
+mod simulation;
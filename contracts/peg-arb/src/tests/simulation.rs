@@ -0,0 +1,105 @@
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{coins, from_binary, Decimal, Uint128};
+use signed_integer::SignedInt;
+use terraswap::asset::{Asset, AssetInfo};
+use white_whale::peg_arb::msg::*;
+
+use crate::contract::query;
+use crate::tests::common::POOL_NAME;
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::mock_dependencies;
+
+const OFFER_AMOUNT: u64 = 1000u64;
+
+fn arb_details(route: ArbRoute) -> ArbDetails {
+    ArbDetails {
+        asset: Asset {
+            amount: Uint128::from(OFFER_AMOUNT),
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        },
+        pool_name: POOL_NAME.to_string(),
+        slippage: Decimal::percent(1),
+        belief_price: Decimal::percent(420),
+        route,
+        min_profit: None,
+        reward_address: None,
+    }
+}
+
+fn native(denom: &str, amount: u64) -> Asset {
+    Asset {
+        info: AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        },
+        amount: Uint128::from(amount),
+    }
+}
+
+#[test]
+fn simulate_arb_below_peg() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+
+    let msg = QueryMsg::SimulateArb {
+        details: arb_details(ArbRoute::MarketModule {}),
+        above_peg: false,
+    };
+    let res: SimulateArbResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+
+    // market swap at 1:1 then the pool simulation
+    assert_eq!(
+        res,
+        SimulateArbResponse {
+            intermediate_amounts: vec![native("uluna", 1000), native("uusd", 1000000)],
+            final_balance: Uint128::from(1000000u64),
+            vault_fee: Uint128::from(10u64),
+            net_profit: SignedInt::from(998990i128),
+        }
+    );
+}
+
+#[test]
+fn simulate_arb_along_route() {
+    let mut deps = mock_dependencies(&coins(100000000, "uusd"));
+    mock_instantiate(deps.as_mut());
+
+    let route = ArbRoute::Hops {
+        hops: vec![
+            Hop::Pool {
+                pool_name: POOL_NAME.to_string(),
+                ask_asset: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            },
+            Hop::MarketModule {
+                ask_denom: "uusd".to_string(),
+            },
+        ],
+    };
+    let msg = QueryMsg::SimulateArb {
+        details: arb_details(route),
+        above_peg: false,
+    };
+    let res: SimulateArbResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res.intermediate_amounts,
+        vec![native("uluna", 1000000), native("uusd", 1000000)]
+    );
+    assert_eq!(res.net_profit, SignedInt::from(998990i128));
+
+    // the route has to end in the borrowed asset
+    let route = ArbRoute::Hops {
+        hops: vec![Hop::MarketModule {
+            ask_denom: "uluna".to_string(),
+        }],
+    };
+    let msg = QueryMsg::SimulateArb {
+        details: arb_details(route),
+        above_peg: false,
+    };
+    assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+}
@@ -0,0 +1,69 @@
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, Deps, Env, Fraction, StdResult, Uint128};
+use terraswap::asset::AssetInfo;
+use terraswap::querier::query_token_balance;
+
+use white_whale::anchor::{anchor_deposit_msg, anchor_withdraw_msg};
+use white_whale::denom::UST_DENOM;
+use white_whale::query::anchor::query_aust_exchange_rate;
+
+use crate::adapters::YieldAdapter;
+
+/// Deposits UST into the Anchor money market, the position is held as aUST
+pub struct AnchorAdapter {
+    pub money_market_address: Addr,
+    pub aust_address: Addr,
+    pub vault_address: Addr,
+}
+
+impl AnchorAdapter {
+    fn aust_balance(&self, deps: Deps) -> StdResult<Uint128> {
+        query_token_balance(
+            &deps.querier,
+            self.aust_address.clone(),
+            self.vault_address.clone(),
+        )
+    }
+}
+
+impl YieldAdapter for AnchorAdapter {
+    fn deposit(&self, deps: Deps, amount: Uint128) -> StdResult<Vec<CosmosMsg>> {
+        Ok(vec![anchor_deposit_msg(
+            deps,
+            self.money_market_address.clone(),
+            Coin::new(amount.u128(), UST_DENOM),
+        )?])
+    }
+
+    fn redeem(&self, deps: Deps, env: &Env, amount: Uint128) -> StdResult<Vec<CosmosMsg>> {
+        let aust_balance = self.aust_balance(deps)?;
+        let exchange_rate = self.exchange_rate(deps, env)?;
+        // Redeem all the aUST left when it isn't worth the requested amount
+        let aust_amount = match exchange_rate.inv() {
+            Some(inverse_rate) => (amount * inverse_rate).min(aust_balance),
+            None => aust_balance,
+        };
+        Ok(vec![anchor_withdraw_msg(
+            self.aust_address.clone(),
+            self.money_market_address.clone(),
+            aust_amount,
+        )?])
+    }
+
+    fn value(&self, deps: Deps, env: &Env) -> StdResult<Uint128> {
+        let aust_balance = self.aust_balance(deps)?;
+        if aust_balance.is_zero() {
+            return Ok(Uint128::zero());
+        }
+        Ok(self.exchange_rate(deps, env)? * aust_balance)
+    }
+
+    fn exchange_rate(&self, deps: Deps, env: &Env) -> StdResult<Decimal> {
+        query_aust_exchange_rate(env.clone(), deps, self.money_market_address.to_string())
+    }
+
+    fn position_info(&self) -> AssetInfo {
+        AssetInfo::Token {
+            contract_addr: self.aust_address.to_string(),
+        }
+    }
+}
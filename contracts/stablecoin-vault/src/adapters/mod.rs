@@ -0,0 +1,154 @@
+use cosmwasm_std::{Addr, Api, CosmosMsg, Decimal, Deps, Env, StdResult, Storage, Uint128};
+use terraswap::asset::AssetInfo;
+
+use white_whale::denom::UST_DENOM;
+use white_whale::ust_vault::msg::{YieldAdapterInfo, YieldAdapterKind};
+
+use crate::adapters::anchor::AnchorAdapter;
+use crate::error::StableVaultError;
+use crate::state::YIELD_ADAPTERS;
+
+pub(crate) mod anchor;
+
+/// Money market or position the vault deposits its idle funds into to earn a yield
+pub trait YieldAdapter {
+    /// Builds the messages depositing the given amount of the base asset from the vault into the adapter
+    fn deposit(&self, deps: Deps, amount: Uint128) -> StdResult<Vec<CosmosMsg>>;
+    /// Builds the messages redeeming the given amount of the base asset from the adapter into the vault
+    fn redeem(&self, deps: Deps, env: &Env, amount: Uint128) -> StdResult<Vec<CosmosMsg>>;
+    /// Values the position of the vault in the adapter, in the base asset
+    fn value(&self, deps: Deps, env: &Env) -> StdResult<Uint128>;
+    /// Base asset returned per unit of the position held
+    fn exchange_rate(&self, deps: Deps, env: &Env) -> StdResult<Decimal>;
+    /// Asset the position of the vault in the adapter is held as
+    fn position_info(&self) -> AssetInfo;
+}
+
+/// Returns the yield adapters of the vault, none for vaults keeping their idle funds
+pub fn load_adapters(storage: &dyn Storage) -> StdResult<Vec<YieldAdapterInfo>> {
+    Ok(YIELD_ADAPTERS.may_load(storage)?.unwrap_or_default())
+}
+
+/// Checks the weights of the adapters add up to 1, their deposit thresholds don't go below the
+/// stable cap, they take the base asset of the vault and that none is set twice
+pub fn validate_adapters(
+    api: &dyn Api,
+    asset_info: &AssetInfo,
+    adapters: Vec<YieldAdapterInfo>,
+) -> Result<Vec<YieldAdapterInfo>, StableVaultError> {
+    let total_weight = adapters
+        .iter()
+        .fold(Decimal::zero(), |total, adapter| total + adapter.weight);
+    if !adapters.is_empty() && total_weight != Decimal::one() {
+        return Err(StableVaultError::InvalidAdapterWeights {});
+    }
+
+    let mut validated: Vec<YieldAdapterInfo> = vec![];
+    for adapter in adapters {
+        if adapter.deposit_threshold < Decimal::one() {
+            return Err(StableVaultError::InvalidDepositThreshold {});
+        }
+        let kind = match adapter.adapter {
+            YieldAdapterKind::Anchor {
+                money_market_address,
+                aust_address,
+            } => {
+                let uusd = AssetInfo::NativeToken {
+                    denom: UST_DENOM.to_string(),
+                };
+                if *asset_info != uusd {
+                    return Err(StableVaultError::UnsupportedAdapterAsset {});
+                }
+                YieldAdapterKind::Anchor {
+                    money_market_address: api.addr_validate(&money_market_address)?.into_string(),
+                    aust_address: api.addr_validate(&aust_address)?.into_string(),
+                }
+            }
+        };
+        if validated.iter().any(|existing| existing.adapter == kind) {
+            return Err(StableVaultError::DuplicatedAdapter {});
+        }
+        validated.push(YieldAdapterInfo {
+            adapter: kind,
+            ..adapter
+        });
+    }
+    Ok(validated)
+}
+
+pub fn load_adapter(env: &Env, kind: &YieldAdapterKind) -> Box<dyn YieldAdapter> {
+    match kind {
+        // the addresses are validated when the adapters are set
+        YieldAdapterKind::Anchor {
+            money_market_address,
+            aust_address,
+        } => Box::new(AnchorAdapter {
+            money_market_address: Addr::unchecked(money_market_address),
+            aust_address: Addr::unchecked(aust_address),
+            vault_address: env.contract.address.clone(),
+        }),
+    }
+}
+
+/// Value of the positions of the vault in all its adapters, in the base asset
+pub fn adapters_value(deps: Deps, env: &Env) -> StdResult<Uint128> {
+    let mut total_value = Uint128::zero();
+    for adapter_info in load_adapters(deps.storage)? {
+        total_value += load_adapter(env, &adapter_info.adapter).value(deps, env)?;
+    }
+    Ok(total_value)
+}
+
+/// Builds the messages splitting the stables held above the stable cap across the adapters by
+/// weight. An adapter only takes deposits once the stables exceed its deposit threshold
+pub fn deposit_into_adapters(
+    deps: Deps,
+    env: &Env,
+    stables_in_contract: Uint128,
+    stable_cap: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    if stables_in_contract <= stable_cap {
+        return Ok(vec![]);
+    }
+    let idle_funds = stables_in_contract - stable_cap;
+
+    let mut messages = vec![];
+    for adapter_info in load_adapters(deps.storage)? {
+        if stables_in_contract <= stable_cap * adapter_info.deposit_threshold {
+            continue;
+        }
+        let deposit_amount = idle_funds * adapter_info.weight;
+        if deposit_amount.is_zero() {
+            continue;
+        }
+        messages.extend(load_adapter(env, &adapter_info.adapter).deposit(deps, deposit_amount)?);
+    }
+    Ok(messages)
+}
+
+/// Builds the messages redeeming up to the given amount from the adapters by ascending redeem
+/// priority, along with the amount they redeem
+pub fn redeem_from_adapters(
+    deps: Deps,
+    env: &Env,
+    amount: Uint128,
+) -> StdResult<(Vec<CosmosMsg>, Uint128)> {
+    let mut adapters = load_adapters(deps.storage)?;
+    adapters.sort_by_key(|adapter_info| adapter_info.redeem_priority);
+
+    let mut messages = vec![];
+    let mut redeemed = Uint128::zero();
+    for adapter_info in adapters {
+        if redeemed >= amount {
+            break;
+        }
+        let adapter = load_adapter(env, &adapter_info.adapter);
+        let redeem_amount = adapter.value(deps, env)?.min(amount - redeemed);
+        if redeem_amount.is_zero() {
+            continue;
+        }
+        messages.extend(adapter.redeem(deps, env, redeem_amount)?);
+        redeemed += redeem_amount;
+    }
+    Ok((messages, redeemed))
+}
@@ -1,39 +1,53 @@
 use cosmwasm_std::{
-    entry_point, from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps,
-    DepsMut, Env, Fraction, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg,
-    Uint128, WasmMsg,
+    entry_point, from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Uint128,
+    WasmMsg,
 };
 use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, MinterResponse};
+use cw_storage_plus::{Bound, U64Key};
 use protobuf::Message;
 use semver::Version;
-use terraswap::asset::{Asset, AssetInfo};
-use terraswap::pair::Cw20HookMsg;
-use terraswap::querier::{query_balance, query_supply, query_token_balance};
+use signed_integer::SignedInt;
+use terraswap::asset::{Asset, AssetInfo, AssetInfoRaw};
+use terraswap::querier::query_supply;
 use terraswap::token::InstantiateMsg as TokenInstantiateMsg;
 
-use white_whale::anchor::{anchor_deposit_msg, anchor_withdraw_msg};
 use white_whale::deposit_info::DepositInfo;
 use white_whale::fee::{Fee, VaultFee};
 use white_whale::memory::LIST_SIZE_LIMIT;
-use white_whale::query::anchor::query_aust_exchange_rate;
-use white_whale::tax::{compute_tax, into_msg_without_tax};
+use white_whale::snapshot::{
+    compute_apr, AprResponse, SharePriceResponse, SnapshotsResponse, VaultSnapshot,
+};
 use white_whale::ust_vault::msg::*;
 use white_whale::ust_vault::msg::{
     EstimateWithdrawFeeResponse, FeeResponse, ValueResponse, VaultQueryMsg as QueryMsg,
 };
-
+use white_whale::vault::fees::query_fee_tiers;
+use white_whale::vault::flashloan::{assert_no_loan_ongoing, PnlResponse, ProfitCheck};
+use white_whale::vault::pause::{self, assert_not_paused, PausableOperation};
+use white_whale::vault::performance_fee;
+
+use crate::adapters::{
+    adapters_value, deposit_into_adapters, load_adapter, load_adapters, redeem_from_adapters,
+    validate_adapters,
+};
 use crate::error::StableVaultError;
+use crate::flashloan::{self, StableVault};
 use crate::pool_info::{PoolInfo, PoolInfoRaw};
 use crate::response::MsgInstantiateContractResponse;
-use crate::state::{ProfitCheck, State, ADMIN, DEPOSIT_INFO, FEE, POOL_INFO, PROFIT, STATE};
+use crate::state::{
+    State, ADMIN, DEPOSIT_INFO, FEE, LAST_SNAPSHOT_ID, LEGACY_STATE, POOL_INFO, PROFIT, SNAPSHOTS,
+    SNAPSHOT_IDS, STATE, YIELD_ADAPTERS,
+};
 
 const INSTANTIATE_REPLY_ID: u8 = 1u8;
 pub const DEFAULT_LP_TOKEN_NAME: &str = "White Whale UST Vault LP Token";
 pub const DEFAULT_LP_TOKEN_SYMBOL: &str = "wwVUst";
-const ROUNDING_ERR_COMPENSATION: u32 = 10u32;
+const DEFAULT_SNAPSHOTS_LIMIT: u32 = 10u32;
+const MAX_SNAPSHOTS_LIMIT: u32 = 30u32;
 
-type VaultResult = Result<Response, StableVaultError>;
+pub(crate) type VaultResult = Result<Response, StableVaultError>;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:stablecoin-vault";
@@ -44,8 +58,6 @@ pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateM
     // Use CW2 to set the contract version, this is needed for migrations
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     let state = State {
-        anchor_money_market_address: deps.api.addr_validate(&msg.anchor_money_market_address)?,
-        aust_address: deps.api.addr_validate(&msg.aust_address)?,
         whitelisted_contracts: vec![],
         allow_non_whitelisted: false,
     };
@@ -53,10 +65,9 @@ pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateM
     // Store the initial config
     STATE.save(deps.storage, &state)?;
 
-    // Check if the provided asset is a native token
-    if !msg.asset_info.is_native_token() {
-        return Err(StableVaultError::NotNativeToken {});
-    }
+    let yield_adapters = validate_adapters(deps.api, &msg.asset_info, msg.yield_adapters)?;
+    YIELD_ADAPTERS.save(deps.storage, &yield_adapters)?;
+
     DEPOSIT_INFO.save(
         deps.storage,
         &DepositInfo {
@@ -84,19 +95,16 @@ pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateM
         contract_addr: env.contract.address.clone(),
         liquidity_token: Addr::unchecked(""),
         stable_cap: msg.stable_cap,
-        asset_infos: [
-            msg.asset_info.to_raw(deps.api)?,
-            AssetInfo::Token {
-                contract_addr: msg.aust_address,
-            }
-            .to_raw(deps.api)?,
-        ],
+        asset_infos: pool_asset_infos(deps.as_ref(), &env, &msg.asset_info, &yield_adapters)?,
     };
     POOL_INFO.save(deps.storage, pool_info)?;
 
     let profit = ProfitCheck {
         last_balance: Uint128::zero(),
         last_profit: Uint128::zero(),
+        last_value: Uint128::zero(),
+        last_pnl: SignedInt::zero(),
+        cumulative_pnl: SignedInt::zero(),
     };
     PROFIT.save(deps.storage, &profit)?;
 
@@ -137,22 +145,73 @@ pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateM
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> VaultResult {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> VaultResult {
     let version: Version = CONTRACT_VERSION.parse()?;
     let storage_version: Version = get_contract_version(deps.storage)?.version.parse()?;
 
     if storage_version < version {
         set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     }
+
+    // Vaults instantiated before the yield adapters use Anchor as only yield backend
+    if YIELD_ADAPTERS.may_load(deps.storage)?.is_none() {
+        migrate_legacy_state(deps, &env)?;
+    }
     Ok(Response::default())
 }
 
+/// Moves the Anchor addresses of the legacy state into an Anchor yield adapter, keeping the
+/// 150% stable cap deposit threshold used so far
+fn migrate_legacy_state(deps: DepsMut, env: &Env) -> StdResult<()> {
+    let legacy_state = LEGACY_STATE.load(deps.storage)?;
+    let yield_adapters = match (
+        legacy_state.anchor_money_market_address,
+        legacy_state.aust_address,
+    ) {
+        (Some(money_market_address), Some(aust_address)) => vec![YieldAdapterInfo {
+            adapter: YieldAdapterKind::Anchor {
+                money_market_address: money_market_address.into_string(),
+                aust_address: aust_address.into_string(),
+            },
+            weight: Decimal::one(),
+            redeem_priority: 0u32,
+            deposit_threshold: Decimal::percent(150),
+        }],
+        _ => vec![],
+    };
+    YIELD_ADAPTERS.save(deps.storage, &yield_adapters)?;
+    STATE.save(
+        deps.storage,
+        &State {
+            whitelisted_contracts: legacy_state.whitelisted_contracts,
+            allow_non_whitelisted: legacy_state.allow_non_whitelisted,
+        },
+    )?;
+
+    let deposit_info = DEPOSIT_INFO.load(deps.storage)?;
+    let mut pool_info = POOL_INFO.load(deps.storage)?;
+    pool_info.asset_infos = pool_asset_infos(
+        deps.as_ref(),
+        env,
+        &deposit_info.asset_info,
+        &yield_adapters,
+    )?;
+    POOL_INFO.save(deps.storage, &pool_info)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> VaultResult {
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
-        ExecuteMsg::ProvideLiquidity { asset } => try_provide_liquidity(deps, env, info, asset),
+        ExecuteMsg::ProvideLiquidity { asset } => {
+            // cw20 deposits go through the Receive hook, which transfers the tokens to the vault
+            if !asset.info.is_native_token() {
+                return Err(StableVaultError::NotNativeToken {});
+            }
+            try_provide_liquidity(deps, env, info, asset)
+        }
         ExecuteMsg::SetStableCap { stable_cap } => set_stable_cap(deps, info, stable_cap),
+        ExecuteMsg::SetYieldAdapters { adapters } => set_yield_adapters(deps, env, info, adapters),
         ExecuteMsg::SetAdmin { admin } => {
             let admin_addr = deps.api.addr_validate(&admin)?;
             let previous_admin = ADMIN.get(deps.as_ref())?.unwrap();
@@ -166,37 +225,27 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> V
             treasury_fee,
             commission_fee,
         } => set_fee(deps, info, flash_loan_fee, treasury_fee, commission_fee),
+        ExecuteMsg::SetPerformanceFee { fee } => set_performance_fee(deps, env, info, fee),
         ExecuteMsg::AddToWhitelist { contract_addr } => add_to_whitelist(deps, info, contract_addr),
         ExecuteMsg::RemoveFromWhitelist { contract_addr } => {
             remove_from_whitelist(deps, info, contract_addr)
         }
-        ExecuteMsg::FlashLoan { payload } => handle_flashloan(deps, env, info, payload),
+        ExecuteMsg::SetFeeOverride { borrower, fee } => {
+            flashloan::set_fee_override(deps, info, borrower, fee)
+        }
+        ExecuteMsg::SetFeeTiers { tiers } => flashloan::set_fee_tiers(deps, info, tiers),
+        ExecuteMsg::SetGuardian { guardian } => {
+            Ok(pause::set_guardian(deps, &ADMIN, info, guardian)?)
+        }
+        ExecuteMsg::Pause { operation, reason } => {
+            Ok(pause::pause(deps, &ADMIN, info, operation, reason)?)
+        }
+        ExecuteMsg::Unpause { operation } => Ok(pause::unpause(deps, &ADMIN, info, operation)?),
+        ExecuteMsg::FlashLoan { payload } => flashloan::handle_flashloan(deps, env, info, payload),
         ExecuteMsg::UpdateState {
-            anchor_money_market_address,
-            aust_address,
             allow_non_whitelisted,
-        } => update_state(
-            deps,
-            info,
-            anchor_money_market_address,
-            aust_address,
-            allow_non_whitelisted,
-        ),
-        ExecuteMsg::Callback(msg) => _handle_callback(deps, env, info, msg),
-    }
-}
-
-//----------------------------------------------------------------------------------------
-//  PRIVATE FUNCTIONS
-//----------------------------------------------------------------------------------------
-
-fn _handle_callback(deps: DepsMut, env: Env, info: MessageInfo, msg: CallbackMsg) -> VaultResult {
-    // Callback functions can only be called this contract itself
-    if info.sender != env.contract.address {
-        return Err(StableVaultError::NotCallback {});
-    }
-    match msg {
-        CallbackMsg::AfterTrade { loan_fee } => after_trade(deps, env, info, loan_fee),
+        } => update_state(deps, info, allow_non_whitelisted),
+        ExecuteMsg::Callback(msg) => flashloan::_handle_callback(deps, env, info, msg),
     }
 }
 
@@ -204,99 +253,6 @@ fn _handle_callback(deps: DepsMut, env: Env, info: MessageInfo, msg: CallbackMsg
 //  EXECUTE FUNCTION HANDLERS
 //----------------------------------------------------------------------------------------
 
-pub fn handle_flashloan(
-    mut deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    payload: FlashLoanPayload,
-) -> VaultResult {
-    let state = STATE.load(deps.storage)?;
-    let deposit_info = DEPOSIT_INFO.load(deps.storage)?;
-    let fees = FEE.load(deps.storage)?;
-    let whitelisted_contracts = state.whitelisted_contracts;
-    let whitelisted: bool;
-    // Check if requested asset is base token of vault
-    deposit_info.assert(&payload.requested_asset.info)?;
-
-    // Check if sender is whitelisted
-    if !whitelisted_contracts.contains(&deps.api.addr_validate(&info.sender.to_string())?) {
-        // Check if non-whitelisted are allowed to borrow
-        if state.allow_non_whitelisted {
-            whitelisted = false;
-        } else {
-            return Err(StableVaultError::NotWhitelisted {});
-        }
-    } else {
-        whitelisted = true;
-    }
-
-    // Do we have enough funds?
-    let pool_info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
-    let (total_value, stables_available, _) = compute_total_value(&env, deps.as_ref(), &pool_info)?;
-    let requested_asset = payload.requested_asset;
-
-    // Max tax buffer will be 2 transfers of the borrowed assets
-    // Anchor -> Vault -> Caller
-    let tax_buffer = Uint128::from(2u32) * requested_asset.compute_tax(&deps.querier)?
-        + Uint128::from(ROUNDING_ERR_COMPENSATION);
-
-    if total_value < requested_asset.amount + tax_buffer {
-        return Err(StableVaultError::Broke {});
-    }
-    // Init response
-    let mut response = Response::new().add_attribute("Action", "Flashloan");
-
-    // Withdraw funds from Anchor if needed
-    // FEE_BUFFER as buffer for fees and taxes
-    if (requested_asset.amount + tax_buffer) > stables_available {
-        // Attempt to remove some money from anchor
-        let to_withdraw = (requested_asset.amount + tax_buffer) - stables_available;
-        let aust_exchange_rate = query_aust_exchange_rate(
-            env.clone(),
-            deps.as_ref(),
-            state.anchor_money_market_address.to_string(),
-        )?;
-
-        let withdraw_msg = anchor_withdraw_msg(
-            state.aust_address,
-            state.anchor_money_market_address,
-            to_withdraw * aust_exchange_rate.inv().unwrap(),
-        )?;
-        // Add msg to response and update withdrawn value
-        response = response
-            .add_message(withdraw_msg)
-            .add_attribute("Anchor withdrawal", to_withdraw.to_string())
-            .add_attribute("ust_aust_rate", aust_exchange_rate.to_string());
-    }
-
-    // If caller not whitelisted, calculate flashloan fee
-
-    let loan_fee: Uint128 = if whitelisted {
-        Uint128::zero()
-    } else {
-        fees.flash_loan_fee.compute(requested_asset.amount)
-    };
-
-    // Construct transfer of funds msg, tax is accounted for by buffer
-    let loan_msg = into_msg_without_tax(requested_asset, info.sender.clone())?;
-    response = response.add_message(loan_msg);
-
-    // Construct return call with received binary
-    let return_call = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: info.sender.into(),
-        msg: payload.callback,
-        funds: vec![],
-    });
-
-    response = response.add_message(return_call);
-
-    // Sets the current value of the vault and save logs
-    response = response.add_attributes(before_trade(deps.branch(), env.clone())?);
-
-    // Call encapsulate function
-    encapsulate_payload(deps.as_ref(), env, response, loan_fee)
-}
-
 // This function should be called alongside a deposit of UST into the contract.
 pub fn try_provide_liquidity(
     deps: DepsMut,
@@ -305,14 +261,10 @@ pub fn try_provide_liquidity(
     asset: Asset,
 ) -> VaultResult {
     let deposit_info = DEPOSIT_INFO.load(deps.storage)?;
-    let profit = PROFIT.load(deps.storage)?;
-    let state = STATE.load(deps.storage)?;
     let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
-    let denom = deposit_info.clone().get_denom()?;
 
-    if profit.last_balance != Uint128::zero() {
-        return Err(StableVaultError::DepositDuringLoan {});
-    }
+    assert_no_loan_ongoing(&StableVault, deps.storage)?;
+    assert_not_paused(deps.storage, PausableOperation::Deposits)?;
 
     // Init vector for logging
     let mut attrs = vec![];
@@ -331,14 +283,19 @@ pub fn try_provide_liquidity(
     // Get total supply of LP tokens and calculate share
     let total_share = query_supply(&deps.querier, info.liquidity_token.clone())?;
 
-    let share = if total_share == Uint128::zero()
-        || total_deposits_in_ust.checked_sub(deposit)? == Uint128::zero()
-    {
-        // Initial share = collateral amount
-        deposit
-    } else {
-        deposit.multiply_ratio(total_share, total_deposits_in_ust.checked_sub(deposit)?)
-    };
+    let share = compute_deposit_share(
+        deposit,
+        total_deposits_in_ust.checked_sub(deposit)?,
+        total_share,
+    );
+    record_snapshot(
+        deps.storage,
+        &env,
+        total_deposits_in_ust.checked_sub(deposit)?,
+        total_deposits_in_ust,
+        total_share.checked_add(share)?,
+        Uint128::zero(),
+    )?;
 
     // mint LP token to sender
     let msg = CosmosMsg::Wasm(WasmMsg::Execute {
@@ -350,26 +307,19 @@ pub fn try_provide_liquidity(
         funds: vec![],
     });
 
-    let response = Response::new().add_attributes(attrs).add_message(msg);
-
-    // If contract holds more then ANCHOR_DEPOSIT_THRESHOLD [UST] then try deposit to anchor and leave UST_CAP [UST] in contract.
-    if stables_in_contract > info.stable_cap * Decimal::percent(150) {
-        let deposit_amount = stables_in_contract - info.stable_cap;
-        let anchor_deposit = Coin::new(deposit_amount.u128(), denom);
-        let deposit_msg = anchor_deposit_msg(
-            deps.as_ref(),
-            state.anchor_money_market_address,
-            anchor_deposit,
-        )?;
-        return Ok(response.add_message(deposit_msg));
-    };
+    // Deposit the stables held above STABLE_CAP into the yield adapters, leaving STABLE_CAP in contract.
+    let deposit_msgs =
+        deposit_into_adapters(deps.as_ref(), &env, stables_in_contract, info.stable_cap)?;
 
-    Ok(response)
+    Ok(Response::new()
+        .add_attributes(attrs)
+        .add_message(msg)
+        .add_messages(deposit_msgs))
 }
 
 /// Attempt to withdraw deposits. Fees are calculated and deducted in lp tokens.
 /// This allowes the war-chest to accumulate a stake in the vault.
-/// The refund is taken out of Anchor if possible.
+/// The refund is taken out of the yield adapters by redeem priority if possible.
 /// Luna holdings are not eligible for withdrawal.
 pub fn try_withdraw_liquidity(
     deps: DepsMut,
@@ -378,14 +328,11 @@ pub fn try_withdraw_liquidity(
     amount: Uint128,
 ) -> VaultResult {
     let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
-    let profit = PROFIT.load(deps.storage)?;
-    let state = STATE.load(deps.storage)?;
-    let denom = DEPOSIT_INFO.load(deps.storage)?.get_denom()?;
+    let deposit_info = DEPOSIT_INFO.load(deps.storage)?;
     let fee_config = FEE.load(deps.storage)?;
 
-    if profit.last_balance != Uint128::zero() {
-        return Err(StableVaultError::DepositDuringLoan {});
-    }
+    assert_no_loan_ongoing(&StableVault, deps.storage)?;
+    assert_not_paused(deps.storage, PausableOperation::Withdrawals)?;
 
     // Logging var
     let mut attrs = vec![];
@@ -393,70 +340,32 @@ pub fn try_withdraw_liquidity(
     // Calculate share of pool and requested pool value
     let lp_addr = info.liquidity_token.clone();
     let total_share: Uint128 = query_supply(&deps.querier, lp_addr)?;
-    let (total_value, _, uaust_value_in_contract) =
-        compute_total_value(&env, deps.as_ref(), &info)?;
-    // Get treasury fee in LP tokens
-    let treasury_fee = get_treasury_fee(deps.as_ref(), amount)?;
-    // Share with fee deducted.
-    let share_ratio: Decimal = Decimal::from_ratio(amount - treasury_fee, total_share);
-    let mut refund_amount: Uint128 = total_value * share_ratio;
-    attrs.push(("Post-fee received:", refund_amount.to_string()));
-
-    // Init response
-    let mut response = Response::new();
-    // Available aUST
-    let max_aust_amount = query_token_balance(
-        &deps.querier,
-        state.aust_address.clone(),
-        env.contract.address.clone(),
+    let (total_value, _, _) = compute_total_value(&env, deps.as_ref(), &info)?;
+    let (withdrawal, redeem_msgs) =
+        compute_withdrawal(deps.as_ref(), &env, amount, total_value, total_share)?;
+    let treasury_fee = withdrawal.treasury_fee;
+    attrs.push(("Post-fee received:", withdrawal.withdraw_value.to_string()));
+    record_snapshot(
+        deps.storage,
+        &env,
+        total_value,
+        total_value.checked_sub(withdrawal.withdraw_value)?,
+        total_share.checked_sub(amount - treasury_fee)?,
+        Uint128::zero(),
     )?;
-    let mut withdrawn_ust = Asset {
-        info: AssetInfo::NativeToken {
-            denom: denom.clone(),
-        },
-        amount: Uint128::zero(),
-    };
 
-    // If we have aUST, try repay with that
-    if max_aust_amount > Uint128::zero() {
-        let aust_exchange_rate = query_aust_exchange_rate(
-            env,
-            deps.as_ref(),
-            state.anchor_money_market_address.to_string(),
-        )?;
-
-        if uaust_value_in_contract < refund_amount {
-            // Withdraw all aUST left
-            let withdraw_msg = anchor_withdraw_msg(
-                state.aust_address.clone(),
-                state.anchor_money_market_address,
-                max_aust_amount,
-            )?;
-            // Add msg to response and update withdrawn value
-            response = response.add_message(withdraw_msg);
-            withdrawn_ust.amount = uaust_value_in_contract;
-        } else {
-            // Repay user share of aUST
-            let withdraw_amount = refund_amount * aust_exchange_rate.inv().unwrap();
-
-            let withdraw_msg = anchor_withdraw_msg(
-                state.aust_address,
-                state.anchor_money_market_address,
-                withdraw_amount,
-            )?;
-            // Add msg to response and update withdrawn value
-            response = response.add_message(withdraw_msg);
-            withdrawn_ust.amount = refund_amount;
-        };
-        response = response
-            .add_attribute("Max anchor withdrawal", max_aust_amount.to_string())
-            .add_attribute("ust_aust_rate", aust_exchange_rate.to_string());
-
-        // Compute tax on Anchor withdraw tx
-        let withdrawtx_tax = withdrawn_ust.compute_tax(&deps.querier)?;
-        refund_amount -= withdrawtx_tax;
-        attrs.push(("After Anchor withdraw:", refund_amount.to_string()));
-    };
+    // The refund is taken out of the yield adapters first, which is taxed for native base assets
+    let refund_amount = withdrawal
+        .withdraw_value
+        .checked_sub(withdrawal.redeem_tax)?;
+    let response = Response::new().add_messages(redeem_msgs);
+    if !withdrawal.redeemed_from_adapters.is_zero() {
+        attrs.push((
+            "Yield adapters withdrawal:",
+            withdrawal.redeemed_from_adapters.to_string(),
+        ));
+        attrs.push(("After yield adapters withdraw:", refund_amount.to_string()));
+    }
 
     // LP token treasury Asset
     let lp_token_treasury_fee = Asset {
@@ -474,17 +383,12 @@ pub fn try_withdraw_liquidity(
     )?;
     attrs.push(("War chest fee:", treasury_fee.to_string()));
 
-    // Construct refund message
+    // Construct refund message, with the tax deducted for native base assets
     let refund_asset = Asset {
-        info: AssetInfo::NativeToken { denom },
+        info: deposit_info.asset_info,
         amount: refund_amount,
     };
-    let tax_assed = refund_asset.deduct_tax(&deps.querier)?;
-
-    let refund_msg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: sender,
-        amount: vec![tax_assed],
-    });
+    let refund_msg = refund_asset.into_msg(&deps.querier, deps.api.addr_validate(&sender)?)?;
     // LP burn msg
     let burn_msg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: info.liquidity_token.to_string(),
@@ -503,113 +407,64 @@ pub fn try_withdraw_liquidity(
         .add_attributes(attrs))
 }
 
-/// Sends the commission fee which is a function of the profit made by the contract, forwarded by the profit-check contract
-fn send_commissions(deps: Deps, _info: MessageInfo, profit: Uint128) -> VaultResult {
-    let fees = FEE.load(deps.storage)?;
-
-    let commission_amount = fees.commission_fee.compute(profit);
-
-    // Construct commission msg
-    let refund_asset = Asset {
-        info: AssetInfo::NativeToken {
-            denom: "uusd".to_string(),
-        },
-        amount: commission_amount,
-    };
-    let commission_msg = refund_asset.into_msg(&deps.querier, fees.treasury_addr)?;
-
-    Ok(Response::new()
-        .add_attribute("treasury commission:", commission_amount.to_string())
-        .add_message(commission_msg))
-}
-
-// Resets last trade and sets current UST balance of caller
-pub fn before_trade(deps: DepsMut, env: Env) -> StdResult<Vec<(&str, String)>> {
-    let mut conf = PROFIT.load(deps.storage)?;
+//----------------------------------------------------------------------------------------
+//  HELPER FUNCTION HANDLERS
+//----------------------------------------------------------------------------------------
 
-    // last_balance call can not be reset until after the loan.
-    if conf.last_balance != Uint128::zero() {
-        return Err(StdError::generic_err(
-            StableVaultError::Nonzero {}.to_string(),
-        ));
+/// LP tokens minted for a deposit, given the value of the vault and the LP token supply before it.
+/// Shared by the deposits and their simulation
+fn compute_deposit_share(deposit: Uint128, total_value: Uint128, total_share: Uint128) -> Uint128 {
+    if total_share.is_zero() || total_value.is_zero() {
+        // Initial share = collateral amount
+        deposit
+    } else {
+        deposit.multiply_ratio(total_share, total_value)
     }
-
-    conf.last_profit = Uint128::zero();
-
-    // Index 0 = total_value
-    conf.last_balance = total_value(deps.as_ref(), &env)?.0;
-    PROFIT.save(deps.storage, &conf)?;
-
-    Ok(vec![(
-        "value before trade: ",
-        conf.last_balance.to_string(),
-    )])
 }
 
-// Checks if balance increased after the trade
-pub fn after_trade(
-    mut deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    loan_fee: Uint128,
-) -> VaultResult {
-    // Deposit funds into anchor if applicable.
-    let response = try_anchor_deposit(deps.branch(), env.clone())?;
-
-    let mut conf = PROFIT.load(deps.storage)?;
-
-    let balance = total_value(deps.as_ref(), &env)?.0;
-
-    // Check if balance increased with expected fee, otherwise cancel everything
-    if balance < conf.last_balance + loan_fee {
-        return Err(StableVaultError::CancelLosingTrade {});
+/// Itemized withdrawal of the given LP tokens, along with the msgs redeeming the yield adapters.
+/// Shared by the withdrawals and their simulation
+fn compute_withdrawal(
+    deps: Deps,
+    env: &Env,
+    lp_amount: Uint128,
+    total_value: Uint128,
+    total_share: Uint128,
+) -> StdResult<(SimulateWithdrawResponse, Vec<CosmosMsg>)> {
+    let asset_info = DEPOSIT_INFO.load(deps.storage)?.asset_info;
+    // Get treasury fee in LP tokens
+    let treasury_fee = get_treasury_fee(deps, lp_amount)?;
+    // Share with fee deducted.
+    let share_ratio = Decimal::from_ratio(lp_amount.checked_sub(treasury_fee)?, total_share);
+    let withdraw_value = total_value * share_ratio;
+
+    // Try repay with the positions held in the yield adapters, by redeem priority
+    let (redeem_msgs, redeemed) = redeem_from_adapters(deps, env, withdraw_value)?;
+    // Compute tax on the yield adapters withdraw tx
+    let redeem_tax = Asset {
+        info: asset_info.clone(),
+        amount: redeemed,
     }
+    .compute_tax(&deps.querier)?;
 
-    let profit = balance - conf.last_balance;
-
-    conf.last_profit = profit;
-    conf.last_balance = Uint128::zero();
-    PROFIT.save(deps.storage, &conf)?;
-
-    let commission_response = send_commissions(deps.as_ref(), info, profit)?;
-
-    Ok(response
-        // Send commission of profit to Treasury
-        .add_submessages(commission_response.messages)
-        .add_attributes(commission_response.attributes)
-        .add_attribute("value after commission: ", balance.to_string()))
-}
-
-//----------------------------------------------------------------------------------------
-//  HELPER FUNCTION HANDLERS
-//----------------------------------------------------------------------------------------
-
-/// Helper method which encapsulates the requested funds.
-/// This function prevents callers from doing unprofitable actions
-/// with the vault funds and makes sure the funds are returned by
-/// the borrower.
-pub fn encapsulate_payload(
-    _deps: Deps,
-    env: Env,
-    response: Response,
-    loan_fee: Uint128,
-) -> VaultResult {
-    let total_response: Response = Response::new().add_attributes(response.attributes);
-
-    // Callback for after the loan
-    let after_trade = CallbackMsg::AfterTrade { loan_fee }.to_cosmos_msg(&env.contract.address)?;
+    // The refund msg deducts the tax of the transfer to the user
+    let refund_asset = Asset {
+        info: asset_info,
+        amount: withdraw_value.checked_sub(redeem_tax)?,
+    };
+    let transfer_tax = refund_asset.compute_tax(&deps.querier)?;
 
-    Ok(total_response
-        // Add response that:
-        // 1. Withdraws funds from Anchor if needed
-        // 2. Sends funds to the borrower
-        // 3. Calls the borrow contract through the provided callback msg
-        .add_submessages(response.messages)
-        // After borrower actions, deposit the received funds back into
-        // Anchor if applicable
-        // Call profit-check to cancel the borrow if
-        // no profit is made.
-        .add_message(after_trade))
+    Ok((
+        SimulateWithdrawResponse {
+            treasury_fee,
+            withdraw_value,
+            redeemed_from_adapters: redeemed,
+            redeem_tax,
+            transfer_tax,
+            return_amount: refund_asset.amount.checked_sub(transfer_tax)?,
+        },
+        redeem_msgs,
+    ))
 }
 
 /// handler function invoked when the stablecoin-vault contract receives
@@ -623,6 +478,20 @@ pub fn receive_cw20(
 ) -> VaultResult {
     match from_binary(&cw20_msg.msg)? {
         Cw20HookMsg::Swap { .. } => Err(StableVaultError::NoSwapAvailable {}),
+        Cw20HookMsg::ProvideLiquidity {} => {
+            // The sender is the token contract, checked against the deposit info
+            let asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: msg_info.sender.to_string(),
+                },
+                amount: cw20_msg.amount,
+            };
+            let depositor = MessageInfo {
+                sender: deps.api.addr_validate(&cw20_msg.sender)?,
+                funds: vec![],
+            };
+            try_provide_liquidity(deps, env, depositor, asset)
+        }
         Cw20HookMsg::WithdrawLiquidity {} => {
             let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
             if deps.api.addr_validate(&msg_info.sender.to_string())? != info.liquidity_token {
@@ -634,31 +503,70 @@ pub fn receive_cw20(
 }
 
 /// compute total value of deposits in UST and return a tuple with those values.
-/// (total, stable, aust)
+/// (total, stable, yield adapters)
 pub fn compute_total_value(
     env: &Env,
     deps: Deps,
     info: &PoolInfoRaw,
 ) -> StdResult<(Uint128, Uint128, Uint128)> {
-    let state = STATE.load(deps.storage)?;
     let stable_info = info.asset_infos[0].to_normal(deps.api)?;
-    let stable_denom = match stable_info {
-        AssetInfo::Token { .. } => String::default(),
-        AssetInfo::NativeToken { denom } => denom,
-    };
-    let stable_amount = query_balance(&deps.querier, info.contract_addr.clone(), stable_denom)?;
-
-    let aust_info = info.asset_infos[1].to_normal(deps.api)?;
-    let aust_amount = aust_info.query_pool(&deps.querier, deps.api, info.contract_addr.clone())?;
-    let aust_exchange_rate = query_aust_exchange_rate(
-        env.clone(),
-        deps,
-        state.anchor_money_market_address.to_string(),
-    )?;
-    let aust_value_in_ust = aust_exchange_rate * aust_amount;
+    let stable_amount =
+        stable_info.query_pool(&deps.querier, deps.api, info.contract_addr.clone())?;
+
+    let adapters_value_in_ust = adapters_value(deps, env)?;
+
+    let total_deposits_in_ust = stable_amount + adapters_value_in_ust;
+    Ok((total_deposits_in_ust, stable_amount, adapters_value_in_ust))
+}
 
-    let total_deposits_in_ust = stable_amount + aust_value_in_ust;
-    Ok((total_deposits_in_ust, stable_amount, aust_value_in_ust))
+/// Assets held by the vault, the base asset followed by the positions held in the yield adapters
+fn pool_asset_infos(
+    deps: Deps,
+    env: &Env,
+    asset_info: &AssetInfo,
+    yield_adapters: &[YieldAdapterInfo],
+) -> StdResult<Vec<AssetInfoRaw>> {
+    let mut asset_infos = vec![asset_info.to_raw(deps.api)?];
+    for adapter_info in yield_adapters {
+        asset_infos.push(
+            load_adapter(env, &adapter_info.adapter)
+                .position_info()
+                .to_raw(deps.api)?,
+        );
+    }
+    Ok(asset_infos)
+}
+
+/// Records the vault value and LP token supply, used to track the share price over time.
+/// The PnL is measured from the value before the event, i.e. before a deposit or withdrawal.
+pub fn record_snapshot(
+    storage: &mut dyn Storage,
+    env: &Env,
+    value_before: Uint128,
+    total_value: Uint128,
+    total_share: Uint128,
+    profit: Uint128,
+) -> StdResult<()> {
+    let mut profit_check = PROFIT.load(storage)?;
+    let pnl = profit_check.record_pnl(value_before, total_value)?;
+    PROFIT.save(storage, &profit_check)?;
+
+    let id = LAST_SNAPSHOT_ID.may_load(storage)?.unwrap_or_default() + 1;
+    LAST_SNAPSHOT_ID.save(storage, &id)?;
+    let timestamp = env.block.time.seconds();
+    SNAPSHOT_IDS.save(storage, U64Key::new(timestamp), &id)?;
+    SNAPSHOTS.save(
+        storage,
+        U64Key::new(id),
+        &VaultSnapshot {
+            id,
+            timestamp,
+            total_value,
+            total_share,
+            profit,
+            pnl,
+        },
+    )
 }
 
 pub fn get_treasury_fee(deps: Deps, amount: Uint128) -> StdResult<Uint128> {
@@ -669,45 +577,24 @@ pub fn get_treasury_fee(deps: Deps, amount: Uint128) -> StdResult<Uint128> {
 
 pub fn get_withdraw_fee(deps: Deps, amount: Uint128) -> StdResult<Uint128> {
     let treasury_fee = get_treasury_fee(deps, amount)?;
-    let anchor_withdraw_fee = compute_tax(
-        deps,
-        &Coin::new((amount - treasury_fee).u128(), String::from("uusd")),
-    )?;
-    let stable_transfer_fee = compute_tax(
-        deps,
-        &Coin::new(
-            (amount - treasury_fee - anchor_withdraw_fee).u128(),
-            String::from("uusd"),
-        ),
-    )?;
-    // Two transfers (anchor -> vault -> user) so ~2x tax.
-    Ok(treasury_fee + anchor_withdraw_fee + stable_transfer_fee)
-}
-
-//----------------------------------------------------------------------------------------
-//  CALLBACK FUNCTION HANDLERS
-//----------------------------------------------------------------------------------------
-
-fn try_anchor_deposit(deps: DepsMut, env: Env) -> VaultResult {
-    let state = STATE.load(deps.storage)?;
-    let stable_denom = DEPOSIT_INFO.load(deps.storage)?.get_denom()?;
-    let stables_in_contract =
-        query_balance(&deps.querier, env.contract.address, stable_denom.clone())?;
-    let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
-
-    // If contract holds more then ANCHOR_DEPOSIT_THRESHOLD [UST] then try deposit to anchor and leave UST_CAP [UST] in contract.
-    if stables_in_contract > info.stable_cap * Decimal::percent(150) {
-        let deposit_amount = stables_in_contract - info.stable_cap;
-        let anchor_deposit = Coin::new(deposit_amount.u128(), stable_denom);
-        let deposit_msg = anchor_deposit_msg(
-            deps.as_ref(),
-            state.anchor_money_market_address,
-            anchor_deposit,
-        )?;
-
-        return Ok(Response::new().add_message(deposit_msg));
+    let asset_info = DEPOSIT_INFO.load(deps.storage)?.asset_info;
+    // No tax is charged on cw20 base assets
+    let adapter_withdraw_fee = if !load_adapters(deps.storage)?.is_empty() {
+        Asset {
+            info: asset_info.clone(),
+            amount: amount - treasury_fee,
+        }
+        .compute_tax(&deps.querier)?
+    } else {
+        Uint128::zero()
     };
-    Ok(Response::default())
+    let stable_transfer_fee = Asset {
+        info: asset_info,
+        amount: amount - treasury_fee - adapter_withdraw_fee,
+    }
+    .compute_tax(&deps.querier)?;
+    // Two transfers (yield adapter -> vault -> user) so ~2x tax, one without yield adapters.
+    Ok(treasury_fee + adapter_withdraw_fee + stable_transfer_fee)
 }
 
 /// This just stores the result for future query
@@ -739,23 +626,12 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
 pub fn update_state(
     deps: DepsMut,
     info: MessageInfo,
-    anchor_money_market_address: Option<String>,
-    aust_address: Option<String>,
     allow_non_whitelisted: Option<bool>,
 ) -> VaultResult {
     // Only the admin should be able to call this
     ADMIN.assert_admin(deps.as_ref(), &info.sender)?;
 
     let mut state = STATE.load(deps.storage)?;
-    let api = deps.api;
-
-    if let Some(anchor_money_market_address) = anchor_money_market_address {
-        state.anchor_money_market_address = api.addr_validate(&anchor_money_market_address)?;
-    }
-
-    if let Some(aust_address) = aust_address {
-        state.aust_address = api.addr_validate(&aust_address)?;
-    }
 
     if let Some(allow_non_whitelisted) = allow_non_whitelisted {
         state.allow_non_whitelisted = allow_non_whitelisted;
@@ -765,6 +641,44 @@ pub fn update_state(
     Ok(Response::new().add_attribute("Update:", "Successful"))
 }
 
+pub fn set_yield_adapters(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    adapters: Vec<YieldAdapterInfo>,
+) -> VaultResult {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let deposit_info = DEPOSIT_INFO.load(deps.storage)?;
+    let adapters = validate_adapters(deps.api, &deposit_info.asset_info, adapters)?;
+
+    // Removed adapters would no longer be accounted for in the vault value
+    for previous_adapter in load_adapters(deps.storage)? {
+        if adapters
+            .iter()
+            .any(|adapter_info| adapter_info.adapter == previous_adapter.adapter)
+        {
+            continue;
+        }
+        let value = load_adapter(&env, &previous_adapter.adapter).value(deps.as_ref(), &env)?;
+        if !value.is_zero() {
+            return Err(StableVaultError::AdapterNotEmpty {});
+        }
+    }
+    YIELD_ADAPTERS.save(deps.storage, &adapters)?;
+
+    // Keep the pool assets in sync with the yield adapters
+    let mut pool_info = POOL_INFO.load(deps.storage)?;
+    pool_info.asset_infos =
+        pool_asset_infos(deps.as_ref(), &env, &deposit_info.asset_info, &adapters)?;
+    POOL_INFO.save(deps.storage, &pool_info)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_yield_adapters")
+        .add_attribute("adapters", adapters.len().to_string()))
+}
+
 pub fn set_stable_cap(deps: DepsMut, msg_info: MessageInfo, stable_cap: Uint128) -> VaultResult {
     // Only the admin should be able to call this
     ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
@@ -863,6 +777,32 @@ pub fn set_fee(
     Ok(Response::default())
 }
 
+/// Sets the performance fee, or disables it if none. The gains made before are not charged
+pub fn set_performance_fee(
+    deps: DepsMut,
+    env: Env,
+    msg_info: MessageInfo,
+    fee: Option<Fee>,
+) -> VaultResult {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+    let fee = fee.map(check_fee).transpose()?;
+
+    let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+    let (total_value, _, _) = compute_total_value(&env, deps.as_ref(), &info)?;
+    let total_share = query_supply(&deps.querier, info.liquidity_token)?;
+    let share_price = if total_share.is_zero() {
+        Decimal::one()
+    } else {
+        Decimal::from_ratio(total_value, total_share)
+    };
+    performance_fee::set_performance_fee(deps.storage, fee.clone(), share_price)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_performance_fee")
+        .add_attribute("fee", format!("{:?}", fee)))
+}
+
 /// Checks that the given [Fee] is valid, i.e. it's lower than 100%
 fn check_fee(fee: Fee) -> Result<Fee, StableVaultError> {
     if fee.share >= Decimal::percent(100) {
@@ -882,12 +822,36 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::PoolState {} => to_binary(&try_query_pool_state(env, deps)?),
         QueryMsg::State {} => to_binary(&try_query_state(deps)?),
         QueryMsg::Fees {} => to_binary(&query_fees(deps)?),
+        QueryMsg::PerformanceFee {} => {
+            to_binary(&performance_fee::query_performance_fee(deps.storage)?)
+        }
         QueryMsg::VaultValue {} => to_binary(&query_total_value(env, deps)?),
         QueryMsg::EstimateWithdrawFee { amount } => {
             to_binary(&estimate_withdraw_fee(deps, amount)?)
         }
+        QueryMsg::SimulateDeposit { amount } => {
+            to_binary(&query_simulate_deposit(deps, env, amount)?)
+        }
+        QueryMsg::SimulateWithdraw { lp_amount } => {
+            to_binary(&query_simulate_withdraw(deps, env, lp_amount)?)
+        }
         QueryMsg::LastBalance {} => to_binary(&try_query_last_balance(deps)?),
         QueryMsg::LastProfit {} => to_binary(&try_query_last_profit(deps)?),
+        QueryMsg::Pnl {} => to_binary(&query_pnl(deps)?),
+        QueryMsg::YieldAdapters {} => to_binary(&query_yield_adapters(deps, env)?),
+        QueryMsg::SharePrice { timestamp } => to_binary(&query_share_price(deps, env, timestamp)?),
+        QueryMsg::Apr { window } => to_binary(&query_apr(deps, env, window)?),
+        QueryMsg::Snapshots { start_after, limit } => {
+            to_binary(&query_snapshots(deps, start_after, limit)?)
+        }
+        QueryMsg::EffectiveFee { borrower, amount } => {
+            to_binary(&flashloan::query_effective_fee(deps, borrower, amount)?)
+        }
+        QueryMsg::FeeTiers {} => to_binary(&query_fee_tiers(deps.storage)?),
+        QueryMsg::BorrowerStats { borrower } => {
+            to_binary(&flashloan::query_borrower_stats(deps, borrower)?)
+        }
+        QueryMsg::PauseInfo {} => to_binary(&pause::query_pause_info(deps)?),
     }
 }
 
@@ -904,13 +868,44 @@ pub fn estimate_withdraw_fee(
 ) -> StdResult<EstimateWithdrawFeeResponse> {
     let fee = get_withdraw_fee(deps, amount)?;
     Ok(EstimateWithdrawFeeResponse {
-        fee: vec![Coin {
-            denom: DEPOSIT_INFO.load(deps.storage)?.get_denom()?,
+        fee: vec![Asset {
+            info: DEPOSIT_INFO.load(deps.storage)?.asset_info,
             amount: fee,
         }],
     })
 }
 
+pub fn query_simulate_deposit(
+    deps: Deps,
+    env: Env,
+    amount: Uint128,
+) -> StdResult<SimulateDepositResponse> {
+    let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+    let (total_value, _, _) = compute_total_value(&env, deps, &info)?;
+    let total_share = query_supply(&deps.querier, info.liquidity_token)?;
+    Ok(SimulateDepositResponse {
+        lp_amount: compute_deposit_share(amount, total_value, total_share),
+        total_value,
+        total_share,
+    })
+}
+
+pub fn query_simulate_withdraw(
+    deps: Deps,
+    env: Env,
+    lp_amount: Uint128,
+) -> StdResult<SimulateWithdrawResponse> {
+    let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+    let total_share = query_supply(&deps.querier, info.liquidity_token.clone())?;
+    if lp_amount > total_share || total_share.is_zero() {
+        return Err(StdError::generic_err(
+            "The amount exceeds the supply of the vault LP token",
+        ));
+    }
+    let (total_value, _, _) = compute_total_value(&env, deps, &info)?;
+    Ok(compute_withdrawal(deps, &env, lp_amount, total_value, total_share)?.0)
+}
+
 pub fn try_query_config(deps: Deps) -> StdResult<PoolInfo> {
     let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
 
@@ -923,7 +918,7 @@ pub fn try_query_state(deps: Deps) -> StdResult<State> {
 
 pub fn try_query_pool_state(env: Env, deps: Deps) -> StdResult<PoolResponse> {
     let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
-    let assets: [Asset; 2] = info.query_pools(deps, info.contract_addr.clone())?;
+    let assets: Vec<Asset> = info.query_pools(deps, info.contract_addr.clone())?;
     let total_share: Uint128 = query_supply(&deps.querier, info.liquidity_token.clone())?;
 
     let (total_value_in_ust, _, _) = compute_total_value(&env, deps, &info)?;
@@ -947,6 +942,10 @@ pub fn query_total_value(env: Env, deps: Deps) -> StdResult<ValueResponse> {
     Ok(ValueResponse { total_ust_value })
 }
 
+pub fn query_pnl(deps: Deps) -> StdResult<PnlResponse> {
+    Ok(PROFIT.load(deps.storage)?.into())
+}
+
 pub fn try_query_last_profit(deps: Deps) -> StdResult<LastProfitResponse> {
     let conf = PROFIT.load(deps.storage)?;
     Ok(LastProfitResponse {
@@ -960,3 +959,87 @@ pub fn try_query_last_balance(deps: Deps) -> StdResult<LastBalanceResponse> {
         last_balance: conf.last_balance,
     })
 }
+
+pub fn query_yield_adapters(deps: Deps, env: Env) -> StdResult<YieldAdaptersResponse> {
+    let adapters = load_adapters(deps.storage)?
+        .into_iter()
+        .map(|adapter_info| {
+            let adapter = load_adapter(&env, &adapter_info.adapter);
+            Ok(YieldAdapterResponse {
+                value: adapter.value(deps, &env)?,
+                exchange_rate: adapter.exchange_rate(deps, &env)?,
+                adapter: adapter_info.adapter,
+                weight: adapter_info.weight,
+                redeem_priority: adapter_info.redeem_priority,
+                deposit_threshold: adapter_info.deposit_threshold,
+            })
+        })
+        .collect::<StdResult<Vec<YieldAdapterResponse>>>()?;
+    Ok(YieldAdaptersResponse { adapters })
+}
+
+pub fn query_share_price(
+    deps: Deps,
+    env: Env,
+    timestamp: Option<u64>,
+) -> StdResult<SharePriceResponse> {
+    let snapshot = snapshot_at(
+        deps.storage,
+        timestamp.unwrap_or_else(|| env.block.time.seconds()),
+    )?;
+    Ok(SharePriceResponse {
+        share_price: snapshot.share_price(),
+        snapshot,
+    })
+}
+
+pub fn query_apr(deps: Deps, env: Env, window: u64) -> StdResult<AprResponse> {
+    let now = env.block.time.seconds();
+    let start = snapshot_at(deps.storage, now.saturating_sub(window))?;
+    let end = snapshot_at(deps.storage, now)?;
+    Ok(AprResponse {
+        apr: compute_apr(&start, &end),
+        start,
+        end,
+    })
+}
+
+pub fn query_snapshots(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SnapshotsResponse> {
+    let limit = limit
+        .unwrap_or(DEFAULT_SNAPSHOTS_LIMIT)
+        .min(MAX_SNAPSHOTS_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive_int);
+    let snapshots = SNAPSHOTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect::<StdResult<Vec<VaultSnapshot>>>()?;
+
+    Ok(SnapshotsResponse { snapshots })
+}
+
+/// Latest snapshot recorded at or before the given timestamp, or the earliest one if they were
+/// all recorded after it
+fn snapshot_at(storage: &dyn Storage, timestamp: u64) -> StdResult<VaultSnapshot> {
+    let snapshot = match SNAPSHOT_IDS
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive_int(timestamp)),
+            Order::Descending,
+        )
+        .next()
+    {
+        Some(item) => SNAPSHOTS.may_load(storage, U64Key::new(item?.1))?,
+        None => SNAPSHOTS
+            .range(storage, None, None, Order::Ascending)
+            .next()
+            .transpose()?
+            .map(|(_, snapshot)| snapshot),
+    };
+    snapshot.ok_or_else(|| StdError::generic_err("No snapshot of the vault was recorded yet"))
+}
@@ -3,6 +3,9 @@ use thiserror::Error;
 use cosmwasm_std::{OverflowError, StdError};
 use cw_controllers::AdminError;
 
+use white_whale::vault::flashloan::FlashLoanError;
+use white_whale::vault::pause::{PausableOperation, PauseError};
+
 #[derive(Error, Debug, PartialEq)]
 pub enum StableVaultError {
     #[error("{0}")]
@@ -38,6 +41,9 @@ pub enum StableVaultError {
     #[error("The provided fee is invalid")]
     InvalidFee {},
 
+    #[error("The fee tiers need strictly ascending minimum loans.")]
+    InvalidFeeTiers {},
+
     #[error("The requesting contract is not whitelisted.")]
     NotWhitelisted {},
 
@@ -55,6 +61,54 @@ pub enum StableVaultError {
 
     #[error("Last balance is non-zero, you can only call this function once.")]
     Nonzero {},
+
+    #[error("The weights of the yield adapters must add up to 1.")]
+    InvalidAdapterWeights {},
+
+    #[error("The yield adapter is set more than once.")]
+    DuplicatedAdapter {},
+
+    #[error("The deposit threshold of a yield adapter can't be lower than 1.")]
+    InvalidDepositThreshold {},
+
+    #[error("The yield adapter doesn't support the base asset of the vault.")]
+    UnsupportedAdapterAsset {},
+
+    #[error("The vault still holds a position in the yield adapter being removed.")]
+    AdapterNotEmpty {},
+
+    #[error("The vault {operation} are paused: {reason}")]
+    Paused {
+        operation: PausableOperation,
+        reason: String,
+    },
+}
+
+impl From<FlashLoanError> for StableVaultError {
+    fn from(err: FlashLoanError) -> Self {
+        match err {
+            FlashLoanError::Std(err) => Self::Std(err),
+            FlashLoanError::NotWhitelisted {} => Self::NotWhitelisted {},
+            FlashLoanError::Broke {} => Self::Broke {},
+            FlashLoanError::Nonzero {} => Self::Nonzero {},
+            FlashLoanError::CancelLosingTrade {} => Self::CancelLosingTrade {},
+            FlashLoanError::NotCallback {} => Self::NotCallback {},
+            FlashLoanError::DepositDuringLoan {} => Self::DepositDuringLoan {},
+            FlashLoanError::InvalidFee {} => Self::InvalidFee {},
+            FlashLoanError::InvalidFeeTiers {} => Self::InvalidFeeTiers {},
+        }
+    }
+}
+
+impl From<PauseError> for StableVaultError {
+    fn from(err: PauseError) -> Self {
+        match err {
+            PauseError::Std(err) => Self::Std(err),
+            PauseError::Admin(err) => Self::Admin(err),
+            PauseError::Unauthorized {} => Self::Unauthorized {},
+            PauseError::Paused { operation, reason } => Self::Paused { operation, reason },
+        }
+    }
 }
 
 impl From<semver::Error> for StableVaultError {
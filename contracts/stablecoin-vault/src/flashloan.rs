@@ -0,0 +1,219 @@
+use cosmwasm_std::{
+    CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Storage, Uint128,
+};
+use terraswap::asset::AssetInfo;
+use terraswap::querier::query_supply;
+
+use white_whale::ust_vault::msg::{CallbackMsg, FlashLoanPayload};
+use white_whale::vault::fees::{self, BorrowerStats, EffectiveFeeResponse, FeeTier, LoanFee};
+use white_whale::vault::flashloan::{
+    self as engine, FlashLoanConfig, FlashLoanVault, ProfitCheck, TradeSettlement,
+};
+use white_whale::vault::pause::{assert_not_paused, PausableOperation};
+use white_whale::vault::performance_fee::{charge_performance_fee, performance_fee_msg};
+
+use crate::adapters::{deposit_into_adapters, redeem_from_adapters};
+use crate::contract::{compute_total_value, record_snapshot, total_value, VaultResult};
+use crate::error::StableVaultError;
+use crate::pool_info::PoolInfoRaw;
+use crate::state::{ADMIN, DEPOSIT_INFO, FEE, POOL_INFO, PROFIT, STATE};
+
+/// The stablecoin vault lends its base asset, redeeming it from the yield adapters when needed
+pub struct StableVault;
+
+impl FlashLoanVault for StableVault {
+    type Error = StableVaultError;
+
+    fn config(&self, deps: Deps) -> StdResult<FlashLoanConfig> {
+        let state = STATE.load(deps.storage)?;
+        let fees = FEE.load(deps.storage)?;
+        Ok(FlashLoanConfig {
+            whitelisted_contracts: state.whitelisted_contracts,
+            allow_non_whitelisted: state.allow_non_whitelisted,
+            flash_loan_fee: fees.flash_loan_fee,
+            commission_fee: fees.commission_fee,
+            treasury_addr: fees.treasury_addr,
+        })
+    }
+
+    fn load_profit_check(&self, storage: &dyn Storage) -> StdResult<ProfitCheck> {
+        PROFIT.load(storage)
+    }
+
+    fn save_profit_check(
+        &self,
+        storage: &mut dyn Storage,
+        profit_check: &ProfitCheck,
+    ) -> StdResult<()> {
+        PROFIT.save(storage, profit_check)
+    }
+
+    fn base_asset_info(&self, deps: Deps) -> Result<AssetInfo, StableVaultError> {
+        Ok(DEPOSIT_INFO.load(deps.storage)?.asset_info)
+    }
+
+    fn assert_loan_asset(
+        &self,
+        deps: Deps,
+        asset_info: &AssetInfo,
+    ) -> Result<(), StableVaultError> {
+        DEPOSIT_INFO.load(deps.storage)?.assert(asset_info)?;
+        Ok(())
+    }
+
+    /// Stables in the contract plus the value held by the yield adapters
+    fn total_value(&self, deps: Deps, env: &Env) -> Result<Uint128, StableVaultError> {
+        Ok(total_value(deps, env)?.0)
+    }
+
+    fn prepare_loan(
+        &self,
+        deps: Deps,
+        env: &Env,
+        amount: Uint128,
+        tax_buffer: Uint128,
+    ) -> VaultResult {
+        let pool_info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+        let (_, stables_available, _) = compute_total_value(env, deps, &pool_info)?;
+        let mut response = Response::new();
+
+        // Redeem funds from the yield adapters if needed, by redeem priority
+        if amount + tax_buffer > stables_available {
+            let to_withdraw = amount + tax_buffer - stables_available;
+            let (redeem_msgs, redeemed) = redeem_from_adapters(deps, env, to_withdraw)?;
+            // Add msgs to response and log the redeemed value
+            response = response
+                .add_messages(redeem_msgs)
+                .add_attribute("Yield adapters withdrawal", redeemed.to_string());
+        }
+        Ok(response)
+    }
+
+    fn settle_trade(&self, deps: DepsMut, env: &Env, settlement: &TradeSettlement) -> VaultResult {
+        let asset_info = DEPOSIT_INFO.load(deps.storage)?.asset_info;
+        let stables_in_contract =
+            asset_info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+        let info: PoolInfoRaw = POOL_INFO.load(deps.storage)?;
+
+        // Deposit the stables held above STABLE_CAP into the yield adapters, leaving STABLE_CAP in contract.
+        // The refund of the borrower stays liquid
+        let deposit_msgs = deposit_into_adapters(
+            deps.as_ref(),
+            env,
+            stables_in_contract.checked_sub(settlement.refund)?,
+            info.stable_cap,
+        )?;
+
+        // Commission on the profit and refund leave the vault
+        let total_value = settlement
+            .total_value
+            .checked_sub(settlement.commission + settlement.refund)?;
+        let total_share = query_supply(&deps.querier, info.liquidity_token.clone())?;
+        let performance_fee = charge_performance_fee(deps.storage, total_value, total_share)?;
+        // the profit of the trade is part of the PnL since the last event
+        record_snapshot(
+            deps.storage,
+            env,
+            total_value,
+            total_value,
+            total_share.checked_add(performance_fee)?,
+            settlement.profit,
+        )?;
+
+        let mut response = Response::new().add_messages(deposit_msgs);
+        if !performance_fee.is_zero() {
+            let treasury_addr = FEE.load(deps.storage)?.treasury_addr;
+            response = response
+                .add_message(performance_fee_msg(
+                    &info.liquidity_token,
+                    &treasury_addr,
+                    performance_fee,
+                )?)
+                .add_attribute("performance_fee", performance_fee.to_string());
+        }
+        Ok(response)
+    }
+
+    fn after_trade_msg(&self, env: &Env, loan_fee: Uint128) -> StdResult<CosmosMsg> {
+        CallbackMsg::AfterTrade { loan_fee }.to_cosmos_msg(&env.contract.address)
+    }
+}
+
+pub fn handle_flashloan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payload: FlashLoanPayload,
+) -> VaultResult {
+    assert_not_paused(deps.storage, PausableOperation::FlashLoans)?;
+    engine::handle_flashloan(
+        &StableVault,
+        deps,
+        env,
+        info,
+        payload.requested_asset,
+        payload.callback,
+    )
+}
+
+/// Sets the flashloan fee of the borrower, or removes its override if none
+pub fn set_fee_override(
+    deps: DepsMut,
+    msg_info: MessageInfo,
+    borrower: String,
+    fee: Option<LoanFee>,
+) -> VaultResult {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let borrower = deps.api.addr_validate(&borrower)?;
+    fees::set_fee_override(deps.storage, &borrower, fee.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_fee_override")
+        .add_attribute("borrower", borrower)
+        .add_attribute("fee", format!("{:?}", fee)))
+}
+
+/// Sets the flashloan fee tiers of the non-whitelisted borrowers
+pub fn set_fee_tiers(deps: DepsMut, msg_info: MessageInfo, tiers: Vec<FeeTier>) -> VaultResult {
+    // Only the admin should be able to call this
+    ADMIN.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let tier_count = tiers.len();
+    fees::set_fee_tiers(deps.storage, tiers)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_fee_tiers")
+        .add_attribute("tiers", tier_count.to_string()))
+}
+
+pub fn query_effective_fee(
+    deps: Deps,
+    borrower: String,
+    amount: Uint128,
+) -> StdResult<EffectiveFeeResponse> {
+    let borrower = deps.api.addr_validate(&borrower)?;
+    fees::query_effective_fee(deps.storage, &StableVault.config(deps)?, &borrower, amount)
+}
+
+pub fn query_borrower_stats(deps: Deps, borrower: String) -> StdResult<BorrowerStats> {
+    let borrower = deps.api.addr_validate(&borrower)?;
+    fees::load_borrower_stats(deps.storage, &borrower)
+}
+
+/// Handles the callback after using a flashloan
+pub fn _handle_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CallbackMsg,
+) -> VaultResult {
+    // Callback functions can only be called this contract itself
+    engine::assert_callback(&env, &info)?;
+    match msg {
+        CallbackMsg::AfterTrade { loan_fee } => {
+            engine::after_trade(&StableVault, deps, env, loan_fee)
+        }
+    }
+}
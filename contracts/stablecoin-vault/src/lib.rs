@@ -1,5 +1,7 @@
+pub mod adapters;
 pub mod contract;
 pub mod error;
+pub mod flashloan;
 pub mod pool_info;
 pub mod querier;
 pub mod response;
@@ -5,7 +5,7 @@ use terraswap::asset::{Asset, AssetInfo, AssetInfoRaw};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PoolInfo {
-    pub asset_infos: [AssetInfo; 2],
+    pub asset_infos: Vec<AssetInfo>,
     pub contract_addr: Addr,
     pub liquidity_token: Addr,
     pub stable_cap: Uint128,
@@ -13,7 +13,8 @@ pub struct PoolInfo {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PoolInfoRaw {
-    pub asset_infos: [AssetInfoRaw; 2],
+    // Base asset first, followed by aUST when Anchor is used
+    pub asset_infos: Vec<AssetInfoRaw>,
     pub contract_addr: Addr,
     pub liquidity_token: Addr,
     pub stable_cap: Uint128,
@@ -25,25 +26,24 @@ impl PoolInfoRaw {
             liquidity_token: self.liquidity_token.clone(),
             stable_cap: self.stable_cap,
             contract_addr: self.contract_addr.clone(),
-            asset_infos: [
-                self.asset_infos[0].to_normal(deps.api)?,
-                self.asset_infos[1].to_normal(deps.api)?,
-            ],
+            asset_infos: self
+                .asset_infos
+                .iter()
+                .map(|asset_info| asset_info.to_normal(deps.api))
+                .collect::<StdResult<Vec<AssetInfo>>>()?,
         })
     }
 
-    pub fn query_pools(&self, deps: Deps, contract_addr: Addr) -> StdResult<[Asset; 2]> {
-        let info_0: AssetInfo = self.asset_infos[0].to_normal(deps.api)?;
-        let info_1: AssetInfo = self.asset_infos[1].to_normal(deps.api)?;
-        Ok([
-            Asset {
-                amount: info_0.query_pool(&deps.querier, deps.api, contract_addr.clone())?,
-                info: info_0,
-            },
-            Asset {
-                amount: info_1.query_pool(&deps.querier, deps.api, contract_addr)?,
-                info: info_1,
-            },
-        ])
+    pub fn query_pools(&self, deps: Deps, contract_addr: Addr) -> StdResult<Vec<Asset>> {
+        self.asset_infos
+            .iter()
+            .map(|asset_info| {
+                let info = asset_info.to_normal(deps.api)?;
+                Ok(Asset {
+                    amount: info.query_pool(&deps.querier, deps.api, contract_addr.clone())?,
+                    info,
+                })
+            })
+            .collect()
     }
 }
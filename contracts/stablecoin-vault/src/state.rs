@@ -1,12 +1,15 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::Addr;
 use cw_controllers::Admin;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map, U64Key};
 
 use white_whale::deposit_info::DepositInfo;
 use white_whale::fee::VaultFee;
+use white_whale::snapshot::VaultSnapshot;
+use white_whale::ust_vault::msg::YieldAdapterInfo;
+use white_whale::vault::flashloan::ProfitCheck;
 
 use crate::pool_info::PoolInfoRaw;
 
@@ -14,21 +17,28 @@ use crate::pool_info::PoolInfoRaw;
 // The Stablecoin-vault State contains configuration options for the vault including
 // the address of the pool to trade in as well as some other addresses
 pub struct State {
-    pub anchor_money_market_address: Addr,
-    pub aust_address: Addr,
     pub whitelisted_contracts: Vec<Addr>,
     pub allow_non_whitelisted: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct ProfitCheck {
-    pub last_balance: Uint128,
-    pub last_profit: Uint128,
+// State of the vaults instantiated before the yield adapters, with Anchor as only yield backend.
+// Migrated to the yield adapters on contract migration
+pub struct LegacyState {
+    pub anchor_money_market_address: Option<Addr>,
+    pub aust_address: Option<Addr>,
+    pub whitelisted_contracts: Vec<Addr>,
+    pub allow_non_whitelisted: bool,
 }
 
 pub const PROFIT: Item<ProfitCheck> = Item::new("\u{0}{6}profit");
 pub const ADMIN: Admin = Admin::new("admin");
 pub const STATE: Item<State> = Item::new("\u{0}{5}state");
+pub const LEGACY_STATE: Item<LegacyState> = Item::new("\u{0}{5}state");
 pub const POOL_INFO: Item<PoolInfoRaw> = Item::new("\u{0}{4}pool");
 pub const DEPOSIT_INFO: Item<DepositInfo> = Item::new("\u{0}{7}deposit");
 pub const FEE: Item<VaultFee> = Item::new("\u{0}{3}fee");
+pub const YIELD_ADAPTERS: Item<Vec<YieldAdapterInfo>> = Item::new("yield_adapters");
+pub const SNAPSHOTS: Map<U64Key, VaultSnapshot> = Map::new("snapshots");
+pub const SNAPSHOT_IDS: Map<U64Key, u64> = Map::new("snapshot_ids");
+pub const LAST_SNAPSHOT_ID: Item<u64> = Item::new("last_snapshot_id");
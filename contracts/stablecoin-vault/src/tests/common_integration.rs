@@ -5,6 +5,7 @@ use terra_mocks::TerraMockQuerier;
 use terra_multi_test::{App, BankKeeper, Contract, ContractWrapper};
 use terraswap::asset::AssetInfo;
 use white_whale::ust_vault::msg::InstantiateMsg as VaultInstantiateMsg;
+use white_whale::ust_vault::msg::{YieldAdapterInfo, YieldAdapterKind};
 
 // Custom Vault Instant msg func which takes code ID
 pub fn instantiate_msg(
@@ -14,8 +15,15 @@ pub fn instantiate_msg(
     aust_address: String,
 ) -> VaultInstantiateMsg {
     VaultInstantiateMsg {
-        anchor_money_market_address: anchor_addr,
-        aust_address,
+        yield_adapters: vec![YieldAdapterInfo {
+            adapter: YieldAdapterKind::Anchor {
+                money_market_address: anchor_addr,
+                aust_address,
+            },
+            weight: Decimal::one(),
+            redeem_priority: 0u32,
+            deposit_threshold: Decimal::percent(150),
+        }],
         treasury_addr: war_chest,
         asset_info: AssetInfo::NativeToken {
             denom: "uusd".to_string(),
@@ -13,7 +13,7 @@ use white_whale::ust_vault::msg::*;
 
 use crate::tests::common::{ARB_CONTRACT, TEST_CREATOR};
 
-use crate::tests::instantiate::mock_instantiate;
+use crate::tests::instantiate::{anchor_adapter, mock_instantiate};
 use crate::tests::mock_querier::mock_dependencies;
 
 const INSTANTIATE_REPLY_ID: u8 = 1u8;
@@ -33,8 +33,6 @@ fn successful_initialization() {
     assert_eq!(
         state,
         State {
-            anchor_money_market_address: deps.api.addr_validate("test_mm").unwrap(),
-            aust_address: deps.api.addr_validate("test_aust").unwrap(),
             whitelisted_contracts: vec![],
             allow_non_whitelisted: false
         }
@@ -151,8 +149,7 @@ fn test_init_with_non_default_vault_lp_token() {
     let custom_token_symbol = String::from("MyLP");
 
     let msg = InstantiateMsg {
-        anchor_money_market_address: "test_mm".to_string(),
-        aust_address: "test_aust".to_string(),
+        yield_adapters: vec![anchor_adapter()],
         treasury_addr: "treasury".to_string(),
         asset_info: AssetInfo::NativeToken {
             denom: "uusd".to_string(),
@@ -0,0 +1,112 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, Decimal, Uint128};
+
+use white_whale::fee::Fee;
+use white_whale::ust_vault::msg::{ExecuteMsg, VaultQueryMsg as QueryMsg};
+use white_whale::vault::fees::{EffectiveFeeResponse, FeeTier, LoanFee};
+
+use crate::contract::{execute, query};
+use crate::error::StableVaultError;
+use crate::tests::common::{ARB_CONTRACT, TEST_CREATOR};
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::mock_dependencies;
+
+fn flat(permille: u64) -> LoanFee {
+    LoanFee::Flat {
+        fee: Fee {
+            share: Decimal::permille(permille),
+        },
+    }
+}
+
+#[test]
+fn unsuccessful_set_fee_tiers_unauthorized() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetFeeTiers {
+        tiers: vec![FeeTier {
+            min_loans: 10,
+            fee: flat(1),
+        }],
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg,
+    );
+    match res {
+        Err(StableVaultError::Admin(_)) => (),
+        _ => panic!("Must return StableVaultError::Admin"),
+    }
+}
+
+#[test]
+fn unsuccessful_set_fee_tiers_not_ascending() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetFeeTiers {
+        tiers: vec![
+            FeeTier {
+                min_loans: 10,
+                fee: flat(3),
+            },
+            FeeTier {
+                min_loans: 5,
+                fee: flat(1),
+            },
+        ],
+    };
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(StableVaultError::InvalidFeeTiers {}) => (),
+        _ => panic!("Must return StableVaultError::InvalidFeeTiers"),
+    }
+}
+
+#[test]
+fn successful_fee_override() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // Non-whitelisted borrowers can't borrow by default
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::EffectiveFee {
+            borrower: ARB_CONTRACT.to_string(),
+            amount: Uint128::new(1000),
+        },
+    );
+    assert!(res.is_err());
+
+    let msg = ExecuteMsg::SetFeeOverride {
+        borrower: ARB_CONTRACT.to_string(),
+        fee: Some(flat(2)),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    let res: EffectiveFeeResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::EffectiveFee {
+                borrower: ARB_CONTRACT.to_string(),
+                amount: Uint128::new(1000),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        EffectiveFeeResponse {
+            fee: flat(2),
+            loan_fee: Uint128::new(2),
+        }
+    );
+}
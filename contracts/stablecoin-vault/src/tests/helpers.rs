@@ -4,9 +4,11 @@ use cw20::Cw20ReceiveMsg;
 use terraswap::pair::Cw20HookMsg;
 
 use white_whale::ust_vault::msg::CallbackMsg;
+use white_whale::vault::flashloan::encapsulate_payload;
 
-use crate::contract::{encapsulate_payload, get_treasury_fee, receive_cw20};
+use crate::contract::{get_treasury_fee, receive_cw20};
 use crate::error::StableVaultError;
+use crate::flashloan::StableVault;
 use crate::tests::common::TEST_CREATOR;
 use crate::tests::instantiate::{mock_instantiate, TREASURY_FEE};
 use crate::tests::mock_querier::mock_dependencies;
@@ -19,7 +21,7 @@ fn test_encapsulate_payload() {
     let response = Response::new();
     let loan_fee = Uint128::new(1000);
 
-    let res = encapsulate_payload(deps.as_ref(), mock_env(), response, loan_fee).unwrap();
+    let res = encapsulate_payload(&StableVault, &mock_env(), response, loan_fee).unwrap();
     assert_eq!(res.messages.len(), 1);
 
     let env = mock_env();
@@ -11,16 +11,28 @@ use white_whale::ust_vault::msg::*;
 
 use crate::contract::{execute, instantiate, query};
 use crate::error::StableVaultError;
-use crate::state::{State, FEE, STATE};
+use crate::pool_info::PoolInfo;
+use crate::state::{State, FEE, STATE, YIELD_ADAPTERS};
 use crate::tests::common::{ARB_CONTRACT, TEST_CREATOR};
 
 const INSTANTIATE_REPLY_ID: u8 = 1u8;
 pub(crate) const TREASURY_FEE: u64 = 10u64;
 
+pub fn anchor_adapter() -> YieldAdapterInfo {
+    YieldAdapterInfo {
+        adapter: YieldAdapterKind::Anchor {
+            money_market_address: "test_mm".to_string(),
+            aust_address: "test_aust".to_string(),
+        },
+        weight: Decimal::one(),
+        redeem_priority: 0u32,
+        deposit_threshold: Decimal::percent(150),
+    }
+}
+
 pub fn instantiate_msg() -> InstantiateMsg {
     InstantiateMsg {
-        anchor_money_market_address: "test_mm".to_string(),
-        aust_address: "test_aust".to_string(),
+        yield_adapters: vec![anchor_adapter()],
         treasury_addr: "treasury".to_string(),
         asset_info: AssetInfo::NativeToken {
             denom: "uusd".to_string(),
@@ -40,8 +52,7 @@ pub fn instantiate_msg() -> InstantiateMsg {
  */
 pub fn mock_instantiate(deps: DepsMut) {
     let msg = InstantiateMsg {
-        anchor_money_market_address: "test_mm".to_string(),
-        aust_address: "test_aust".to_string(),
+        yield_adapters: vec![anchor_adapter()],
         treasury_addr: "treasury".to_string(),
         asset_info: AssetInfo::NativeToken {
             denom: "uusd".to_string(),
@@ -76,12 +87,14 @@ fn successful_initialization() {
     assert_eq!(
         state,
         State {
-            anchor_money_market_address: deps.api.addr_validate("test_mm").unwrap(),
-            aust_address: deps.api.addr_validate("test_aust").unwrap(),
             whitelisted_contracts: vec![],
             allow_non_whitelisted: false,
         }
     );
+    assert_eq!(
+        YIELD_ADAPTERS.load(&deps.storage).unwrap(),
+        vec![anchor_adapter()]
+    );
 
     let msg = ExecuteMsg::AddToWhitelist {
         contract_addr: ARB_CONTRACT.to_string(),
@@ -99,8 +112,7 @@ fn unsuccessful_initialization_invalid_fees() {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
-        anchor_money_market_address: "test_mm".to_string(),
-        aust_address: "test_aust".to_string(),
+        yield_adapters: vec![anchor_adapter()],
         treasury_addr: "treasury".to_string(),
         asset_info: AssetInfo::NativeToken {
             denom: "uusd".to_string(),
@@ -127,8 +139,7 @@ fn unsuccessful_initialization_invalid_asset() {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
-        anchor_money_market_address: "test_mm".to_string(),
-        aust_address: "test_aust".to_string(),
+        yield_adapters: vec![anchor_adapter()],
         treasury_addr: "treasury".to_string(),
         asset_info: AssetInfo::Token {
             //invalid asset
@@ -146,8 +157,53 @@ fn unsuccessful_initialization_invalid_asset() {
     let info = mock_info(TEST_CREATOR, &[]);
     let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
     match res {
-        Err(StableVaultError::NotNativeToken {}) => (),
-        _ => panic!("Must return StableVaultError::NotNativeToken"),
+        Err(StableVaultError::UnsupportedAdapterAsset {}) => (),
+        _ => panic!("Must return StableVaultError::UnsupportedAdapterAsset"),
+    }
+}
+
+#[test]
+fn successful_initialization_cw20_asset_without_anchor() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        yield_adapters: vec![],
+        asset_info: AssetInfo::Token {
+            contract_addr: "stable_token".to_string(),
+        },
+        ..instantiate_msg()
+    };
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::PoolConfig {}).unwrap();
+    let pool_info: PoolInfo = from_binary(&res).unwrap();
+    assert_eq!(
+        pool_info.asset_infos,
+        vec![AssetInfo::Token {
+            contract_addr: "stable_token".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn unsuccessful_initialization_invalid_adapter_weights() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        yield_adapters: vec![YieldAdapterInfo {
+            weight: Decimal::percent(50),
+            ..anchor_adapter()
+        }],
+        ..instantiate_msg()
+    };
+
+    let info = mock_info(TEST_CREATOR, &[]);
+    let res = instantiate(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(StableVaultError::InvalidAdapterWeights {}) => (),
+        _ => panic!("Must return StableVaultError::InvalidAdapterWeights"),
     }
 }
 
@@ -275,8 +331,7 @@ fn test_init_with_non_default_vault_lp_token() {
 
     // Define a custom Init Msg with the custom token info provided
     let msg = InstantiateMsg {
-        anchor_money_market_address: "test_mm".to_string(),
-        aust_address: "test_aust".to_string(),
+        yield_adapters: vec![anchor_adapter()],
         treasury_addr: "treasury".to_string(),
         asset_info: AssetInfo::NativeToken {
             denom: "uusd".to_string(),
@@ -11,7 +11,7 @@ use terra_multi_test::Executor;
 use terraswap::asset::{Asset, AssetInfo};
 use terraswap::pair::Cw20HookMsg;
 use white_whale::treasury::msg::InstantiateMsg as TreasuryInitMsg;
-use white_whale::ust_vault::msg::ExecuteMsg;
+use white_whale::ust_vault::msg::{ExecuteMsg, SimulateWithdrawResponse, VaultQueryMsg};
 
 const DEFAULT_SMALL_AMOUNT_OF_UST: u128 = 10000u128;
 const DEFAULT_LARGE_AMOUNT_OF_UST: u128 = 100000000000000000u128;
@@ -225,6 +225,26 @@ fn stablecoin_vault_fees_are_allocated() {
     // Withdraw some liquidity
     let msg = Cw20HookMsg::WithdrawLiquidity {};
     let withdraw_amount = Uint128::new(100);
+
+    // The simulation charges the same treasury fee as the withdrawal
+    let simulation: SimulateWithdrawResponse = router
+        .wrap()
+        .query_wasm_smart(
+            vault_addr.clone(),
+            &VaultQueryMsg::SimulateWithdraw {
+                lp_amount: withdraw_amount,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        simulation.treasury_fee,
+        withdraw_amount.checked_div(Uint128::new(10)).unwrap()
+    );
+    assert_eq!(
+        simulation.return_amount,
+        simulation.withdraw_value - simulation.redeem_tax - simulation.transfer_tax
+    );
+
     // Prepare cw20 message with our attempt to withdraw tokens, this should incur a fee
     let send_msg = Cw20ExecuteMsg::Send {
         contract: vault_addr.to_string(),
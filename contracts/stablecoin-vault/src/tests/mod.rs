@@ -12,8 +12,11 @@ mod pool;
 
 mod anchor_mock;
 mod deposit;
+mod fees;
 mod helpers;
+mod pause;
 mod query;
 mod state;
 mod tswap_mock;
 mod whitelist;
+mod yield_adapters;
@@ -0,0 +1,121 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{coins, from_binary, Uint128};
+use terraswap::asset::{Asset, AssetInfo};
+
+use white_whale::denom::UST_DENOM;
+use white_whale::ust_vault::msg::{ExecuteMsg, FlashLoanPayload, VaultQueryMsg as QueryMsg};
+use white_whale::vault::pause::{PausableOperation, PauseInfoResponse, PauseState};
+
+use crate::contract::{execute, query};
+use crate::error::StableVaultError;
+use crate::tests::common::TEST_CREATOR;
+use crate::tests::instantiate::mock_instantiate;
+use crate::tests::mock_querier::mock_dependencies;
+
+const GUARDIAN: &str = "guardian";
+
+fn ust(amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::NativeToken {
+            denom: UST_DENOM.to_string(),
+        },
+        amount: Uint128::new(amount),
+    }
+}
+
+#[test]
+fn unsuccessful_pause_unauthorized() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::Pause {
+        operation: PausableOperation::Deposits,
+        reason: "exploit".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg,
+    );
+    match res {
+        Err(StableVaultError::Unauthorized {}) => (),
+        _ => panic!("Must return StableVaultError::Unauthorized"),
+    }
+}
+
+#[test]
+fn successful_guardian_pause() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetGuardian {
+        guardian: Some(GUARDIAN.to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    for operation in [PausableOperation::Deposits, PausableOperation::FlashLoans] {
+        let msg = ExecuteMsg::Pause {
+            operation,
+            reason: "exploit".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(GUARDIAN, &[]), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::ProvideLiquidity { asset: ust(1000) };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(TEST_CREATOR, &coins(1000, UST_DENOM)),
+        msg,
+    );
+    assert_eq!(
+        res,
+        Err(StableVaultError::Paused {
+            operation: PausableOperation::Deposits,
+            reason: "exploit".to_string(),
+        })
+    );
+
+    let msg = ExecuteMsg::FlashLoan {
+        payload: FlashLoanPayload {
+            requested_asset: ust(1000),
+            callback: Default::default(),
+        },
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(StableVaultError::Paused { .. }) => (),
+        _ => panic!("Must return StableVaultError::Paused"),
+    }
+
+    // Only the admin can unpause
+    let msg = ExecuteMsg::Unpause {
+        operation: PausableOperation::Deposits,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(GUARDIAN, &[]),
+        msg.clone(),
+    );
+    match res {
+        Err(StableVaultError::Admin(_)) => (),
+        _ => panic!("Must return StableVaultError::Admin"),
+    }
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    let res: PauseInfoResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::PauseInfo {}).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        PauseInfoResponse {
+            guardian: Some(GUARDIAN.to_string()),
+            paused: PauseState {
+                deposits: None,
+                withdrawals: None,
+                flash_loans: Some("exploit".to_string()),
+            },
+        }
+    );
+}
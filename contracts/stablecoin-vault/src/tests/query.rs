@@ -1,11 +1,12 @@
 use cosmwasm_std::testing::mock_env;
 use cosmwasm_std::{coin, from_binary};
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Decimal, Timestamp, Uint128};
 
+use white_whale::snapshot::{AprResponse, SharePriceResponse};
 use white_whale::ust_vault::msg::VaultQueryMsg as QueryMsg;
 use white_whale::ust_vault::msg::*;
 
-use crate::contract::query;
+use crate::contract::{query, record_snapshot};
 use crate::tests::instantiate::mock_instantiate;
 use crate::tests::mock_querier::mock_dependencies;
 
@@ -71,3 +72,57 @@ pub fn test_vault_estimate_fee_query() {
     .unwrap();
     assert_ne!(q_res.fee, vec![])
 }
+
+#[test]
+pub fn test_share_price_and_apr_queries() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    let day = 86_400u64;
+
+    // no snapshot recorded yet
+    assert!(query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SharePrice { timestamp: None }
+    )
+    .is_err());
+
+    // share price goes from 1 to 1.02 over 73 days, i.e. 10% APR
+    for (timestamp, total_value, total_share) in
+        [(day, 1000u128, 1000u128), (74 * day, 2040u128, 2000u128)]
+    {
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(timestamp);
+        record_snapshot(
+            &mut deps.storage,
+            &env,
+            Uint128::new(total_value),
+            Uint128::new(total_value),
+            Uint128::new(total_share),
+            Uint128::zero(),
+        )
+        .unwrap();
+    }
+
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(80 * day);
+    let q_res: SharePriceResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::SharePrice {
+                timestamp: Some(30 * day),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(q_res.share_price, Decimal::one());
+    assert_eq!(q_res.snapshot.timestamp, day);
+
+    let q_res: AprResponse =
+        from_binary(&query(deps.as_ref(), env, QueryMsg::Apr { window: 79 * day }).unwrap())
+            .unwrap();
+    assert_eq!(q_res.apr, Decimal::percent(10));
+    assert_eq!(q_res.end.timestamp, 74 * day);
+}
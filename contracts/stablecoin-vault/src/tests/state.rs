@@ -16,8 +16,6 @@ fn unsuccessful_set_state_unauthorized() {
     mock_instantiate(deps.as_mut());
 
     let msg = ExecuteMsg::UpdateState {
-        anchor_money_market_address: None,
-        aust_address: None,
         allow_non_whitelisted: None,
     };
     let info = MessageInfo {
@@ -40,8 +38,6 @@ fn successful_set_state() {
     let original_state = STATE.load(deps.as_mut().storage).unwrap();
 
     let msg = ExecuteMsg::UpdateState {
-        anchor_money_market_address: Some(String::from("new_anchor_money_market_address")),
-        aust_address: Some(String::from("new_aust_address")),
         allow_non_whitelisted: Some(true),
     };
     let info = MessageInfo {
@@ -54,15 +50,5 @@ fn successful_set_state() {
 
     let new_state = STATE.load(deps.as_mut().storage).unwrap();
     assert_ne!(original_state, new_state.clone());
-    assert_eq!(
-        new_state.anchor_money_market_address,
-        deps.api
-            .addr_validate("new_anchor_money_market_address")
-            .unwrap()
-    );
-    assert_eq!(
-        new_state.aust_address,
-        deps.api.addr_validate("new_aust_address").unwrap()
-    );
     assert_eq!(new_state.allow_non_whitelisted, true);
 }
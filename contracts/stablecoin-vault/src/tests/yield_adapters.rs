@@ -0,0 +1,198 @@
+use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::{from_binary, Decimal, Uint128};
+use terraswap::asset::AssetInfo;
+
+use white_whale::ust_vault::msg::VaultQueryMsg as QueryMsg;
+use white_whale::ust_vault::msg::*;
+
+use crate::adapters::{deposit_into_adapters, redeem_from_adapters};
+use crate::contract::{execute, query};
+use crate::error::StableVaultError;
+use crate::pool_info::PoolInfo;
+use crate::state::YIELD_ADAPTERS;
+use crate::tests::common::TEST_CREATOR;
+use crate::tests::instantiate::{anchor_adapter, mock_instantiate};
+use crate::tests::mock_querier::mock_dependencies;
+
+#[test]
+fn test_query_yield_adapters() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::YieldAdapters {}).unwrap();
+    let response: YieldAdaptersResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        response.adapters,
+        vec![YieldAdapterResponse {
+            adapter: anchor_adapter().adapter,
+            weight: Decimal::one(),
+            redeem_priority: 0u32,
+            deposit_threshold: Decimal::percent(150),
+            value: Uint128::zero(),
+            exchange_rate: Decimal::percent(120),
+        }]
+    );
+}
+
+#[test]
+fn unsuccessful_set_yield_adapters_unauthorized() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetYieldAdapters { adapters: vec![] };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("unauthorized", &[]),
+        msg,
+    );
+    match res {
+        Err(StableVaultError::Admin(_)) => (),
+        _ => panic!("Must return StableVaultError::Admin"),
+    }
+}
+
+#[test]
+fn unsuccessful_set_yield_adapters_duplicated() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let half_anchor = YieldAdapterInfo {
+        weight: Decimal::percent(50),
+        ..anchor_adapter()
+    };
+    let msg = ExecuteMsg::SetYieldAdapters {
+        adapters: vec![half_anchor.clone(), half_anchor],
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(StableVaultError::DuplicatedAdapter {}) => (),
+        _ => panic!("Must return StableVaultError::DuplicatedAdapter"),
+    }
+}
+
+#[test]
+fn unsuccessful_set_yield_adapters_invalid_address() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetYieldAdapters {
+        adapters: vec![YieldAdapterInfo {
+            adapter: YieldAdapterKind::Anchor {
+                money_market_address: "mm".to_string(),
+                aust_address: "test_aust".to_string(),
+            },
+            ..anchor_adapter()
+        }],
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(StableVaultError::Std(_)) => (),
+        _ => panic!("Must return StableVaultError::Std"),
+    }
+}
+
+#[test]
+fn unsuccessful_set_yield_adapters_invalid_deposit_threshold() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let msg = ExecuteMsg::SetYieldAdapters {
+        adapters: vec![YieldAdapterInfo {
+            deposit_threshold: Decimal::percent(90),
+            ..anchor_adapter()
+        }],
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg);
+    match res {
+        Err(StableVaultError::InvalidDepositThreshold {}) => (),
+        _ => panic!("Must return StableVaultError::InvalidDepositThreshold"),
+    }
+}
+
+#[test]
+fn successful_set_yield_adapters_removes_empty_adapter() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    // the vault holds no aUST, so Anchor can be removed
+    let msg = ExecuteMsg::SetYieldAdapters { adapters: vec![] };
+    execute(deps.as_mut(), mock_env(), mock_info(TEST_CREATOR, &[]), msg).unwrap();
+
+    assert_eq!(YIELD_ADAPTERS.load(&deps.storage).unwrap(), vec![]);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::PoolConfig {}).unwrap();
+    let pool_info: PoolInfo = from_binary(&res).unwrap();
+    assert_eq!(
+        pool_info.asset_infos,
+        vec![AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_deposit_into_adapters_above_threshold() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+    let stable_cap = Uint128::new(100_000_000u128);
+
+    // below 150% of the stable cap the funds stay in the vault
+    let messages = deposit_into_adapters(
+        deps.as_ref(),
+        &mock_env(),
+        Uint128::new(150_000_000u128),
+        stable_cap,
+    )
+    .unwrap();
+    assert!(messages.is_empty());
+
+    let messages = deposit_into_adapters(
+        deps.as_ref(),
+        &mock_env(),
+        Uint128::new(400_000_000u128),
+        stable_cap,
+    )
+    .unwrap();
+    assert_eq!(messages.len(), 1);
+
+    // adapters with a higher threshold are skipped
+    YIELD_ADAPTERS
+        .save(
+            &mut deps.storage,
+            &vec![
+                YieldAdapterInfo {
+                    weight: Decimal::percent(50),
+                    ..anchor_adapter()
+                },
+                YieldAdapterInfo {
+                    adapter: YieldAdapterKind::Anchor {
+                        money_market_address: "other_mm".to_string(),
+                        aust_address: "other_aust".to_string(),
+                    },
+                    weight: Decimal::percent(50),
+                    redeem_priority: 1u32,
+                    deposit_threshold: Decimal::percent(500),
+                },
+            ],
+        )
+        .unwrap();
+    let messages = deposit_into_adapters(
+        deps.as_ref(),
+        &mock_env(),
+        Uint128::new(400_000_000u128),
+        stable_cap,
+    )
+    .unwrap();
+    assert_eq!(messages.len(), 1);
+}
+
+#[test]
+fn test_redeem_from_empty_adapters() {
+    let mut deps = mock_dependencies(&[]);
+    mock_instantiate(deps.as_mut());
+
+    let (messages, redeemed) =
+        redeem_from_adapters(deps.as_ref(), &mock_env(), Uint128::new(1_000u128)).unwrap();
+    assert!(messages.is_empty());
+    assert_eq!(redeemed, Uint128::zero());
+}
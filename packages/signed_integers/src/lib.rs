@@ -1,3 +1,5 @@
+mod signed_decimal;
 mod signed_integer;
 
+pub use crate::signed_decimal::SignedDecimal;
 pub use crate::signed_integer::SignedInt;
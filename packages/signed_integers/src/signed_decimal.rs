@@ -0,0 +1,280 @@
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::fmt;
+use std::ops;
+use std::str::FromStr;
+
+use cosmwasm_std::{
+    Decimal, Fraction, OverflowError, OverflowOperation, StdError, Uint128, Uint256,
+};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{de, ser, Deserialize, Deserializer, Serialize};
+
+use crate::signed_integer::SignedInt;
+
+/// Decimal with a sign, the boolean is set for negative values.
+/// Serialized as a string, i.e. "-0.5" for SignedDecimal(Decimal::percent(50), true). Zero is
+/// never negative.
+#[derive(Copy, Clone, Default, Debug, Eq)]
+pub struct SignedDecimal(pub Decimal, pub bool);
+
+impl SignedDecimal {
+    /// Zero is always positive
+    pub fn new(value: Decimal, negative: bool) -> SignedDecimal {
+        SignedDecimal(value, negative && !value.is_zero())
+    }
+
+    pub fn zero() -> SignedDecimal {
+        SignedDecimal(Decimal::zero(), false)
+    }
+
+    pub fn one() -> SignedDecimal {
+        SignedDecimal(Decimal::one(), false)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.1 && !self.0.is_zero()
+    }
+
+    /// Absolute value
+    pub fn abs(&self) -> Decimal {
+        self.0
+    }
+
+    /// Returns the ratio (numerator / denominator), panics if the denominator is zero as
+    /// Decimal::from_ratio does
+    pub fn from_ratio(numerator: SignedInt, denominator: SignedInt) -> SignedDecimal {
+        SignedDecimal::new(
+            Decimal::from_ratio(numerator.0, denominator.0),
+            numerator.is_negative() != denominator.is_negative(),
+        )
+    }
+
+    pub fn checked_add(self, other: SignedDecimal) -> Result<SignedDecimal, OverflowError> {
+        let sum = SignedInt::new(atomics(self.0), self.is_negative())
+            .checked_add(SignedInt::new(atomics(other.0), other.is_negative()))?;
+        Ok(SignedDecimal::new(from_atomics(sum.0), sum.is_negative()))
+    }
+
+    pub fn checked_sub(self, other: SignedDecimal) -> Result<SignedDecimal, OverflowError> {
+        self.checked_add(-other)
+    }
+
+    /// Rounds towards zero
+    pub fn checked_mul(self, other: Decimal) -> Result<SignedDecimal, OverflowError> {
+        let product: Uint128 = (atomics(self.0).full_mul(other.numerator())
+            / Uint256::from(other.denominator()))
+        .try_into()
+        .map_err(|_| OverflowError::new(OverflowOperation::Mul, self, other))?;
+        Ok(SignedDecimal::new(
+            from_atomics(product),
+            self.is_negative(),
+        ))
+    }
+}
+
+/// Integer representation of the decimal, i.e. 10^18 for 1
+fn atomics(decimal: Decimal) -> Uint128 {
+    Uint128::new(decimal.numerator())
+}
+
+fn from_atomics(atomics: Uint128) -> Decimal {
+    Decimal::from_ratio(atomics, Decimal::one().denominator())
+}
+
+impl ops::Neg for SignedDecimal {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        SignedDecimal::new(self.0, !self.is_negative())
+    }
+}
+
+/// Panics on overflow, use checked_mul to handle it
+impl ops::Mul<Decimal> for SignedDecimal {
+    type Output = Self;
+
+    fn mul(self, rhs: Decimal) -> Self {
+        self.checked_mul(rhs).expect("multiplication overflow")
+    }
+}
+
+impl Ord for SignedDecimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (false, false) => self.0.cmp(&other.0),
+            (true, true) => other.0.cmp(&self.0),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for SignedDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SignedDecimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl From<Decimal> for SignedDecimal {
+    fn from(value: Decimal) -> Self {
+        SignedDecimal(value, false)
+    }
+}
+
+impl fmt::Display for SignedDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for SignedDecimal {
+    type Err = StdError;
+
+    /// Parses a decimal with an optional leading minus sign, i.e. "-0.5"
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (negative, decimal) = match input.strip_prefix('-') {
+            Some(decimal) => (true, decimal),
+            None => (false, input),
+        };
+        Ok(SignedDecimal::new(Decimal::from_str(decimal)?, negative))
+    }
+}
+
+impl Serialize for SignedDecimal {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for SignedDecimal {
+    fn deserialize<D>(deserializer: D) -> Result<SignedDecimal, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(SignedDecimalVisitor)
+    }
+}
+
+struct SignedDecimalVisitor;
+
+impl<'de> de::Visitor<'de> for SignedDecimalVisitor {
+    type Value = SignedDecimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("string-encoded signed decimal")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        SignedDecimal::from_str(v)
+            .map_err(|e| E::custom(format!("invalid SignedDecimal '{}' - {}", v, e)))
+    }
+}
+
+impl JsonSchema for SignedDecimal {
+    fn schema_name() -> String {
+        "SignedDecimal".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cosmwasm_std::{from_slice, to_vec};
+
+    fn signed(percent: u64, negative: bool) -> SignedDecimal {
+        SignedDecimal::new(Decimal::percent(percent), negative)
+    }
+
+    #[test]
+    fn from_ratio() {
+        assert_eq!(
+            SignedDecimal::from_ratio(SignedInt::from(-1i128), SignedInt::from(4i128)),
+            signed(25, true)
+        );
+        assert_eq!(
+            SignedDecimal::from_ratio(SignedInt::from(-1i128), SignedInt::from(-4i128)),
+            signed(25, false)
+        );
+    }
+
+    #[test]
+    fn checked_add_and_sub() {
+        assert_eq!(
+            signed(50, false).checked_add(signed(75, true)),
+            Ok(signed(25, true))
+        );
+        assert_eq!(
+            signed(50, true).checked_sub(signed(25, false)),
+            Ok(signed(75, true))
+        );
+        assert_eq!(
+            signed(50, true).checked_sub(signed(50, true)),
+            Ok(SignedDecimal::zero())
+        );
+        assert!(SignedDecimal::from(Decimal::MAX)
+            .checked_add(SignedDecimal::one())
+            .is_err());
+    }
+
+    #[test]
+    fn mul_decimal() {
+        assert_eq!(signed(50, true) * Decimal::percent(50), signed(25, true));
+        assert_eq!(
+            signed(150, false) * Decimal::percent(200),
+            signed(300, false)
+        );
+        assert_eq!(signed(50, true) * Decimal::zero(), SignedDecimal::zero());
+        assert!(SignedDecimal::from(Decimal::MAX)
+            .checked_mul(Decimal::percent(200))
+            .is_err());
+    }
+
+    #[test]
+    fn ordering_respects_sign() {
+        assert!(signed(50, true) < signed(25, true));
+        assert!(signed(25, true) < SignedDecimal::zero());
+        assert!(SignedDecimal::zero() < signed(1, false));
+        assert_eq!(SignedDecimal(Decimal::zero(), true), SignedDecimal::zero());
+    }
+
+    #[test]
+    fn serde() {
+        assert_eq!(signed(50, true).to_string(), "-0.5");
+        assert_eq!(to_vec(&signed(50, true)).unwrap(), br#""-0.5""#.to_vec());
+        assert_eq!(
+            from_slice::<SignedDecimal>(br#""-0.5""#).unwrap(),
+            signed(50, true)
+        );
+        assert_eq!(
+            from_slice::<SignedDecimal>(br#""1.25""#).unwrap(),
+            signed(125, false)
+        );
+        assert!(from_slice::<SignedDecimal>(br#""--1""#).is_err());
+    }
+}
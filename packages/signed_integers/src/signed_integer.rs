@@ -1,15 +1,49 @@
-use cosmwasm_std::Uint128;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops;
+use std::str::FromStr;
+
+use cosmwasm_std::{
+    ConversionOverflowError, Decimal, DivideByZeroError, OverflowError, StdError, Uint128,
+};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{de, ser, Deserialize, Deserializer, Serialize};
+
+use crate::signed_decimal::SignedDecimal;
 
 /// boolean is set for specifying the negativity.
 /// false means the value is positive.
-#[derive(
-    Serialize, Deserialize, Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
-)]
-pub struct SignedInt(#[schemars(with = "String")] pub Uint128, pub bool);
+/// Serialized as a string, i.e. "-10" for SignedInt(10, true). Zero is never negative, so
+/// SignedInt(0, true) equals SignedInt(0, false).
+#[derive(Copy, Clone, Default, Debug, Eq)]
+pub struct SignedInt(pub Uint128, pub bool);
 
 impl SignedInt {
+    /// Zero is always positive
+    pub fn new(value: Uint128, negative: bool) -> SignedInt {
+        SignedInt(value, negative && !value.is_zero())
+    }
+
+    pub fn zero() -> SignedInt {
+        SignedInt(Uint128::zero(), false)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.1 && !self.0.is_zero()
+    }
+
+    /// Absolute value
+    pub fn abs(&self) -> Uint128 {
+        self.0
+    }
+
     pub fn from_subtraction<A: Into<Uint128>, B: Into<Uint128>>(
         minuend: A,
         subtrahend: B,
@@ -22,12 +56,207 @@ impl SignedInt {
         }
         SignedInt(subtraction.unwrap(), false)
     }
+
+    pub fn checked_add(self, other: SignedInt) -> Result<SignedInt, OverflowError> {
+        match (self.is_negative(), other.is_negative()) {
+            (false, false) => Ok(SignedInt(self.0.checked_add(other.0)?, false)),
+            (true, true) => Ok(SignedInt(self.0.checked_add(other.0)?, true)),
+            (false, true) => Ok(SignedInt::from_subtraction(self.0, other.0)),
+            (true, false) => Ok(SignedInt::from_subtraction(other.0, self.0)),
+        }
+    }
+
+    pub fn checked_sub(self, other: SignedInt) -> Result<SignedInt, OverflowError> {
+        self.checked_add(-other)
+    }
+
+    pub fn checked_mul(self, other: SignedInt) -> Result<SignedInt, OverflowError> {
+        Ok(SignedInt::new(
+            self.0.checked_mul(other.0)?,
+            self.is_negative() != other.is_negative(),
+        ))
+    }
+
+    /// Rounds towards zero, i.e. -7 / 2 = -3
+    pub fn checked_div(self, other: SignedInt) -> Result<SignedInt, DivideByZeroError> {
+        Ok(SignedInt::new(
+            self.0.checked_div(other.0)?,
+            self.is_negative() != other.is_negative(),
+        ))
+    }
+}
+
+impl ops::Neg for SignedInt {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        SignedInt::new(self.0, !self.is_negative())
+    }
+}
+
+/// Rounds towards zero, as Uint128 * Decimal does
+impl ops::Mul<Decimal> for SignedInt {
+    type Output = Self;
+
+    fn mul(self, rhs: Decimal) -> Self {
+        SignedInt::new(self.0 * rhs, self.is_negative())
+    }
+}
+
+/// Rounds towards zero, as Uint128 * Decimal does
+impl ops::Mul<SignedDecimal> for SignedInt {
+    type Output = Self;
+
+    fn mul(self, rhs: SignedDecimal) -> Self {
+        SignedInt::new(self.0 * rhs.0, self.is_negative() != rhs.is_negative())
+    }
+}
+
+impl Ord for SignedInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (false, false) => self.0.cmp(&other.0),
+            (true, true) => other.0.cmp(&self.0),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for SignedInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SignedInt {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl From<Uint128> for SignedInt {
+    fn from(value: Uint128) -> Self {
+        SignedInt(value, false)
+    }
+}
+
+impl From<i128> for SignedInt {
+    fn from(value: i128) -> Self {
+        SignedInt::new(Uint128::new(value.unsigned_abs()), value < 0)
+    }
+}
+
+impl TryFrom<SignedInt> for Uint128 {
+    type Error = ConversionOverflowError;
+
+    fn try_from(value: SignedInt) -> Result<Self, Self::Error> {
+        if value.is_negative() {
+            return Err(ConversionOverflowError::new(
+                "SignedInt",
+                "Uint128",
+                value.to_string(),
+            ));
+        }
+        Ok(value.0)
+    }
+}
+
+impl TryFrom<SignedInt> for i128 {
+    type Error = ConversionOverflowError;
+
+    fn try_from(value: SignedInt) -> Result<Self, Self::Error> {
+        let abs = value.0.u128();
+        let converted = if !value.is_negative() {
+            i128::try_from(abs).ok()
+        } else if abs == i128::MIN.unsigned_abs() {
+            Some(i128::MIN)
+        } else {
+            i128::try_from(abs).ok().map(|abs| -abs)
+        };
+        converted
+            .ok_or_else(|| ConversionOverflowError::new("SignedInt", "i128", value.to_string()))
+    }
+}
+
+impl fmt::Display for SignedInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for SignedInt {
+    type Err = StdError;
+
+    /// Parses an integer with an optional leading minus sign, i.e. "-10"
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match input.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, input),
+        };
+        let value = digits
+            .parse::<u128>()
+            .map_err(|e| StdError::generic_err(format!("Parsing SignedInt: {}", e)))?;
+        Ok(SignedInt::new(Uint128::new(value), negative))
+    }
+}
+
+impl Serialize for SignedInt {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for SignedInt {
+    fn deserialize<D>(deserializer: D) -> Result<SignedInt, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(SignedIntVisitor)
+    }
+}
+
+struct SignedIntVisitor;
+
+impl<'de> de::Visitor<'de> for SignedIntVisitor {
+    type Value = SignedInt;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("string-encoded signed integer")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        SignedInt::from_str(v).map_err(|e| E::custom(format!("invalid SignedInt '{}' - {}", v, e)))
+    }
+}
+
+impl JsonSchema for SignedInt {
+    fn schema_name() -> String {
+        "SignedInt".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use cosmwasm_std::Uint128;
+    use cosmwasm_std::{from_slice, to_vec, Uint128};
+
+    fn signed(value: i128) -> SignedInt {
+        SignedInt::from(value)
+    }
 
     #[test]
     fn from_subtraction() {
@@ -44,4 +273,100 @@ mod test {
         assert_eq!(signed_integer.0, Uint128::new(100000));
         assert!(signed_integer.1);
     }
+
+    #[test]
+    fn checked_add_and_sub() {
+        let ten = SignedInt::from(Uint128::new(10));
+        let minus_four = SignedInt::new(Uint128::new(4), true);
+
+        assert_eq!(
+            ten.checked_add(minus_four),
+            Ok(SignedInt::from(Uint128::new(6)))
+        );
+        assert_eq!(
+            minus_four.checked_add(minus_four),
+            Ok(SignedInt::new(Uint128::new(8), true))
+        );
+        assert_eq!(
+            minus_four.checked_sub(ten),
+            Ok(SignedInt::new(Uint128::new(14), true))
+        );
+        assert_eq!(minus_four.checked_sub(minus_four), Ok(SignedInt::zero()));
+        assert!(SignedInt::from(Uint128::MAX).checked_add(ten).is_err());
+    }
+
+    #[test]
+    fn checked_mul_and_div() {
+        assert_eq!(signed(-3).checked_mul(signed(4)), Ok(signed(-12)));
+        assert_eq!(signed(-3).checked_mul(signed(-4)), Ok(signed(12)));
+        assert_eq!(signed(-3).checked_mul(signed(0)), Ok(SignedInt::zero()));
+        assert!(SignedInt::from(Uint128::MAX)
+            .checked_mul(signed(-2))
+            .is_err());
+
+        assert_eq!(signed(-7).checked_div(signed(2)), Ok(signed(-3)));
+        assert_eq!(signed(-7).checked_div(signed(-2)), Ok(signed(3)));
+        assert_eq!(signed(1).checked_div(signed(-2)), Ok(SignedInt::zero()));
+        assert!(signed(7).checked_div(SignedInt::zero()).is_err());
+    }
+
+    #[test]
+    fn neg_and_mul_decimal() {
+        assert_eq!(-signed(5), signed(-5));
+        assert_eq!(-signed(-5), signed(5));
+        assert!(!(-SignedInt::zero()).is_negative());
+
+        assert_eq!(signed(-100) * Decimal::percent(25), signed(-25));
+        assert_eq!(
+            signed(-100) * SignedDecimal::new(Decimal::percent(25), true),
+            signed(25)
+        );
+    }
+
+    #[test]
+    fn ordering_respects_sign() {
+        assert!(signed(-5) < signed(-4));
+        assert!(signed(-4) < SignedInt::zero());
+        assert!(SignedInt::zero() < signed(3));
+        assert_eq!(SignedInt(Uint128::zero(), true), SignedInt::zero());
+        assert_eq!(std::cmp::max(signed(-10), signed(-20)), signed(-10));
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(i128::try_from(signed(-42)), Ok(-42));
+        assert_eq!(i128::try_from(signed(i128::MIN)), Ok(i128::MIN));
+        assert_eq!(i128::try_from(signed(i128::MAX)), Ok(i128::MAX));
+        assert!(i128::try_from(SignedInt::from(Uint128::MAX)).is_err());
+
+        assert_eq!(Uint128::try_from(signed(42)), Ok(Uint128::new(42)));
+        assert!(Uint128::try_from(signed(-42)).is_err());
+    }
+
+    #[test]
+    fn display_and_from_str() {
+        assert_eq!(signed(-42).to_string(), "-42");
+        assert_eq!(signed(42).to_string(), "42");
+        assert_eq!(SignedInt::from_str("-42").unwrap(), signed(-42));
+        assert_eq!(SignedInt::from_str("-0").unwrap(), SignedInt::zero());
+        assert!(SignedInt::from_str("--1").is_err());
+        assert!(SignedInt::from_str("1.5").is_err());
+    }
+
+    #[test]
+    fn serde() {
+        let minus_four = SignedInt::new(Uint128::new(4), true);
+        assert_eq!(to_vec(&minus_four).unwrap(), br#""-4""#.to_vec());
+        assert_eq!(from_slice::<SignedInt>(br#""-4""#).unwrap(), minus_four);
+        assert_eq!(
+            from_slice::<SignedInt>(br#""10""#).unwrap(),
+            SignedInt::from(Uint128::new(10))
+        );
+        // zero is never negative
+        assert_eq!(
+            from_slice::<SignedInt>(br#""-0""#).unwrap(),
+            SignedInt::zero()
+        );
+        assert!(from_slice::<SignedInt>(br#""1-0""#).is_err());
+    }
 }
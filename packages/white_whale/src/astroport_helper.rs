@@ -53,6 +53,12 @@ pub enum AstroportQueryMsg {
     },
 }
 
+/// SimulateSwapOperationsResponse returns the router swap simulation response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateSwapOperationsResponse {
+    pub amount: Uint128,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SwapOperation {
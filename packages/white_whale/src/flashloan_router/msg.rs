@@ -0,0 +1,103 @@
+use cosmwasm_std::{to_binary, Addr, Binary, CosmosMsg, StdResult, WasmMsg};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use terraswap::asset::{Asset, AssetInfo};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// vaults to borrow from, one per asset
+    pub vaults: Vec<VaultInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultInfo {
+    /// base asset of the vault
+    pub asset_info: AssetInfo,
+    pub vault_address: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Borrows the requested assets from their vaults and calls the borrower's callback once
+    /// with all of them. The loans are taken by the router, so the vaults charge their
+    /// flashloan fee to the router unless it is whitelisted. Only the borrowers whitelisted on
+    /// the router can borrow through it, and only from vaults charging the router a flat fee.
+    FlashLoan {
+        payload: MultiFlashLoanPayload,
+    },
+    /// Called by the vaults once they sent the borrowed funds to the router
+    VaultCallback {},
+    UpdateVaults {
+        to_add: Option<Vec<VaultInfo>>,
+        to_remove: Option<Vec<AssetInfo>>,
+    },
+    /// Allows the contract to borrow through the router
+    AddToWhitelist {
+        contract_addr: String,
+    },
+    /// Removes the contract from the borrowers allowed to borrow through the router
+    RemoveFromWhitelist {
+        contract_addr: String,
+    },
+    SetAdmin {
+        admin: String,
+    },
+    Callback(CallbackMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MultiFlashLoanPayload {
+    /// assets to borrow, each from the vault registered for it
+    pub requested_assets: Vec<Asset>,
+    /// msg the borrower is called with once all the assets are borrowed
+    pub callback: Binary,
+}
+
+// Modified from
+// https://github.com/CosmWasm/cosmwasm-plus/blob/v0.2.3/packages/cw20/src/receiver.rs#L15
+impl CallbackMsg {
+    pub fn to_cosmos_msg(&self, contract_addr: &Addr) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from(contract_addr),
+            msg: to_binary(&ExecuteMsg::Callback(self.clone()))?,
+            funds: vec![],
+        }))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CallbackMsg {
+    /// Checks the borrower returned the borrowed assets and pays back each vault
+    RepayLoans {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Vaults {},
+    /// Borrowers allowed to borrow through the router
+    Whitelist {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultsResponse {
+    pub vaults: Vec<VaultInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WhitelistResponse {
+    pub contracts: Vec<String>,
+}
+
+/// MigrateMsg allows a privileged contract administrator to run
+/// a migration on the contract. In this case it is just migrating
+/// from one terra code to the same code, but taking advantage of the
+/// migration step to set a new validator.
+///
+/// Note that the contract doesn't enforce permissions here, this is done
+/// by blockchain logic (in the future by blockchain governance)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
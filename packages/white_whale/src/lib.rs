@@ -6,14 +6,17 @@ pub mod denom;
 pub mod deposit_info;
 pub mod emissions;
 pub mod fee;
+pub mod flashloan_router;
 pub mod governance;
 pub mod luna_vault;
 pub mod memory;
 pub mod peg_arb;
 pub mod prism;
 pub mod query;
+pub mod snapshot;
 pub mod tax;
 pub mod tokenomics;
 pub mod treasury;
 pub mod ust_vault;
+pub mod vault;
 pub mod vesting;
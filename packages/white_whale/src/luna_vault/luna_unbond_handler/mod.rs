@@ -1,4 +1,5 @@
 pub mod msg;
+pub mod queries;
 
 pub const OWNER_KEY: &str = "owner";
 pub const EXPIRATION_TIME_KEY: &str = "expiration_time";
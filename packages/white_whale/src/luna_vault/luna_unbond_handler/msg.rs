@@ -4,6 +4,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::query::{anchor, prism};
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: Option<String>,
@@ -14,9 +16,9 @@ pub struct InstantiateMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Receive hook for bluna
+    /// Receive hook for bluna and cluna
     Receive(Cw20ReceiveMsg),
-    /// Withdraws unbonded luna from Anchor and send to the user
+    /// Withdraws unbonded luna from Anchor and Prism and send to the user
     WithdrawUnbonded { triggered_by_addr: String },
     /// Set the admin of the contract
     SetAdmin { admin: String },
@@ -39,7 +41,7 @@ pub enum Cw20HookMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum CallbackMsg {
-    /// To be called after withdrawing luna from Anchor so luna is sent back to the owner
+    /// To be called after withdrawing luna from Anchor and Prism so luna is sent back to the owner
     AfterWithdraw { triggered_by_addr: String },
 }
 
@@ -63,9 +65,20 @@ impl CallbackMsg {
 pub enum QueryMsg {
     /// Queries the state
     State {},
+    /// Queries the luna withdrawable from the Anchor and Prism hubs
     WithdrawableUnbonded {},
+    /// Queries the pending unbond requests on the Anchor and Prism hubs
     UnbondRequests {},
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondRequestsResponse {
+    pub address: String,
+    /// unbond requests on the Anchor bLuna hub
+    pub anchor_requests: anchor::UnbondRequest,
+    /// unbond requests on the Prism cLuna hub
+    pub prism_requests: prism::UnbondRequest,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
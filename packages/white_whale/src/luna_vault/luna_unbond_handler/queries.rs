@@ -0,0 +1,26 @@
+use cosmwasm_std::{to_binary, Addr, Deps, QueryRequest, StdResult, WasmQuery};
+
+use crate::luna_vault::luna_unbond_handler::msg::{QueryMsg, UnbondRequestsResponse};
+use crate::query::anchor::WithdrawableUnbondedResponse;
+
+/// Queries the luna the given unbond handler can withdraw from the Anchor and Prism hubs
+pub fn query_handler_withdrawable_unbonded(
+    deps: Deps,
+    unbond_handler_addr: &Addr,
+) -> StdResult<WithdrawableUnbondedResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: unbond_handler_addr.to_string(),
+        msg: to_binary(&QueryMsg::WithdrawableUnbonded {})?,
+    }))
+}
+
+/// Queries the pending unbond requests of the given unbond handler on the Anchor and Prism hubs
+pub fn query_handler_unbond_requests(
+    deps: Deps,
+    unbond_handler_addr: &Addr,
+) -> StdResult<UnbondRequestsResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: unbond_handler_addr.to_string(),
+        msg: to_binary(&QueryMsg::UnbondRequests {})?,
+    }))
+}
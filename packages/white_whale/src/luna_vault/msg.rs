@@ -10,6 +10,8 @@ use terra_rust_script_derive::CosmWasmContract;
 use terraswap::asset::{Asset, AssetInfo};
 
 use crate::fee::{Fee, VaultFee};
+use crate::vault::fees::{FeeTier, LoanFee};
+use crate::vault::pause::PausableOperation;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, CosmWasmContract)]
 pub struct InstantiateMsg {
@@ -38,8 +40,12 @@ pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
     /// Provide liquidity to the vault
     ProvideLiquidity { asset: Asset },
-    /// Send back unbonded luna to the user
+    /// Send back unbonded luna to the user, from all the unbond tickets that can be claimed
     WithdrawUnbonded {},
+    /// Send back unbonded luna to the user from the given unbond ticket
+    ClaimUnbondTicket { ticket_id: u64 },
+    /// Send back the luna reserved for the user from the vault's released Anchor/Prism unbonds
+    ClaimReservedUnbonds {},
     /// Withdraws the vault's unbonded bluna or cluna from anchor/prism
     WithdrawUnbondedFlashloan {},
     /// Sets the withdraw fee and flash loan fee
@@ -47,7 +53,11 @@ pub enum ExecuteMsg {
         flash_loan_fee: Option<Fee>,
         treasury_fee: Option<Fee>,
         commission_fee: Option<Fee>,
+        instant_withdraw_fee: Option<Fee>,
     },
+    /// Sets the fee charged on the vLuna price gains above the high-water mark, minted to the
+    /// treasury in vLuna. None disables it
+    SetPerformanceFee { fee: Option<Fee> },
     /// Set the admin of the contract
     SetAdmin { admin: String },
     /// Add provided contract to the whitelisted contracts
@@ -63,10 +73,49 @@ pub enum ExecuteMsg {
         whitelisted_contracts: Option<Vec<String>>,
         allow_non_whitelisted: Option<bool>,
     },
+    /// Sets the flashloan fee of the borrower, overriding the whitelist and fee tiers. None
+    /// removes the override
+    SetFeeOverride {
+        borrower: String,
+        fee: Option<LoanFee>,
+    },
+    /// Sets the flashloan fees of the non-whitelisted borrowers by their successful loans
+    SetFeeTiers { tiers: Vec<FeeTier> },
+    /// Sets the guardian allowed to pause the vault, or removes it if none
+    SetGuardian { guardian: Option<String> },
+    /// Pauses deposits, withdrawals or flashloans, by the guardian or the admin
+    Pause {
+        operation: PausableOperation,
+        reason: String,
+    },
+    /// Resumes a paused operation, by the admin only
+    Unpause { operation: PausableOperation },
     /// Execute a flashloan
     FlashLoan { payload: FlashLoanPayload },
-    /// Swaps the passive strategy token rewards for luna
-    SwapRewards {},
+    /// Swaps the passive strategy token rewards for luna, along the configured swap route.
+    /// Fails if the rewards would return less than minimum_receive luna
+    SwapRewards {
+        max_spread: Option<Decimal>,
+        minimum_receive: Option<Uint128>,
+    },
+    /// Sets how the passive strategy token rewards are swapped for luna. The rewards are swapped
+    /// through the Astroport router along the given route of intermediate assets, or directly on
+    /// the reward-luna pair if the route is empty. Harvests returning less than the dust threshold
+    /// are skipped
+    SetHarvestConfig {
+        router_address: Option<String>,
+        swap_route: Vec<AssetInfo>,
+        dust_threshold: Uint128,
+    },
+    /// Sets the passive strategies of the vault along with their target weights
+    SetStrategies { strategies: Vec<StrategyInfo> },
+    /// Moves luna between the passive strategies to match their target weights
+    Rebalance {},
+    /// Sets the deposit caps of the vault, in luna. None means uncapped
+    SetDepositCaps {
+        tvl_cap: Option<Uint128>,
+        per_address_cap: Option<Uint128>,
+    },
     /// Internal callback message
     Callback(CallbackMsg),
     /// Messages sent by unbond handlers to the vault
@@ -108,6 +157,56 @@ pub enum UnbondHandlerMsg {
     },
 }
 
+/// Liquid staking hub on which Luna is being unbonded
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnbondHub {
+    /// Anchor bLuna hub
+    Anchor,
+    /// Prism cLuna hub
+    Prism,
+}
+
+/// Pro-rata part of one of the vault's unbond request batches on a hub, reserved for a user
+/// that withdrew from the vault
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondReservation {
+    pub hub: UnbondHub,
+    pub batch_id: u64,
+    /// bLuna or cLuna amount of the batch
+    pub amount: Uint128,
+    /// luna the amount is worth at the hub exchange rate, i.e. what is paid out once released
+    pub luna_amount: Uint128,
+}
+
+/// Passive strategy the vault can deposit luna into
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PassiveStrategyKind {
+    /// Astroport bLuna-Luna stable pair LP, with ASTRO rewards from the generator
+    AstroportBlunaLp,
+    /// bLuna held by the vault, bought on the Astroport bLuna-Luna pair for the burn discount
+    HeldBluna,
+    /// LP of a cLuna-Luna pair implementing the Astroport pair interface, i.e. the Prism cLuna-Luna pair
+    ClunaLp { pair_address: String },
+}
+
+/// Passive strategy along with the share of the vault's passive luna it should hold
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StrategyInfo {
+    pub strategy: PassiveStrategyKind,
+    pub target_weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StrategyResponse {
+    pub strategy: PassiveStrategyKind,
+    pub target_weight: Decimal,
+    /// share of the value held by all the passive strategies
+    pub current_weight: Decimal,
+    pub value_in_luna: Uint128,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum UnbondActionReply {
@@ -138,12 +237,17 @@ pub enum VaultQueryMsg {
     PoolState {},
     State {},
     Fees {},
+    /// queries the performance fee of the vault along with its high-water mark
+    PerformanceFee {},
     EstimateWithdrawFee {
         amount: Uint128,
     },
     VaultValue {},
     LastBalance {},
     LastProfit {},
+    /// queries the profit and loss of the vault, of the last deposit, withdrawal or flashloan
+    /// and cumulatively
+    Pnl {},
     /// queries anchor for withdrawable unbonded amount
     WithdrawableUnbonded {
         address: String,
@@ -154,12 +258,85 @@ pub enum VaultQueryMsg {
     },
     /// queries the expiration time for unbond handlers
     UnbondHandlerExpirationTime {},
+    /// queries the vault unbonds reserved for the given address
+    ReservedUnbonds {
+        address: String,
+    },
+    /// queries the deposit caps of the vault
+    DepositCaps {},
+    /// queries how the passive strategy token rewards are swapped for luna
+    HarvestConfig {},
+    /// queries the cumulative rewards harvested by the vault, along with the harvests made after
+    /// the given harvest id
+    HarvestHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// simulates the luna returned for the given amount of vLuna, in each withdrawal mode
+    SimulateWithdraw {
+        amount: Uint128,
+    },
+    /// queries the unbond tickets of the given address, ordered by ticket id
+    UnbondTickets {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// queries the unbond handlers assigned to users, ordered by handler address
+    AssignedUnbondHandlers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// queries the unbond handlers available to be assigned to users
+    AvailableUnbondHandlers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// queries the unbond handlers expired as of the given timestamp, defaulting to the current
+    /// block time, and assigned to the given owner if any. Those can be liquidated with
+    /// LiquidateExpiredUnbondHandler
+    ExpiredUnbondHandlers {
+        expired_at: Option<u64>,
+        owner: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// queries the vLuna share price at the given timestamp, defaulting to the latest snapshot
+    SharePrice {
+        timestamp: Option<u64>,
+    },
+    /// queries the APR of the vault over the given trailing window, in seconds
+    Apr {
+        window: u64,
+    },
+    /// queries the share price snapshots recorded after the given snapshot id
+    Snapshots {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// queries the flashloan fee the borrower pays for the given amount
+    EffectiveFee {
+        borrower: String,
+        amount: Uint128,
+    },
+    /// queries the flashloan fee tiers of the non-whitelisted borrowers
+    FeeTiers {},
+    /// queries the successful flashloans of the borrower and the fees it paid
+    BorrowerStats {
+        borrower: String,
+    },
+    /// queries the guardian of the vault and the paused operations, with the reason they are
+    /// paused for
+    PauseInfo {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
+    /// Withdraws through an unbond handler, waiting for the Anchor/Prism unbonding period
     Unbond {},
+    /// Withdraws right away from the vault's liquid luna and passive strategies, for an extra fee
+    InstantWithdraw {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -168,6 +345,7 @@ pub struct PoolResponse {
     pub total_value_in_luna: Uint128,
     pub total_share: Uint128,
     pub liquidity_token: String,
+    pub strategies: Vec<StrategyResponse>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -177,12 +355,85 @@ pub struct DepositResponse {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ValueResponse {
+    /// total value with bLuna and cLuna valued at the Anchor and Prism hub exchange rates
     pub total_luna_value: Uint128,
+    /// total value assuming 1 bLuna = 1 cLuna = 1 Luna
+    pub nominal_luna_value: Uint128,
+    pub bluna_exchange_rate: Decimal,
+    pub cluna_exchange_rate: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct FeeResponse {
     pub fees: VaultFee,
+    pub instant_withdraw_fee: Fee,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnbondTicketStatus {
+    /// none of the unbonded luna was released by the hubs yet
+    Unbonding,
+    /// some unbonded luna was released by the hubs and can be claimed
+    Claimable,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondTicketResponse {
+    pub id: u64,
+    pub unbond_handler: String,
+    pub created_at: u64,
+    /// time at which the hubs are expected to release the unbonded luna
+    pub expected_release_time: u64,
+    pub status: UnbondTicketStatus,
+    /// bLuna and cLuna unbonding for the ticket on the Anchor and Prism hubs, not withdrawn yet
+    pub pending_amount: Uint128,
+    /// luna released by the hubs that can be claimed
+    pub withdrawable_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondTicketsResponse {
+    pub tickets: Vec<UnbondTicketResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondHandlerInfo {
+    pub address: String,
+    /// user the handler is assigned to, if any
+    pub owner: Option<String>,
+    /// unbond ticket the handler is backing, if any
+    pub ticket_id: Option<u64>,
+    /// time at which the handler expires, if it's assigned
+    pub expiration_time: Option<u64>,
+    /// bLuna and cLuna unbonding for the handler on the Anchor and Prism hubs, not withdrawn yet
+    pub pending_amount: Uint128,
+    /// luna released by the hubs that the handler can withdraw
+    pub withdrawable_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondHandlersResponse {
+    pub unbond_handlers: Vec<UnbondHandlerInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExpiredUnbondHandlersResponse {
+    pub unbond_handlers: Vec<UnbondHandlerInfo>,
+    /// last unbond handler read, to be passed as start_after to read the next page. None once all
+    /// the unbond handlers were read
+    pub last_unbond_handler: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateWithdrawResponse {
+    /// luna returned through the unbond handler, once the Anchor/Prism unbonding period is over
+    pub unbond_amount: Uint128,
+    /// luna returned right away by the instant withdrawal, after the slippage of selling the
+    /// passive strategies' bLuna or cLuna
+    pub instant_amount: Uint128,
+    /// extra fee charged by the instant withdrawal, in luna
+    pub instant_withdraw_fee: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -211,3 +462,53 @@ pub struct LastBalanceResponse {
 pub struct LastProfitResponse {
     pub last_profit: Uint128,
 }
+
+/// Limits on the luna the vault accepts. None means uncapped
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct DepositCaps {
+    /// max total value of the vault, deposit included
+    pub tvl_cap: Option<Uint128>,
+    /// max value of the vLuna held by a single address, deposit included
+    pub per_address_cap: Option<Uint128>,
+}
+
+/// How the passive strategy token rewards are swapped for luna
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct HarvestConfig {
+    /// Astroport router swapping the rewards along the swap route
+    pub router_address: Option<Addr>,
+    /// assets the rewards are swapped through before being swapped for luna, i.e. [UST] for ASTRO->UST->Luna
+    pub swap_route: Vec<AssetInfo>,
+    /// min amount of luna a harvest has to return not to be skipped
+    pub dust_threshold: Uint128,
+}
+
+/// Rewards harvested by the vault in a single SwapRewards
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HarvestRecord {
+    pub id: u64,
+    pub timestamp: u64,
+    /// reward tokens swapped for luna
+    pub rewards: Vec<Asset>,
+    /// luna the rewards were simulated to return when harvested
+    pub luna_return: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HarvestHistoryResponse {
+    /// number of harvests made by the vault
+    pub total_harvests: u64,
+    /// reward tokens swapped for luna over all the harvests
+    pub total_rewards: Vec<Asset>,
+    /// luna the rewards were simulated to return over all the harvests
+    pub total_luna_return: Uint128,
+    pub harvests: Vec<HarvestRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReservedUnbondsResponse {
+    /// unbonds reserved for the address that are still pending on the hubs
+    pub pending: Vec<UnbondReservation>,
+    /// amount of luna released by the hubs that can be claimed
+    pub claimable: Uint128,
+}
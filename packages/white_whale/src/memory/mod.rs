@@ -8,5 +8,6 @@ pub const LIST_SIZE_LIMIT: usize = 100;
 // Memory asset and contract ids
 pub const ANCHOR_BLUNA_HUB_ID: &str = "anchor_bluna_hub";
 pub const BLUNA_TOKEN_MEMORY_ID: &str = "bluna";
+pub const CLUNA_TOKEN_MEMORY_ID: &str = "cluna";
 pub const TREASURY_ADDRESS_ID: &str = "white_whale_treasury";
 pub const PRISM_CLUNA_HUB_ID: &str = "prism_cluna_hub";
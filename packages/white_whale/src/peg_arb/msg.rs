@@ -1,9 +1,10 @@
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, StdResult, WasmMsg};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use signed_integer::SignedInt;
 use terraswap::asset::{Asset, AssetInfo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -26,16 +27,29 @@ pub enum ExecuteMsg {
     BelowPegCallback {
         details: ArbDetails,
     },
+    /// Arbitrages the pair across the two pools of an AMM to AMM route
+    AmmArbCallback {
+        details: ArbDetails,
+    },
+    /// Runs the hops of a multi-hop route in sequence
+    RouteArbCallback {
+        details: ArbDetails,
+    },
     SetAdmin {
         admin: String,
     },
+    /// Adds pools by name, along with their address and the DEX they belong to
     UpdatePools {
-        to_add: Option<Vec<(String, String)>>,
+        to_add: Option<Vec<(String, String, DexKind)>>,
         to_remove: Option<Vec<String>>,
     },
     SetVault {
         vault: String,
     },
+    /// Sets the share of the arb profits paid to the keepers
+    SetKeeperShare {
+        share: Decimal,
+    },
     Callback(CallbackMsg),
 }
 
@@ -58,20 +72,126 @@ impl CallbackMsg {
 #[serde(rename_all = "snake_case")]
 pub enum CallbackMsg {
     AfterSuccessfulTradeCallback {},
+    /// Swaps the whole balance of the offer asset on the hop
+    SwapHop {
+        offer_asset: AssetInfo,
+        hop: Hop,
+        max_spread: Decimal,
+    },
+    /// Checks the profit of the arb after the vault fee covers the minimum profit
+    AssertMinimumProfit {
+        borrowed: Uint128,
+        min_profit: Uint128,
+    },
+    /// Sends the keeper share of the profit, after the vault fee, to the keeper
+    PayKeeperReward {
+        keeper: String,
+        borrowed: Uint128,
+    },
+    /// Checks the borrowed asset received since its balance was `balance_before` covers the
+    /// minimum receive
+    AssertMinimumReceive {
+        balance_before: Uint128,
+        minimum_receive: Uint128,
+    },
+    /// Pays the keeper share of the refund of a profit share loan, which is the profit left
+    /// after the vault fee, and sends the rest of it to the vault treasury
+    SettleProfitShare {
+        keeper: Option<String>,
+    },
+}
+
+/// DEX of a pool, which sets how to simulate and build the swaps on it
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DexKind {
+    Terraswap,
+    Astroport,
+}
+
+/// Trades the arb goes through
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArbRoute {
+    /// Between the Terra market module and the `pool_name` pool, in the direction set by
+    /// `above_peg`
+    MarketModule {},
+    /// Swaps the borrowed asset for `ask_denom` on the `pool_name` pool and back on the
+    /// `second_pool_name` pool
+    AmmToAmm {
+        second_pool_name: String,
+        ask_denom: String,
+    },
+    /// Swaps through the hops in order, starting and ending in the borrowed asset
+    Hops { hops: Vec<Hop> },
+}
+
+/// Swap of a multi-hop route, offering what the previous hop returned
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Hop {
+    /// Swaps on the named pool, for the `ask_asset` of the pair
+    Pool {
+        pool_name: String,
+        ask_asset: AssetInfo,
+    },
+    /// Swaps on the Terra market module for `ask_denom`
+    MarketModule { ask_denom: String },
+}
+
+impl Default for ArbRoute {
+    fn default() -> Self {
+        ArbRoute::MarketModule {}
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ArbDetails {
     pub asset: Asset,
     pub slippage: Decimal,
+    /// belief price of the swap on the `pool_name` pool
     pub belief_price: Decimal,
     pub pool_name: String,
+    #[serde(default)]
+    pub route: ArbRoute,
+    /// minimum profit after the vault fee, the arb isn't started if the simulation falls short
+    /// and fails if the arb does
+    pub min_profit: Option<Uint128>,
+    /// address receiving the keeper share of the profit, the caller of ExecuteArb by default
+    pub reward_address: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Config {},
+    /// Simulates the arb with the same prices and taxes as its execution
+    SimulateArb {
+        details: ArbDetails,
+        above_peg: bool,
+    },
+    /// Share of the arb profits paid to the keepers
+    KeeperShare {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KeeperShareResponse {
+    pub share: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateArbResponse {
+    /// asset received from each swap of the route, the last one being the borrowed asset
+    pub intermediate_amounts: Vec<Asset>,
+    /// balance of the borrowed asset after the route
+    pub final_balance: Uint128,
+    /// flashloan fee of the vault
+    pub vault_fee: Uint128,
+    /// what the vault gets back, less the loan and its fee, before the keeper reward. Negative
+    /// if the arb loses money
+    pub net_profit: SignedInt,
+    /// part of the net profit paid to the keeper
+    pub keeper_reward: Uint128,
 }
 
 /// MigrateMsg allows a privileged contract administrator to run
@@ -18,6 +18,8 @@ pub enum AnchorQuery {
     WithdrawableUnbonded {
         address: String,
     },
+    State {},
+    Parameters {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -39,6 +41,19 @@ pub struct WithdrawableUnbondedResponse {
     pub withdrawable: Uint128,
 }
 
+/// State of the Anchor bLuna hub, only the fields used to value bLuna are deserialized
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HubStateResponse {
+    pub bluna_exchange_rate: Decimal,
+}
+
+/// Parameters of the Anchor bLuna hub, only the fields used to time the unbonds are deserialized
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HubParametersResponse {
+    pub epoch_period: u64,
+    pub unbonding_period: u64,
+}
+
 pub fn query_aust_exchange_rate(
     env: Env,
     deps: Deps,
@@ -86,3 +101,24 @@ pub fn query_withdrawable_unbonded(
 
     Ok(response)
 }
+
+/// Queries the bLuna to Luna exchange rate on the Anchor bLuna hub
+pub fn query_bluna_exchange_rate(deps: Deps, bluna_hub_address: Addr) -> StdResult<Decimal> {
+    let response: HubStateResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: bluna_hub_address.to_string(),
+        msg: to_binary(&AnchorQuery::State {})?,
+    }))?;
+
+    Ok(response.bluna_exchange_rate)
+}
+
+/// Queries the epoch and unbonding periods of the Anchor bLuna hub, in seconds
+pub fn query_hub_parameters(
+    deps: Deps,
+    bluna_hub_address: Addr,
+) -> StdResult<HubParametersResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: bluna_hub_address.to_string(),
+        msg: to_binary(&AnchorQuery::Parameters {})?,
+    }))
+}
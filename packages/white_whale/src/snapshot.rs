@@ -0,0 +1,113 @@
+use cosmwasm_std::{Decimal, Fraction, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use signed_integer::SignedInt;
+
+pub const SECONDS_PER_YEAR: u64 = 31_536_000u64;
+
+/// State of a vault recorded at a deposit, withdrawal or flashloan, used to track its share price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultSnapshot {
+    /// sequence id of the snapshot, increasing with every snapshot recorded by the vault
+    pub id: u64,
+    pub timestamp: u64,
+    /// total value of the vault, in the vault's base asset
+    pub total_value: Uint128,
+    /// total supply of the vault LP token
+    pub total_share: Uint128,
+    /// profit made by the flashloan the snapshot was recorded at, zero for deposits and withdrawals
+    pub profit: Uint128,
+    /// change of the vault value since the previous deposit, withdrawal or flashloan
+    #[serde(default)]
+    pub pnl: SignedInt,
+}
+
+impl VaultSnapshot {
+    /// Value of one vault LP token in the vault's base asset, 1 if there are no LP tokens
+    pub fn share_price(&self) -> Decimal {
+        if self.total_share.is_zero() {
+            Decimal::one()
+        } else {
+            Decimal::from_ratio(self.total_value, self.total_share)
+        }
+    }
+}
+
+/// Annualizes the share price growth between the given snapshots, without compounding.
+/// Zero if the share price didn't grow
+pub fn compute_apr(start: &VaultSnapshot, end: &VaultSnapshot) -> Decimal {
+    let start_share_price = start.share_price();
+    let end_share_price = end.share_price();
+    if end.timestamp <= start.timestamp
+        || start_share_price.is_zero()
+        || end_share_price <= start_share_price
+    {
+        return Decimal::zero();
+    }
+
+    let growth = end_share_price - start_share_price;
+    Decimal::from_ratio(
+        Uint128::from(growth.numerator())
+            .multiply_ratio(SECONDS_PER_YEAR, end.timestamp - start.timestamp),
+        start_share_price.numerator(),
+    )
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharePriceResponse {
+    pub share_price: Decimal,
+    /// snapshot the share price was taken from
+    pub snapshot: VaultSnapshot,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AprResponse {
+    pub apr: Decimal,
+    /// snapshot the window starts at
+    pub start: VaultSnapshot,
+    /// snapshot the window ends at
+    pub end: VaultSnapshot,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SnapshotsResponse {
+    pub snapshots: Vec<VaultSnapshot>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(timestamp: u64, total_value: u128, total_share: u128) -> VaultSnapshot {
+        VaultSnapshot {
+            id: 0,
+            timestamp,
+            total_value: Uint128::new(total_value),
+            total_share: Uint128::new(total_share),
+            profit: Uint128::zero(),
+            pnl: SignedInt::zero(),
+        }
+    }
+
+    #[test]
+    fn test_share_price() {
+        assert_eq!(snapshot(0, 150, 100).share_price(), Decimal::percent(150));
+        assert_eq!(snapshot(0, 0, 0).share_price(), Decimal::one());
+    }
+
+    #[test]
+    fn test_compute_apr() {
+        // 5% growth over half a year
+        let start = snapshot(0, 1000, 1000);
+        let end = snapshot(SECONDS_PER_YEAR / 2, 1050, 1000);
+        assert_eq!(compute_apr(&start, &end), Decimal::percent(10));
+
+        // deposits and withdrawals don't move the share price
+        let end = snapshot(SECONDS_PER_YEAR / 2, 2100, 2000);
+        assert_eq!(compute_apr(&start, &end), Decimal::percent(10));
+
+        // losses are reported as no yield
+        let end = snapshot(SECONDS_PER_YEAR, 900, 1000);
+        assert_eq!(compute_apr(&start, &end), Decimal::zero());
+    }
+}
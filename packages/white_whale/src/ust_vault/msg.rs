@@ -1,4 +1,6 @@
 use crate::fee::{Fee, VaultFee};
+use crate::vault::fees::{FeeTier, LoanFee};
+use crate::vault::pause::PausableOperation;
 use cosmwasm_std::{
     to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg,
 };
@@ -11,8 +13,8 @@ use terraswap::asset::{Asset, AssetInfo};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, CosmWasmContract)]
 pub struct InstantiateMsg {
-    pub anchor_money_market_address: String,
-    pub aust_address: String,
+    /// Yield adapters the idle funds are deposited into. Without any, idle funds stay in the vault
+    pub yield_adapters: Vec<YieldAdapterInfo>,
     pub treasury_addr: String,
     pub asset_info: AssetInfo,
     pub token_code_id: u64,
@@ -27,18 +29,24 @@ pub struct InstantiateMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, CosmWasmContract)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Receive hook for the liquidity token
+    /// Receive hook for the liquidity token and the cw20 base asset
     Receive(Cw20ReceiveMsg),
-    /// Provide liquidity to the vault
+    /// Provide liquidity to the vault, for native base assets
     ProvideLiquidity { asset: Asset },
-    /// Set minimum amount of stables held liquid (not deposited into anchor)
+    /// Set minimum amount of stables held liquid (not deposited into the yield adapters)
     SetStableCap { stable_cap: Uint128 },
+    /// Sets the yield adapters the idle funds are deposited into. Adapters can only be removed
+    /// once the vault's position in them is redeemed
+    SetYieldAdapters { adapters: Vec<YieldAdapterInfo> },
     /// Sets the withdraw fee and flash loan fee
     SetFee {
         flash_loan_fee: Option<Fee>,
         treasury_fee: Option<Fee>,
         commission_fee: Option<Fee>,
     },
+    /// Sets the fee charged on the share price gains above the high-water mark, minted to the
+    /// treasury in vault LP tokens. None disables it
+    SetPerformanceFee { fee: Option<Fee> },
     /// Set the admin of the contract
     SetAdmin { admin: String },
     /// Add provided contract to the whitelisted contracts
@@ -46,17 +54,69 @@ pub enum ExecuteMsg {
     /// Remove provided contract from the whitelisted contracts
     RemoveFromWhitelist { contract_addr: String },
     /// Update the interal State struct
-    UpdateState {
-        anchor_money_market_address: Option<String>,
-        aust_address: Option<String>,
-        allow_non_whitelisted: Option<bool>,
+    UpdateState { allow_non_whitelisted: Option<bool> },
+    /// Sets the flashloan fee of the borrower, overriding the whitelist and fee tiers. None
+    /// removes the override
+    SetFeeOverride {
+        borrower: String,
+        fee: Option<LoanFee>,
     },
+    /// Sets the flashloan fees of the non-whitelisted borrowers by their successful loans
+    SetFeeTiers { tiers: Vec<FeeTier> },
+    /// Sets the guardian allowed to pause the vault, or removes it if none
+    SetGuardian { guardian: Option<String> },
+    /// Pauses deposits, withdrawals or flashloans, by the guardian or the admin
+    Pause {
+        operation: PausableOperation,
+        reason: String,
+    },
+    /// Resumes a paused operation, by the admin only
+    Unpause { operation: PausableOperation },
     /// Execute a flashloan
     FlashLoan { payload: FlashLoanPayload },
     /// Internal callback message
     Callback(CallbackMsg),
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Provide liquidity to the vault, for cw20 base assets
+    ProvideLiquidity {},
+    /// Burn the vault LP tokens sent to withdraw the base asset
+    WithdrawLiquidity {},
+    /// Swaps are not supported, kept for compatibility with the terraswap pair hook
+    Swap {
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+/// Money market or position the vault deposits its idle funds into to earn a yield
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum YieldAdapterKind {
+    /// Anchor money market, the deposits are held as aUST. Only takes UST
+    Anchor {
+        money_market_address: String,
+        aust_address: String,
+    },
+}
+
+/// Yield adapter along with how the vault uses it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct YieldAdapterInfo {
+    pub adapter: YieldAdapterKind,
+    /// share of the idle funds deposited into the adapter
+    pub weight: Decimal,
+    /// adapters are redeemed from by ascending priority for flashloans and withdrawals
+    pub redeem_priority: u32,
+    /// idle funds are deposited into the adapter once the liquid stables exceed the stable cap
+    /// by this ratio, i.e. 1.5 deposits above 150% of the stable cap
+    pub deposit_threshold: Decimal,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct FlashLoanPayload {
     pub requested_asset: Asset,
@@ -101,15 +161,57 @@ pub enum VaultQueryMsg {
     PoolState {},
     State {},
     Fees {},
-    EstimateWithdrawFee { amount: Uint128 },
+    /// Performance fee of the vault along with its high-water mark
+    PerformanceFee {},
+    EstimateWithdrawFee {
+        amount: Uint128,
+    },
+    /// Vault LP tokens minted for a deposit of the given amount of the base asset
+    SimulateDeposit {
+        amount: Uint128,
+    },
+    /// Itemized base asset returned for burning the given amount of vault LP tokens
+    SimulateWithdraw {
+        lp_amount: Uint128,
+    },
     VaultValue {},
     LastBalance {},
     LastProfit {},
+    /// Profit and loss of the vault, of the last deposit, withdrawal or flashloan and cumulatively
+    Pnl {},
+    /// Yield adapters of the vault, along with the value of the vault's position in each
+    YieldAdapters {},
+    /// Share price of the vault LP token at the given timestamp, defaulting to the latest snapshot
+    SharePrice {
+        timestamp: Option<u64>,
+    },
+    /// APR of the vault over the given trailing window, in seconds
+    Apr {
+        window: u64,
+    },
+    /// Share price snapshots recorded after the given snapshot id
+    Snapshots {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Flashloan fee the borrower pays for the given amount
+    EffectiveFee {
+        borrower: String,
+        amount: Uint128,
+    },
+    FeeTiers {},
+    /// Successful flashloans of the borrower and the fees it paid
+    BorrowerStats {
+        borrower: String,
+    },
+    /// Guardian of the vault and the paused operations, with the reason they are paused for
+    PauseInfo {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PoolResponse {
-    pub assets: [Asset; 2],
+    /// base asset, followed by the positions held in the yield adapters
+    pub assets: Vec<Asset>,
     pub total_value_in_ust: Uint128,
     pub total_share: Uint128,
     pub liquidity_token: String,
@@ -136,16 +238,57 @@ pub struct EstimateDepositFeeResponse {
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct EstimateWithdrawFeeResponse {
-    pub fee: Vec<Coin>,
+    pub fee: Vec<Asset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateDepositResponse {
+    /// vault LP tokens minted for the deposit
+    pub lp_amount: Uint128,
+    /// value of the vault before the deposit
+    pub total_value: Uint128,
+    /// supply of the vault LP token before the deposit
+    pub total_share: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateWithdrawResponse {
+    /// vault LP tokens sent to the treasury as withdraw fee
+    pub treasury_fee: Uint128,
+    /// value of the burnt LP tokens, before taxes
+    pub withdraw_value: Uint128,
+    /// redeemed from the yield adapters to cover the withdrawal
+    pub redeemed_from_adapters: Uint128,
+    /// tax on the redemption from the yield adapters
+    pub redeem_tax: Uint128,
+    /// tax on the transfer to the user, for native base assets
+    pub transfer_tax: Uint128,
+    /// base asset received by the user
+    pub return_amount: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct StateResponse {
-    pub anchor_money_market_address: String,
-    pub aust_address: String,
     pub allow_non_whitelisted: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct YieldAdapterResponse {
+    pub adapter: YieldAdapterKind,
+    pub weight: Decimal,
+    pub redeem_priority: u32,
+    pub deposit_threshold: Decimal,
+    /// value of the vault's position in the adapter, in the base asset
+    pub value: Uint128,
+    /// base asset returned per unit of the position held
+    pub exchange_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct YieldAdaptersResponse {
+    pub adapters: Vec<YieldAdapterResponse>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct LastBalanceResponse {
     pub last_balance: Uint128,
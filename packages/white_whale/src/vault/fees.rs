@@ -0,0 +1,341 @@
+use cosmwasm_std::{Addr, Decimal, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::fee::Fee;
+use crate::vault::flashloan::{FlashLoanConfig, FlashLoanError};
+
+/// Fee charged to a borrower for a flashloan
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LoanFee {
+    /// Share of the borrowed amount, to be returned on top of the loan
+    Flat { fee: Fee },
+    /// The borrower returns all the proceeds of the trade, the vault keeps this share of the
+    /// profit and sends the rest back. The trade has to make a profit
+    ProfitShare { share: Fee },
+}
+
+impl LoanFee {
+    /// Fee to be returned on top of the borrowed amount. Profit shares are only known after the
+    /// trade, nothing is due upfront
+    pub fn upfront_fee(&self, amount: Uint128) -> Uint128 {
+        match self {
+            LoanFee::Flat { fee } => fee.compute(amount),
+            LoanFee::ProfitShare { .. } => Uint128::zero(),
+        }
+    }
+
+    /// Fees have to be lower than 100%, like the other vault fees
+    fn is_valid(&self) -> bool {
+        match self {
+            LoanFee::Flat { fee } => fee.share < Decimal::one(),
+            LoanFee::ProfitShare { share } => share.share < Decimal::one(),
+        }
+    }
+}
+
+/// Fee for the borrowers having made at least `min_loans` successful flashloans
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeTier {
+    pub min_loans: u64,
+    pub fee: LoanFee,
+}
+
+/// Flashloans of a borrower, for the fee tiers and reporting
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct BorrowerStats {
+    pub successful_loans: u64,
+    /// total amount borrowed
+    pub volume: Uint128,
+    /// flat fees and profit shares paid to the vault
+    pub fees_paid: Uint128,
+}
+
+/// Flashloan being executed, settled in the after trade callback
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OngoingLoan {
+    pub borrower: Addr,
+    pub amount: Uint128,
+    pub fee: LoanFee,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EffectiveFeeResponse {
+    pub fee: LoanFee,
+    /// fee to be returned on top of the requested amount
+    pub loan_fee: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeTiersResponse {
+    pub tiers: Vec<FeeTier>,
+}
+
+/// Fees set for specific borrowers, by borrower address
+pub const FEE_OVERRIDES: Map<&str, LoanFee> = Map::new("fee_overrides");
+/// Sorted by ascending min_loans
+pub const FEE_TIERS: Item<Vec<FeeTier>> = Item::new("fee_tiers");
+pub const BORROWER_STATS: Map<&str, BorrowerStats> = Map::new("borrower_stats");
+pub const ONGOING_LOAN: Item<OngoingLoan> = Item::new("ongoing_loan");
+
+/// Fee the borrower pays for a flashloan. Borrowers with a fee override pay it, whitelisted
+/// contracts borrow for free and the other borrowers, if allowed, pay the fee tier of their
+/// successful loans or the flash loan fee of the vault
+pub fn effective_fee(
+    storage: &dyn Storage,
+    config: &FlashLoanConfig,
+    borrower: &Addr,
+) -> Result<LoanFee, FlashLoanError> {
+    if let Some(fee) = FEE_OVERRIDES.may_load(storage, borrower.as_str())? {
+        return Ok(fee);
+    }
+    if config.whitelisted_contracts.contains(borrower) {
+        return Ok(LoanFee::Flat {
+            fee: Fee {
+                share: Decimal::zero(),
+            },
+        });
+    }
+    if !config.allow_non_whitelisted {
+        return Err(FlashLoanError::NotWhitelisted {});
+    }
+
+    let successful_loans = load_borrower_stats(storage, borrower)?.successful_loans;
+    let tier = FEE_TIERS
+        .may_load(storage)?
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .find(|tier| tier.min_loans <= successful_loans);
+    Ok(match tier {
+        Some(tier) => tier.fee,
+        None => LoanFee::Flat {
+            fee: config.flash_loan_fee.clone(),
+        },
+    })
+}
+
+/// Sets the fee of the borrower, or removes its override if none
+pub fn set_fee_override(
+    storage: &mut dyn Storage,
+    borrower: &Addr,
+    fee: Option<LoanFee>,
+) -> Result<(), FlashLoanError> {
+    match fee {
+        Some(fee) => {
+            if !fee.is_valid() {
+                return Err(FlashLoanError::InvalidFee {});
+            }
+            FEE_OVERRIDES.save(storage, borrower.as_str(), &fee)?;
+        }
+        None => FEE_OVERRIDES.remove(storage, borrower.as_str()),
+    }
+    Ok(())
+}
+
+/// Replaces the fee tiers, which need strictly ascending min_loans
+pub fn set_fee_tiers(storage: &mut dyn Storage, tiers: Vec<FeeTier>) -> Result<(), FlashLoanError> {
+    if tiers.iter().any(|tier| !tier.fee.is_valid()) {
+        return Err(FlashLoanError::InvalidFee {});
+    }
+    if tiers
+        .windows(2)
+        .any(|pair| pair[0].min_loans >= pair[1].min_loans)
+    {
+        return Err(FlashLoanError::InvalidFeeTiers {});
+    }
+    FEE_TIERS.save(storage, &tiers)?;
+    Ok(())
+}
+
+pub fn load_borrower_stats(storage: &dyn Storage, borrower: &Addr) -> StdResult<BorrowerStats> {
+    Ok(BORROWER_STATS
+        .may_load(storage, borrower.as_str())?
+        .unwrap_or_default())
+}
+
+/// Records a successful flashloan of the borrower
+pub fn record_loan(
+    storage: &mut dyn Storage,
+    borrower: &Addr,
+    amount: Uint128,
+    fee_paid: Uint128,
+) -> StdResult<()> {
+    let mut stats = load_borrower_stats(storage, borrower)?;
+    stats.successful_loans += 1;
+    stats.volume += amount;
+    stats.fees_paid += fee_paid;
+    BORROWER_STATS.save(storage, borrower.as_str(), &stats)
+}
+
+pub fn query_effective_fee(
+    storage: &dyn Storage,
+    config: &FlashLoanConfig,
+    borrower: &Addr,
+    amount: Uint128,
+) -> StdResult<EffectiveFeeResponse> {
+    let fee = effective_fee(storage, config, borrower).map_err(|err| match err {
+        FlashLoanError::Std(err) => err,
+        err => StdError::generic_err(err.to_string()),
+    })?;
+    Ok(EffectiveFeeResponse {
+        loan_fee: fee.upfront_fee(amount),
+        fee,
+    })
+}
+
+pub fn query_fee_tiers(storage: &dyn Storage) -> StdResult<FeeTiersResponse> {
+    Ok(FeeTiersResponse {
+        tiers: FEE_TIERS.may_load(storage)?.unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockStorage;
+
+    use super::*;
+
+    fn flat(percent: u64) -> LoanFee {
+        LoanFee::Flat {
+            fee: Fee {
+                share: Decimal::percent(percent),
+            },
+        }
+    }
+
+    fn config(allow_non_whitelisted: bool) -> FlashLoanConfig {
+        FlashLoanConfig {
+            whitelisted_contracts: vec![Addr::unchecked("whitelisted")],
+            allow_non_whitelisted,
+            flash_loan_fee: Fee {
+                share: Decimal::percent(3),
+            },
+            commission_fee: Fee {
+                share: Decimal::percent(10),
+            },
+            treasury_addr: Addr::unchecked("treasury"),
+        }
+    }
+
+    #[test]
+    fn test_effective_fee() {
+        let mut storage = MockStorage::new();
+        let borrower = Addr::unchecked("borrower");
+
+        assert_eq!(
+            effective_fee(&storage, &config(false), &Addr::unchecked("whitelisted")),
+            Ok(flat(0))
+        );
+        assert_eq!(
+            effective_fee(&storage, &config(false), &borrower),
+            Err(FlashLoanError::NotWhitelisted {})
+        );
+        assert_eq!(
+            effective_fee(&storage, &config(true), &borrower),
+            Ok(flat(3))
+        );
+
+        // the fee decreases with the successful loans
+        set_fee_tiers(
+            &mut storage,
+            vec![
+                FeeTier {
+                    min_loans: 2,
+                    fee: flat(2),
+                },
+                FeeTier {
+                    min_loans: 5,
+                    fee: flat(1),
+                },
+            ],
+        )
+        .unwrap();
+        for _ in 0..3 {
+            record_loan(&mut storage, &borrower, Uint128::new(100), Uint128::new(1)).unwrap();
+        }
+        assert_eq!(
+            effective_fee(&storage, &config(true), &borrower),
+            Ok(flat(2))
+        );
+        assert_eq!(
+            load_borrower_stats(&storage, &borrower).unwrap(),
+            BorrowerStats {
+                successful_loans: 3,
+                volume: Uint128::new(300),
+                fees_paid: Uint128::new(3),
+            }
+        );
+
+        // overrides apply even if non-whitelisted borrowers aren't allowed
+        let profit_share = LoanFee::ProfitShare {
+            share: Fee {
+                share: Decimal::percent(50),
+            },
+        };
+        set_fee_override(&mut storage, &borrower, Some(profit_share.clone())).unwrap();
+        assert_eq!(
+            query_effective_fee(&storage, &config(false), &borrower, Uint128::new(100)),
+            Ok(EffectiveFeeResponse {
+                fee: profit_share,
+                loan_fee: Uint128::zero(),
+            })
+        );
+        set_fee_override(&mut storage, &borrower, None).unwrap();
+        assert_eq!(
+            effective_fee(&storage, &config(true), &borrower),
+            Ok(flat(2))
+        );
+    }
+
+    #[test]
+    fn test_invalid_fees() {
+        let mut storage = MockStorage::new();
+        assert_eq!(
+            set_fee_override(&mut storage, &Addr::unchecked("borrower"), Some(flat(101))),
+            Err(FlashLoanError::InvalidFee {})
+        );
+
+        // fees have to be lower than 100%
+        assert_eq!(
+            set_fee_override(&mut storage, &Addr::unchecked("borrower"), Some(flat(100))),
+            Err(FlashLoanError::InvalidFee {})
+        );
+        let profit_share = LoanFee::ProfitShare {
+            share: Fee {
+                share: Decimal::percent(100),
+            },
+        };
+        assert_eq!(
+            set_fee_override(
+                &mut storage,
+                &Addr::unchecked("borrower"),
+                Some(profit_share)
+            ),
+            Err(FlashLoanError::InvalidFee {})
+        );
+        assert_eq!(
+            set_fee_override(&mut storage, &Addr::unchecked("borrower"), Some(flat(99))),
+            Ok(())
+        );
+
+        assert_eq!(
+            set_fee_tiers(
+                &mut storage,
+                vec![
+                    FeeTier {
+                        min_loans: 5,
+                        fee: flat(2),
+                    },
+                    FeeTier {
+                        min_loans: 5,
+                        fee: flat(1),
+                    },
+                ],
+            ),
+            Err(FlashLoanError::InvalidFeeTiers {})
+        );
+    }
+}
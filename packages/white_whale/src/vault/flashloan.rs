@@ -0,0 +1,778 @@
+use cosmwasm_std::{
+    attr, Addr, Attribute, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Storage, Uint128, WasmMsg,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use terraswap::asset::{Asset, AssetInfo};
+use thiserror::Error;
+
+use signed_integer::SignedInt;
+
+use crate::fee::Fee;
+use crate::tax::into_msg_without_tax;
+use crate::vault::fees::{effective_fee, record_loan, LoanFee, OngoingLoan, ONGOING_LOAN};
+
+/// Added to the tax buffer of the loans to cover rounding errors
+pub const ROUNDING_ERR_COMPENSATION: u32 = 10u32;
+
+/// Value of the vault before the ongoing flashloan and profit made by the last one, along with
+/// the profit and loss of the vault between its deposits, withdrawals and flashloans
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProfitCheck {
+    pub last_balance: Uint128,
+    pub last_profit: Uint128,
+    /// value of the vault after the last deposit, withdrawal or flashloan
+    #[serde(default)]
+    pub last_value: Uint128,
+    /// change of the vault value since the event before the last one, including the yield or
+    /// losses of the strategies
+    #[serde(default)]
+    pub last_pnl: SignedInt,
+    #[serde(default)]
+    pub cumulative_pnl: SignedInt,
+}
+
+impl ProfitCheck {
+    /// The value before the trade is only set while a flashloan is ongoing
+    pub fn is_loan_ongoing(&self) -> bool {
+        !self.last_balance.is_zero()
+    }
+
+    /// Records the PnL made since the last event, i.e. the change between the value after the
+    /// last event and the value before this one, which isn't moved by deposits and withdrawals.
+    /// Nothing is recorded at the first event
+    pub fn record_pnl(
+        &mut self,
+        value_before: Uint128,
+        value_after: Uint128,
+    ) -> StdResult<SignedInt> {
+        self.last_pnl = if self.last_value.is_zero() {
+            SignedInt::zero()
+        } else {
+            SignedInt::from_subtraction(value_before, self.last_value)
+        };
+        self.cumulative_pnl = self.cumulative_pnl.checked_add(self.last_pnl)?;
+        self.last_value = value_after;
+        Ok(self.last_pnl)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PnlResponse {
+    pub last_pnl: SignedInt,
+    pub cumulative_pnl: SignedInt,
+    /// value of the vault the next PnL is measured from
+    pub last_value: Uint128,
+}
+
+impl From<ProfitCheck> for PnlResponse {
+    fn from(profit_check: ProfitCheck) -> Self {
+        PnlResponse {
+            last_pnl: profit_check.last_pnl,
+            cumulative_pnl: profit_check.cumulative_pnl,
+            last_value: profit_check.last_value,
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum FlashLoanError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("The requesting contract is not whitelisted.")]
+    NotWhitelisted {},
+
+    #[error("Not enough funds to perform trade")]
+    Broke {},
+
+    #[error("Last balance is non-zero, you can only call this function once.")]
+    Nonzero {},
+
+    #[error("Cancel losing trade.")]
+    CancelLosingTrade {},
+
+    #[error("Call is not a callback!")]
+    NotCallback {},
+
+    #[error("You can not deposit into the vault during a flashloan.")]
+    DepositDuringLoan {},
+
+    #[error("The provided fee is invalid")]
+    InvalidFee {},
+
+    #[error("The fee tiers need strictly ascending minimum loans.")]
+    InvalidFeeTiers {},
+}
+
+/// Who can borrow from the vault and the fees charged on the loans
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlashLoanConfig {
+    pub whitelisted_contracts: Vec<Addr>,
+    pub allow_non_whitelisted: bool,
+    pub flash_loan_fee: Fee,
+    pub commission_fee: Fee,
+    pub treasury_addr: Addr,
+}
+
+/// Outcome of a profitable flashloan, passed to the vault to settle it
+#[derive(Clone, Debug, PartialEq)]
+pub struct TradeSettlement {
+    /// value of the vault after the trade, commission and refund included
+    pub total_value: Uint128,
+    /// profit kept by the vault
+    pub profit: Uint128,
+    /// commission on the profit sent to the treasury
+    pub commission: Uint128,
+    /// share of the profit sent back to the borrower, for profit share loans
+    pub refund: Uint128,
+}
+
+/// Vault lending its funds through flashloans. The engine handles the whitelist, the fees, the
+/// reentrancy and the profit check, the vault values its funds and settles the trades
+pub trait FlashLoanVault {
+    type Error: From<StdError> + From<FlashLoanError>;
+
+    fn config(&self, deps: Deps) -> StdResult<FlashLoanConfig>;
+    fn load_profit_check(&self, storage: &dyn Storage) -> StdResult<ProfitCheck>;
+    fn save_profit_check(
+        &self,
+        storage: &mut dyn Storage,
+        profit_check: &ProfitCheck,
+    ) -> StdResult<()>;
+    /// Base asset of the vault, the commissions are paid in it
+    fn base_asset_info(&self, deps: Deps) -> Result<AssetInfo, Self::Error>;
+    /// Checks the requested asset can be borrowed from the vault
+    fn assert_loan_asset(&self, deps: Deps, asset_info: &AssetInfo) -> Result<(), Self::Error>;
+    /// Total value of the vault, in its base asset
+    fn total_value(&self, deps: Deps, env: &Env) -> Result<Uint128, Self::Error>;
+    /// Makes the requested amount liquid before it is lent, e.g. by withdrawing it from the
+    /// yield backends of the vault
+    fn prepare_loan(
+        &self,
+        deps: Deps,
+        env: &Env,
+        amount: Uint128,
+        tax_buffer: Uint128,
+    ) -> Result<Response, Self::Error>;
+    /// Settles a profitable trade, e.g. by depositing the funds back into the yield backends
+    fn settle_trade(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        settlement: &TradeSettlement,
+    ) -> Result<Response, Self::Error>;
+    /// Message calling the vault back once the borrower is done
+    fn after_trade_msg(&self, env: &Env, loan_fee: Uint128) -> StdResult<CosmosMsg>;
+}
+
+/// Lends the requested asset to the sender and calls it back with the given payload. The vault
+/// checks the trade was profitable in the after trade callback
+pub fn handle_flashloan<V: FlashLoanVault>(
+    vault: &V,
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    requested_asset: Asset,
+    callback: Binary,
+) -> Result<Response, V::Error> {
+    let config = vault.config(deps.as_ref())?;
+    // Check if requested asset is base token of vault
+    vault.assert_loan_asset(deps.as_ref(), &requested_asset.info)?;
+
+    // Check if sender is allowed to borrow and get its fee
+    let fee = effective_fee(deps.storage, &config, &info.sender)?;
+
+    // Do we have enough funds?
+    let total_value = vault.total_value(deps.as_ref(), &env)?;
+    // Max tax buffer will be 2 transfers of the borrowed assets
+    // Yield backend -> Vault -> Caller
+    let tax_buffer = Uint128::from(2u32) * requested_asset.compute_tax(&deps.querier)?
+        + Uint128::from(ROUNDING_ERR_COMPENSATION);
+    if total_value < requested_asset.amount + tax_buffer {
+        return Err(FlashLoanError::Broke {}.into());
+    }
+
+    // Make the requested funds liquid if needed
+    let preparation =
+        vault.prepare_loan(deps.as_ref(), &env, requested_asset.amount, tax_buffer)?;
+    let mut response = Response::new()
+        .add_attribute("Action", "Flashloan")
+        .add_submessages(preparation.messages)
+        .add_attributes(preparation.attributes);
+
+    // Flat fees are returned with the loan, profit shares are settled after the trade
+    let loan_fee = fee.upfront_fee(requested_asset.amount);
+    ONGOING_LOAN.save(
+        deps.storage,
+        &OngoingLoan {
+            borrower: info.sender.clone(),
+            amount: requested_asset.amount,
+            fee,
+        },
+    )?;
+
+    // Construct transfer of funds msg, tax is accounted for by buffer
+    let loan_msg = into_msg_without_tax(requested_asset, info.sender.clone())?;
+    // Construct return call with received binary
+    let return_call = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: info.sender.into(),
+        msg: callback,
+        funds: vec![],
+    });
+    response = response.add_message(loan_msg).add_message(return_call);
+
+    // Sets the current value of the vault and save logs
+    response = response.add_attributes(before_trade(vault, deps.branch(), &env)?);
+
+    encapsulate_payload(vault, &env, response, loan_fee)
+}
+
+/// Resets the last profit and stores the value of the vault before the trade
+pub fn before_trade<V: FlashLoanVault>(
+    vault: &V,
+    deps: DepsMut,
+    env: &Env,
+) -> Result<Vec<Attribute>, V::Error> {
+    let mut profit_check = vault.load_profit_check(deps.storage)?;
+
+    // last_balance call can not be reset until after the loan.
+    if profit_check.is_loan_ongoing() {
+        return Err(FlashLoanError::Nonzero {}.into());
+    }
+
+    profit_check.last_profit = Uint128::zero();
+    profit_check.last_balance = vault.total_value(deps.as_ref(), env)?;
+    vault.save_profit_check(deps.storage, &profit_check)?;
+
+    Ok(vec![attr(
+        "value before trade: ",
+        profit_check.last_balance.to_string(),
+    )])
+}
+
+/// Checks the value of the vault increased at least by the loan fee, otherwise cancels
+/// everything. Profit share loans get the borrower's share of the profit back. The vault then
+/// settles the trade and the commission is sent to the treasury
+pub fn after_trade<V: FlashLoanVault>(
+    vault: &V,
+    mut deps: DepsMut,
+    env: Env,
+    loan_fee: Uint128,
+) -> Result<Response, V::Error> {
+    let mut profit_check = vault.load_profit_check(deps.storage)?;
+    let total_value = vault.total_value(deps.as_ref(), &env)?;
+
+    if total_value < profit_check.last_balance + loan_fee {
+        return Err(FlashLoanError::CancelLosingTrade {}.into());
+    }
+    let trade_profit = total_value - profit_check.last_balance;
+
+    // The loan is only unknown if the callback is called outside of a flashloan
+    let loan = ONGOING_LOAN.may_load(deps.storage)?;
+    ONGOING_LOAN.remove(deps.storage);
+    let (profit, refund) = match &loan {
+        Some(OngoingLoan {
+            fee: LoanFee::ProfitShare { share },
+            ..
+        }) => {
+            if trade_profit.is_zero() {
+                return Err(FlashLoanError::CancelLosingTrade {}.into());
+            }
+            let profit = share.compute(trade_profit);
+            (profit, trade_profit - profit)
+        }
+        _ => (trade_profit, Uint128::zero()),
+    };
+    if let Some(loan) = &loan {
+        let fee_paid = match loan.fee {
+            LoanFee::Flat { .. } => loan_fee,
+            LoanFee::ProfitShare { .. } => profit,
+        };
+        record_loan(deps.storage, &loan.borrower, loan.amount, fee_paid)?;
+    }
+
+    profit_check.last_profit = profit;
+    profit_check.last_balance = Uint128::zero();
+    vault.save_profit_check(deps.storage, &profit_check)?;
+
+    // Commission on the profit leaves the vault
+    let config = vault.config(deps.as_ref())?;
+    let commission = config.commission_fee.compute(profit);
+    let mut response = vault.settle_trade(
+        deps.branch(),
+        &env,
+        &TradeSettlement {
+            total_value,
+            profit,
+            commission,
+            refund,
+        },
+    )?;
+
+    let base_asset_info = vault.base_asset_info(deps.as_ref())?;
+    if let Some(loan) = loan.filter(|_| !refund.is_zero()) {
+        // Send the borrower's share of the profit back
+        let refund_asset = Asset {
+            info: base_asset_info.clone(),
+            amount: refund,
+        };
+        response = response
+            .add_message(refund_asset.into_msg(&deps.querier, loan.borrower)?)
+            .add_attribute("refund_amount", refund.to_string());
+    }
+
+    let commission_asset = Asset {
+        info: base_asset_info,
+        amount: commission,
+    };
+    let commission_msg = commission_asset.into_msg(&deps.querier, config.treasury_addr)?;
+
+    Ok(response
+        // Send commission of profit to Treasury
+        .add_message(commission_msg)
+        .add_attribute("commission_amount", commission.to_string())
+        .add_attribute("value after trade: ", total_value.to_string()))
+}
+
+/// Wraps the loan messages, calling the vault back after the borrower actions.
+/// This prevents callers from doing unprofitable actions with the vault funds and makes sure
+/// the funds are returned by the borrower.
+pub fn encapsulate_payload<V: FlashLoanVault>(
+    vault: &V,
+    env: &Env,
+    response: Response,
+    loan_fee: Uint128,
+) -> Result<Response, V::Error> {
+    Ok(Response::new()
+        .add_attributes(response.attributes)
+        // Add response that:
+        // 1. Makes the funds liquid if needed
+        // 2. Sends funds to the borrower
+        // 3. Calls the borrow contract through the provided callback msg
+        .add_submessages(response.messages)
+        // After borrower actions, call the vault to cancel the borrow if no profit is made
+        .add_message(vault.after_trade_msg(env, loan_fee)?))
+}
+
+/// Callback functions can only be called by the vault itself
+pub fn assert_callback(env: &Env, info: &MessageInfo) -> Result<(), FlashLoanError> {
+    if info.sender != env.contract.address {
+        return Err(FlashLoanError::NotCallback {});
+    }
+    Ok(())
+}
+
+/// Deposits and withdrawals would tamper with the profit check of an ongoing flashloan
+pub fn assert_no_loan_ongoing<V: FlashLoanVault>(
+    vault: &V,
+    storage: &dyn Storage,
+) -> Result<(), V::Error> {
+    if vault.load_profit_check(storage)?.is_loan_ongoing() {
+        return Err(FlashLoanError::DepositDuringLoan {}.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+        MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{to_binary, Decimal, OwnedDeps};
+    use cw_storage_plus::Item;
+
+    use super::*;
+    use crate::vault::fees::{load_borrower_stats, BorrowerStats};
+
+    const PROFIT: Item<ProfitCheck> = Item::new("profit");
+    const VALUE: Item<Uint128> = Item::new("value");
+    const WHITELISTED: &str = "whitelisted";
+
+    #[derive(Debug, PartialEq)]
+    enum MockVaultError {
+        Std(StdError),
+        FlashLoan(FlashLoanError),
+    }
+
+    impl From<StdError> for MockVaultError {
+        fn from(err: StdError) -> Self {
+            Self::Std(err)
+        }
+    }
+
+    impl From<FlashLoanError> for MockVaultError {
+        fn from(err: FlashLoanError) -> Self {
+            Self::FlashLoan(err)
+        }
+    }
+
+    /// Vault lending a cw20 token, valued at whatever is stored under VALUE
+    struct MockVault {
+        allow_non_whitelisted: bool,
+    }
+
+    impl FlashLoanVault for MockVault {
+        type Error = MockVaultError;
+
+        fn config(&self, _deps: Deps) -> StdResult<FlashLoanConfig> {
+            Ok(FlashLoanConfig {
+                whitelisted_contracts: vec![Addr::unchecked(WHITELISTED)],
+                allow_non_whitelisted: self.allow_non_whitelisted,
+                flash_loan_fee: Fee {
+                    share: Decimal::percent(1),
+                },
+                commission_fee: Fee {
+                    share: Decimal::percent(10),
+                },
+                treasury_addr: Addr::unchecked("treasury"),
+            })
+        }
+
+        fn load_profit_check(&self, storage: &dyn Storage) -> StdResult<ProfitCheck> {
+            PROFIT.load(storage)
+        }
+
+        fn save_profit_check(
+            &self,
+            storage: &mut dyn Storage,
+            profit_check: &ProfitCheck,
+        ) -> StdResult<()> {
+            PROFIT.save(storage, profit_check)
+        }
+
+        fn base_asset_info(&self, _deps: Deps) -> Result<AssetInfo, Self::Error> {
+            Ok(AssetInfo::Token {
+                contract_addr: "base_token".to_string(),
+            })
+        }
+
+        fn assert_loan_asset(&self, deps: Deps, asset_info: &AssetInfo) -> Result<(), Self::Error> {
+            if *asset_info != self.base_asset_info(deps)? {
+                return Err(StdError::generic_err("Wrong asset").into());
+            }
+            Ok(())
+        }
+
+        fn total_value(&self, deps: Deps, _env: &Env) -> Result<Uint128, Self::Error> {
+            Ok(VALUE.load(deps.storage)?)
+        }
+
+        fn prepare_loan(
+            &self,
+            _deps: Deps,
+            _env: &Env,
+            amount: Uint128,
+            _tax_buffer: Uint128,
+        ) -> Result<Response, Self::Error> {
+            Ok(Response::new().add_attribute("prepared", amount.to_string()))
+        }
+
+        fn settle_trade(
+            &self,
+            _deps: DepsMut,
+            _env: &Env,
+            settlement: &TradeSettlement,
+        ) -> Result<Response, Self::Error> {
+            Ok(Response::new().add_attribute("settled", settlement.profit.to_string()))
+        }
+
+        fn after_trade_msg(&self, env: &Env, loan_fee: Uint128) -> StdResult<CosmosMsg> {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_binary(&loan_fee)?,
+                funds: vec![],
+            }))
+        }
+    }
+
+    fn mock_vault_deps(
+        total_value: u128,
+        last_balance: u128,
+    ) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies(&[]);
+        VALUE
+            .save(&mut deps.storage, &Uint128::new(total_value))
+            .unwrap();
+        PROFIT
+            .save(
+                &mut deps.storage,
+                &ProfitCheck {
+                    last_balance: Uint128::new(last_balance),
+                    last_profit: Uint128::zero(),
+                    last_value: Uint128::zero(),
+                    last_pnl: SignedInt::zero(),
+                    cumulative_pnl: SignedInt::zero(),
+                },
+            )
+            .unwrap();
+        deps
+    }
+
+    fn base_token(amount: u128) -> Asset {
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: "base_token".to_string(),
+            },
+            amount: Uint128::new(amount),
+        }
+    }
+
+    fn flashloan(
+        vault: &MockVault,
+        deps: DepsMut,
+        sender: &str,
+        asset: Asset,
+    ) -> Result<Response, MockVaultError> {
+        handle_flashloan(
+            vault,
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            asset,
+            Binary::default(),
+        )
+    }
+
+    #[test]
+    fn test_flashloan_whitelisted() {
+        let vault = MockVault {
+            allow_non_whitelisted: false,
+        };
+        let mut deps = mock_vault_deps(10_000, 0);
+
+        let res = flashloan(&vault, deps.as_mut(), WHITELISTED, base_token(1_000)).unwrap();
+        // loan, borrower callback and after trade callback
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[2].msg,
+            vault.after_trade_msg(&mock_env(), Uint128::zero()).unwrap()
+        );
+        assert!(res.attributes.contains(&attr("prepared", "1000")));
+        // the value before the trade is stored for the profit check
+        assert_eq!(
+            PROFIT.load(&deps.storage).unwrap().last_balance,
+            Uint128::new(10_000)
+        );
+    }
+
+    #[test]
+    fn test_flashloan_non_whitelisted() {
+        let mut deps = mock_vault_deps(10_000, 0);
+        let res = flashloan(
+            &MockVault {
+                allow_non_whitelisted: false,
+            },
+            deps.as_mut(),
+            "borrower",
+            base_token(1_000),
+        );
+        assert_eq!(
+            res,
+            Err(MockVaultError::FlashLoan(FlashLoanError::NotWhitelisted {}))
+        );
+
+        // non-whitelisted borrowers are charged the flashloan fee
+        let vault = MockVault {
+            allow_non_whitelisted: true,
+        };
+        let res = flashloan(&vault, deps.as_mut(), "borrower", base_token(1_000)).unwrap();
+        assert_eq!(
+            res.messages[2].msg,
+            vault
+                .after_trade_msg(&mock_env(), Uint128::new(10))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_flashloan_checks() {
+        let vault = MockVault {
+            allow_non_whitelisted: false,
+        };
+
+        // the rounding error compensation is kept in the vault
+        let mut deps = mock_vault_deps(1_005, 0);
+        let res = flashloan(&vault, deps.as_mut(), WHITELISTED, base_token(1_000));
+        assert_eq!(
+            res,
+            Err(MockVaultError::FlashLoan(FlashLoanError::Broke {}))
+        );
+
+        let mut deps = mock_vault_deps(10_000, 0);
+        let res = flashloan(
+            &vault,
+            deps.as_mut(),
+            WHITELISTED,
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uusd".to_string(),
+                },
+                amount: Uint128::new(1_000),
+            },
+        );
+        assert_eq!(
+            res,
+            Err(MockVaultError::Std(StdError::generic_err("Wrong asset")))
+        );
+    }
+
+    #[test]
+    fn test_flashloan_reentrancy() {
+        let vault = MockVault {
+            allow_non_whitelisted: false,
+        };
+        let mut deps = mock_vault_deps(10_000, 10_000);
+
+        let res = flashloan(&vault, deps.as_mut(), WHITELISTED, base_token(1_000));
+        assert_eq!(
+            res,
+            Err(MockVaultError::FlashLoan(FlashLoanError::Nonzero {}))
+        );
+        assert_eq!(
+            assert_no_loan_ongoing(&vault, &deps.storage),
+            Err(MockVaultError::FlashLoan(
+                FlashLoanError::DepositDuringLoan {}
+            ))
+        );
+    }
+
+    #[test]
+    fn test_after_trade_losing_trade() {
+        let vault = MockVault {
+            allow_non_whitelisted: true,
+        };
+        // the trade made less than the loan fee
+        let mut deps = mock_vault_deps(10_005, 10_000);
+
+        let res = after_trade(&vault, deps.as_mut(), mock_env(), Uint128::new(10));
+        assert_eq!(
+            res,
+            Err(MockVaultError::FlashLoan(
+                FlashLoanError::CancelLosingTrade {}
+            ))
+        );
+    }
+
+    #[test]
+    fn test_after_trade_profitable_trade() {
+        let vault = MockVault {
+            allow_non_whitelisted: true,
+        };
+        let mut deps = mock_vault_deps(11_000, 10_000);
+
+        let res = after_trade(&vault, deps.as_mut(), mock_env(), Uint128::new(10)).unwrap();
+        assert!(res.attributes.contains(&attr("settled", "1000")));
+        assert!(res.attributes.contains(&attr("commission_amount", "100")));
+        // commission sent to the treasury
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            PROFIT.load(&deps.storage).unwrap(),
+            ProfitCheck {
+                last_balance: Uint128::zero(),
+                last_profit: Uint128::new(1_000),
+                last_value: Uint128::zero(),
+                last_pnl: SignedInt::zero(),
+                cumulative_pnl: SignedInt::zero(),
+            }
+        );
+        assert_eq!(assert_no_loan_ongoing(&vault, &deps.storage), Ok(()));
+    }
+
+    #[test]
+    fn test_after_trade_profit_share() {
+        let vault = MockVault {
+            allow_non_whitelisted: true,
+        };
+        let borrower = Addr::unchecked("borrower");
+        let ongoing_loan = OngoingLoan {
+            borrower: borrower.clone(),
+            amount: Uint128::new(1_000),
+            fee: LoanFee::ProfitShare {
+                share: Fee {
+                    share: Decimal::percent(40),
+                },
+            },
+        };
+
+        // profit shares require a profit
+        let mut deps = mock_vault_deps(10_000, 10_000);
+        ONGOING_LOAN.save(&mut deps.storage, &ongoing_loan).unwrap();
+        let res = after_trade(&vault, deps.as_mut(), mock_env(), Uint128::zero());
+        assert_eq!(
+            res,
+            Err(MockVaultError::FlashLoan(
+                FlashLoanError::CancelLosingTrade {}
+            ))
+        );
+
+        let mut deps = mock_vault_deps(11_000, 10_000);
+        ONGOING_LOAN.save(&mut deps.storage, &ongoing_loan).unwrap();
+        let res = after_trade(&vault, deps.as_mut(), mock_env(), Uint128::zero()).unwrap();
+        // the vault keeps its share of the profit and sends the rest back
+        assert!(res.attributes.contains(&attr("settled", "400")));
+        assert!(res.attributes.contains(&attr("refund_amount", "600")));
+        assert!(res.attributes.contains(&attr("commission_amount", "40")));
+        // refund and commission
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(
+            load_borrower_stats(&deps.storage, &borrower).unwrap(),
+            BorrowerStats {
+                successful_loans: 1,
+                volume: Uint128::new(1_000),
+                fees_paid: Uint128::new(400),
+            }
+        );
+        assert!(ONGOING_LOAN.may_load(&deps.storage).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_record_pnl() {
+        let mut profit_check = ProfitCheck {
+            last_balance: Uint128::zero(),
+            last_profit: Uint128::zero(),
+            last_value: Uint128::zero(),
+            last_pnl: SignedInt::zero(),
+            cumulative_pnl: SignedInt::zero(),
+        };
+
+        // first deposit
+        assert_eq!(
+            profit_check.record_pnl(Uint128::zero(), Uint128::new(1_000)),
+            Ok(SignedInt::zero())
+        );
+        // 50 of yield, then a deposit of 500
+        assert_eq!(
+            profit_check.record_pnl(Uint128::new(1_050), Uint128::new(1_550)),
+            Ok(SignedInt::from(Uint128::new(50)))
+        );
+        // 150 of losses, then a withdrawal of 400
+        assert_eq!(
+            profit_check.record_pnl(Uint128::new(1_400), Uint128::new(1_000)),
+            Ok(SignedInt::new(Uint128::new(150), true))
+        );
+        assert_eq!(
+            PnlResponse::from(profit_check),
+            PnlResponse {
+                last_pnl: SignedInt::new(Uint128::new(150), true),
+                cumulative_pnl: SignedInt::new(Uint128::new(100), true),
+                last_value: Uint128::new(1_000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_assert_callback() {
+        let env = mock_env();
+        assert_eq!(
+            assert_callback(&env, &mock_info("anyone", &[])),
+            Err(FlashLoanError::NotCallback {})
+        );
+        assert_eq!(
+            assert_callback(&env, &mock_info(MOCK_CONTRACT_ADDR, &[])),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,4 @@
+pub mod fees;
+pub mod flashloan;
+pub mod pause;
+pub mod performance_fee;
@@ -0,0 +1,247 @@
+use std::fmt;
+
+use cosmwasm_std::{Addr, Deps, DepsMut, MessageInfo, Response, StdError, StdResult, Storage};
+use cw_controllers::{Admin, AdminError};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Vault operations the guardian can pause separately
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PausableOperation {
+    Deposits,
+    Withdrawals,
+    FlashLoans,
+}
+
+impl fmt::Display for PausableOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PausableOperation::Deposits => write!(f, "deposits"),
+            PausableOperation::Withdrawals => write!(f, "withdrawals"),
+            PausableOperation::FlashLoans => write!(f, "flash loans"),
+        }
+    }
+}
+
+/// Reason each operation is paused for, none if it isn't paused
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PauseState {
+    pub deposits: Option<String>,
+    pub withdrawals: Option<String>,
+    pub flash_loans: Option<String>,
+}
+
+impl PauseState {
+    pub fn reason(&self, operation: PausableOperation) -> Option<&String> {
+        match operation {
+            PausableOperation::Deposits => self.deposits.as_ref(),
+            PausableOperation::Withdrawals => self.withdrawals.as_ref(),
+            PausableOperation::FlashLoans => self.flash_loans.as_ref(),
+        }
+    }
+
+    fn set_reason(&mut self, operation: PausableOperation, reason: Option<String>) {
+        match operation {
+            PausableOperation::Deposits => self.deposits = reason,
+            PausableOperation::Withdrawals => self.withdrawals = reason,
+            PausableOperation::FlashLoans => self.flash_loans = reason,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PauseInfoResponse {
+    pub guardian: Option<String>,
+    pub paused: PauseState,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PauseError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Admin(#[from] AdminError),
+
+    #[error("Only the guardian or the admin can pause the vault.")]
+    Unauthorized {},
+
+    #[error("The vault {operation} are paused: {reason}")]
+    Paused {
+        operation: PausableOperation,
+        reason: String,
+    },
+}
+
+/// Address allowed to pause the vault, but not to unpause it
+pub const GUARDIAN: Item<Addr> = Item::new("guardian");
+pub const PAUSE_STATE: Item<PauseState> = Item::new("pause_state");
+
+/// Returns a Paused error if the operation is paused
+pub fn assert_not_paused(
+    storage: &dyn Storage,
+    operation: PausableOperation,
+) -> Result<(), PauseError> {
+    let pause_state = PAUSE_STATE.may_load(storage)?.unwrap_or_default();
+    match pause_state.reason(operation) {
+        Some(reason) => Err(PauseError::Paused {
+            operation,
+            reason: reason.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Pauses the operation, either by the guardian or the admin
+pub fn pause(
+    deps: DepsMut,
+    admin: &Admin,
+    msg_info: MessageInfo,
+    operation: PausableOperation,
+    reason: String,
+) -> Result<Response, PauseError> {
+    let is_guardian = GUARDIAN.may_load(deps.storage)?.as_ref() == Some(&msg_info.sender);
+    if !is_guardian && !admin.is_admin(deps.as_ref(), &msg_info.sender)? {
+        return Err(PauseError::Unauthorized {});
+    }
+
+    let mut pause_state = PAUSE_STATE.may_load(deps.storage)?.unwrap_or_default();
+    pause_state.set_reason(operation, Some(reason.clone()));
+    PAUSE_STATE.save(deps.storage, &pause_state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("operation", operation.to_string())
+        .add_attribute("reason", reason)
+        .add_attribute("paused_by", msg_info.sender))
+}
+
+/// Resumes the operation. Only the admin, i.e. governance, can unpause the vault
+pub fn unpause(
+    deps: DepsMut,
+    admin: &Admin,
+    msg_info: MessageInfo,
+    operation: PausableOperation,
+) -> Result<Response, PauseError> {
+    admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    let mut pause_state = PAUSE_STATE.may_load(deps.storage)?.unwrap_or_default();
+    pause_state.set_reason(operation, None);
+    PAUSE_STATE.save(deps.storage, &pause_state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "unpause")
+        .add_attribute("operation", operation.to_string()))
+}
+
+/// Sets the guardian, or removes it if none. Only the admin can call this
+pub fn set_guardian(
+    deps: DepsMut,
+    admin: &Admin,
+    msg_info: MessageInfo,
+    guardian: Option<String>,
+) -> Result<Response, PauseError> {
+    admin.assert_admin(deps.as_ref(), &msg_info.sender)?;
+
+    match &guardian {
+        Some(guardian) => GUARDIAN.save(deps.storage, &deps.api.addr_validate(guardian)?)?,
+        None => GUARDIAN.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_guardian")
+        .add_attribute("guardian", guardian.unwrap_or_default()))
+}
+
+pub fn query_pause_info(deps: Deps) -> StdResult<PauseInfoResponse> {
+    Ok(PauseInfoResponse {
+        guardian: GUARDIAN
+            .may_load(deps.storage)?
+            .map(|guardian| guardian.to_string()),
+        paused: PAUSE_STATE.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_info};
+
+    use super::*;
+
+    const ADMIN: Admin = Admin::new("admin");
+
+    #[test]
+    fn test_pause() {
+        let mut deps = mock_dependencies(&[]);
+        ADMIN
+            .set(deps.as_mut(), Some(Addr::unchecked("admin")))
+            .unwrap();
+        set_guardian(
+            deps.as_mut(),
+            &ADMIN,
+            mock_info("admin", &[]),
+            Some("guardian".to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            pause(
+                deps.as_mut(),
+                &ADMIN,
+                mock_info("anyone", &[]),
+                PausableOperation::Deposits,
+                "exploit".to_string(),
+            ),
+            Err(PauseError::Unauthorized {})
+        );
+        pause(
+            deps.as_mut(),
+            &ADMIN,
+            mock_info("guardian", &[]),
+            PausableOperation::Deposits,
+            "exploit".to_string(),
+        )
+        .unwrap();
+
+        // operations are paused separately
+        assert_eq!(
+            assert_not_paused(&deps.storage, PausableOperation::Deposits),
+            Err(PauseError::Paused {
+                operation: PausableOperation::Deposits,
+                reason: "exploit".to_string(),
+            })
+        );
+        assert_eq!(
+            assert_not_paused(&deps.storage, PausableOperation::Withdrawals),
+            Ok(())
+        );
+
+        // the guardian can't unpause
+        assert!(matches!(
+            unpause(
+                deps.as_mut(),
+                &ADMIN,
+                mock_info("guardian", &[]),
+                PausableOperation::Deposits,
+            ),
+            Err(PauseError::Admin(_))
+        ));
+        unpause(
+            deps.as_mut(),
+            &ADMIN,
+            mock_info("admin", &[]),
+            PausableOperation::Deposits,
+        )
+        .unwrap();
+        assert_eq!(
+            query_pause_info(deps.as_ref()).unwrap(),
+            PauseInfoResponse {
+                guardian: Some("guardian".to_string()),
+                paused: PauseState::default(),
+            }
+        );
+    }
+}
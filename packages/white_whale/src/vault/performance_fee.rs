@@ -0,0 +1,162 @@
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, StdResult, Storage, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::fee::Fee;
+
+/// Fee on the share price gains above the high-water mark, disabled if not set
+pub const PERFORMANCE_FEE: Item<Fee> = Item::new("performance_fee");
+/// Highest share price the performance fee was charged at
+pub const HIGH_WATER_MARK: Item<Decimal> = Item::new("high_water_mark");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PerformanceFeeResponse {
+    pub fee: Option<Fee>,
+    pub high_water_mark: Option<Decimal>,
+}
+
+/// Sets the performance fee, or disables it if none. Only the gains above the current share
+/// price are charged, and losses made before the fee was disabled still have to be made up
+pub fn set_performance_fee(
+    storage: &mut dyn Storage,
+    fee: Option<Fee>,
+    share_price: Decimal,
+) -> StdResult<()> {
+    match fee {
+        Some(fee) => {
+            PERFORMANCE_FEE.save(storage, &fee)?;
+            let high_water_mark = HIGH_WATER_MARK.may_load(storage)?.unwrap_or_default();
+            HIGH_WATER_MARK.save(storage, &std::cmp::max(high_water_mark, share_price))
+        }
+        None => {
+            PERFORMANCE_FEE.remove(storage);
+            Ok(())
+        }
+    }
+}
+
+/// LP tokens to mint to the treasury for the share price gain above the high-water mark. The
+/// high-water mark is moved up to the share price after the fee, nothing is charged until the
+/// share price is back above it after a loss
+pub fn charge_performance_fee(
+    storage: &mut dyn Storage,
+    total_value: Uint128,
+    total_share: Uint128,
+) -> StdResult<Uint128> {
+    let fee = match PERFORMANCE_FEE.may_load(storage)? {
+        Some(fee) => fee,
+        None => return Ok(Uint128::zero()),
+    };
+    if total_share.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let share_price = Decimal::from_ratio(total_value, total_share);
+    let high_water_mark = HIGH_WATER_MARK.may_load(storage)?.unwrap_or_default();
+    if share_price <= high_water_mark {
+        return Ok(Uint128::zero());
+    }
+
+    // Value of the gain above the high-water mark, over all the shares
+    let gain = total_value.checked_sub(total_share * high_water_mark)?;
+    let fee_value = fee.compute(gain);
+    // Minting m LP tokens worth the fee: m / (total_share + m) * total_value = fee_value
+    let fee_share = fee_value.multiply_ratio(total_share, total_value.checked_sub(fee_value)?);
+    HIGH_WATER_MARK.save(
+        storage,
+        &Decimal::from_ratio(total_value, total_share.checked_add(fee_share)?),
+    )?;
+    Ok(fee_share)
+}
+
+/// Mints the performance fee, in LP tokens, to the treasury
+pub fn performance_fee_msg(
+    liquidity_token: &Addr,
+    treasury_addr: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: liquidity_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Mint {
+            recipient: treasury_addr.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+pub fn query_performance_fee(storage: &dyn Storage) -> StdResult<PerformanceFeeResponse> {
+    Ok(PerformanceFeeResponse {
+        fee: PERFORMANCE_FEE.may_load(storage)?,
+        high_water_mark: HIGH_WATER_MARK.may_load(storage)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockStorage;
+
+    use super::*;
+
+    #[test]
+    fn test_charge_performance_fee() {
+        let mut storage = MockStorage::new();
+
+        // disabled by default
+        assert_eq!(
+            charge_performance_fee(&mut storage, Uint128::new(2000), Uint128::new(1000)),
+            Ok(Uint128::zero())
+        );
+
+        let fee = Fee {
+            share: Decimal::percent(20),
+        };
+        set_performance_fee(&mut storage, Some(fee), Decimal::one()).unwrap();
+
+        // 100 of gains, 20 of fee minted as LP tokens worth 20 after the mint
+        let fee_share =
+            charge_performance_fee(&mut storage, Uint128::new(1100), Uint128::new(1000)).unwrap();
+        assert_eq!(fee_share, Uint128::new(18));
+        assert_eq!(
+            HIGH_WATER_MARK.load(&storage).unwrap(),
+            Decimal::from_ratio(1100u128, 1018u128)
+        );
+
+        // the loss has to be made up before charging again
+        let total_share = Uint128::new(1018);
+        assert_eq!(
+            charge_performance_fee(&mut storage, Uint128::new(1000), total_share),
+            Ok(Uint128::zero())
+        );
+        assert_eq!(
+            charge_performance_fee(&mut storage, Uint128::new(1100), total_share),
+            Ok(Uint128::zero())
+        );
+        assert!(
+            !charge_performance_fee(&mut storage, Uint128::new(1200), total_share)
+                .unwrap()
+                .is_zero()
+        );
+    }
+
+    #[test]
+    fn test_set_performance_fee_keeps_high_water_mark() {
+        let mut storage = MockStorage::new();
+        let fee = Fee {
+            share: Decimal::percent(20),
+        };
+        set_performance_fee(&mut storage, Some(fee.clone()), Decimal::percent(120)).unwrap();
+        set_performance_fee(&mut storage, None, Decimal::percent(120)).unwrap();
+        set_performance_fee(&mut storage, Some(fee.clone()), Decimal::percent(90)).unwrap();
+
+        assert_eq!(
+            query_performance_fee(&storage).unwrap(),
+            PerformanceFeeResponse {
+                fee: Some(fee),
+                high_water_mark: Some(Decimal::percent(120)),
+            }
+        );
+    }
+}